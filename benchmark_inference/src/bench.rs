@@ -0,0 +1,136 @@
+use crate::Result;
+use crate::report::{DriverReport, LengthReport, RunStats};
+use sn_core::types::completion_prompt::CompletionPrompt;
+use sn_core::types::embedding_options::EmbeddingOptions;
+use sn_inference::runner::Runner;
+use std::time::Instant;
+
+static PROMPT: &str = include_str!("prompt.txt");
+
+static SENTENCES: [&str; 5] = [
+    "The cat sits on the mat.",
+    "Rust makes systems programming safe.",
+    "Machine learning is fun!",
+    "I love open source software.",
+    "Tomorrow will be a sunny day.",
+];
+
+static QUERIES: [&str; 2] = ["What is the capital of China?", "Explain gravity"];
+
+static DOCUMENTS: [&str; 2] = [
+    "The capital of China is Beijing.",
+    "Gravity is a force that attracts two bodies towards each other. It gives weight to physical objects and is responsible for the movement of planets around the sun.",
+];
+
+/// Prompts cut from the same text, so each length measures the same tokens.
+pub struct Bench<'a> {
+    runner: &'a Runner,
+    model_id: String,
+    prompt_ids: Vec<u32>,
+    max_tokens: usize,
+    ignore_eos: bool,
+}
+
+impl<'a> Bench<'a> {
+    pub fn new(
+        runner: &'a Runner,
+        model_id: String,
+        max_tokens: usize,
+        ignore_eos: bool,
+    ) -> Result<Bench<'a>> {
+        let prompt_ids = runner.tokenize(&model_id, PROMPT, false)?;
+        if prompt_ids.is_empty() {
+            return Err("the benchmark prompt is empty".into());
+        }
+        Ok(Bench {
+            runner,
+            model_id,
+            prompt_ids,
+            max_tokens,
+            ignore_eos,
+        })
+    }
+
+    /// Runs `warmup` times left out of the report, then `repetitions` times.
+    pub fn run_length(
+        &self,
+        prompt_length: usize,
+        warmup: usize,
+        repetitions: usize,
+    ) -> Result<LengthReport> {
+        let prompt = CompletionPrompt::Ids(repeat_to_length(&self.prompt_ids, prompt_length));
+        for _ in 0..warmup {
+            self.run(&prompt)?;
+        }
+        let runs = (0..repetitions)
+            .map(|_| self.run(&prompt))
+            .collect::<Result<Vec<RunStats>>>()?;
+        Ok(LengthReport::new(prompt_length, runs))
+    }
+
+    fn run(&self, prompt: &CompletionPrompt) -> Result<RunStats> {
+        // Without a session, every run starts from an empty cache.
+        let result = self.runner.generate_completion(
+            &self.model_id,
+            prompt,
+            false,
+            Some(self.max_tokens),
+            self.ignore_eos,
+            None,
+            None,
+            None,
+        )?;
+        let stats = result.stats.ok_or("the generation returned no stats")?;
+        Ok(RunStats::from(&stats))
+    }
+}
+
+/// Embeddings of a few sentences by the driver, timed like the prompt lengths.
+pub fn run_embedding(runner: &Runner, warmup: usize, repetitions: usize) -> Result<DriverReport> {
+    let sentences = SENTENCES.map(String::from).to_vec();
+    let options = EmbeddingOptions::default();
+    time_driver(sentences.len(), warmup, repetitions, || {
+        runner
+            .generate_embeddings(&sentences, &options)?
+            .try_as_slice::<f32>()?;
+        Ok(())
+    })
+}
+
+/// Similarity of queries to documents by the driver, both embedded in one batch.
+pub fn run_similarity(runner: &Runner, warmup: usize, repetitions: usize) -> Result<DriverReport> {
+    let queries = QUERIES.map(String::from).to_vec();
+    let documents = DOCUMENTS.map(String::from).to_vec();
+    let options = EmbeddingOptions::default();
+    time_driver(queries.len() + documents.len(), warmup, repetitions, || {
+        // Reading the scores evaluates them, MLX computes lazily.
+        runner
+            .generate_similarity(&queries, &documents, &options)?
+            .try_as_slice::<f32>()?;
+        Ok(())
+    })
+}
+
+fn time_driver(
+    inputs: usize,
+    warmup: usize,
+    repetitions: usize,
+    run: impl Fn() -> Result<()>,
+) -> Result<DriverReport> {
+    for _ in 0..warmup {
+        run()?;
+    }
+    let durations = (0..repetitions)
+        .map(|_| {
+            let start = Instant::now();
+            run()?;
+            Ok(start.elapsed().as_secs_f64())
+        })
+        .collect::<Result<Vec<f64>>>()?;
+    Ok(DriverReport::new(inputs, durations))
+}
+
+/// The first `length` ids, starting over from the beginning when there are fewer.
+fn repeat_to_length(ids: &[u32], length: usize) -> Vec<u32> {
+    ids.iter().copied().cycle().take(length).collect()
+}
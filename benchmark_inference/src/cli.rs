@@ -0,0 +1,44 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Measure prefill and decode throughput of an installed model over prompt lengths.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// Alias or id of an installed model.
+    #[arg(short, long)]
+    pub model: String,
+    /// Prompt lengths in tokens, cut from the bundled prompt.
+    #[arg(long, value_delimiter = ',', default_value = "128,512,2048")]
+    pub prompt_lengths: Vec<usize>,
+    /// Tokens generated by each run, fewer when the model ends it first.
+    #[arg(long, default_value_t = 128)]
+    pub max_tokens: usize,
+    /// Keep generating past end of sequence tokens, so every run decodes `max_tokens`.
+    #[arg(long)]
+    pub ignore_eos: bool,
+    /// Runs of each prompt length left out of the report.
+    #[arg(long, default_value_t = 1)]
+    pub warmup: usize,
+    #[arg(short, long, default_value_t = 3)]
+    pub repetitions: usize,
+    /// Also time the embedding driver on a batch of sentences.
+    #[arg(long)]
+    pub embedding: bool,
+    /// Also time the similarity of queries to documents with the embedding driver.
+    #[arg(long)]
+    pub similarity: bool,
+    /// Quantize a full precision model in memory to this number of bits.
+    #[arg(long)]
+    pub bits: Option<i32>,
+    #[arg(long, default_value_t = 64)]
+    pub group_size: i32,
+    #[arg(short, long, default_value = "benchmark_report.json")]
+    pub output: PathBuf,
+    /// Report of an earlier run, the benchmark fails when a metric regressed from it.
+    #[arg(short, long)]
+    pub baseline: Option<PathBuf>,
+    /// Relative change from the baseline tolerated before it counts as a regression.
+    #[arg(long, default_value_t = 0.05)]
+    pub tolerance: f64,
+}
@@ -2,65 +2,116 @@
 #[global_allocator]
 static ALLOC: dhat::Alloc = dhat::Alloc;
 
-use sn_core::types::conversation::Conversation;
-use sn_core::types::message::{MessageBuilder, MessageRole};
+use crate::bench::{Bench, run_embedding, run_similarity};
+use crate::cli::Cli;
+use crate::report::{BenchConfig, Report, check_comparable, compare};
+use clap::Parser;
+use sn_core::types::model_quantization::ModelQuantization;
 use sn_inference::runner::Runner;
+mod bench;
+mod cli;
+mod report;
 
-fn similarity(runner: &Runner) -> Result<(), Box<dyn std::error::Error>> {
-    let queries = vec![
-        String::from("What is the capital of China?"),
-        String::from("Explain gravity"),
-    ];
-    let documents = vec![
-        String::from("The capital of China is Beijing."),
-        String::from(
-            "Gravity is a force that attracts two bodies towards each other. It gives weight to physical objects and is responsible for the movement of planets around the sun.",
-        ),
-    ];
-
-    let result = runner.generate_similarity(&queries, &documents)?;
-    println!("Similarity Results: {:?}", result);
-    Ok(())
-}
-fn embedding(runner: &Runner) -> Result<(), Box<dyn std::error::Error>> {
-    let sentences = vec![
-        "The cat sits on the mat.".to_string(),
-        "Rust makes systems programming safe.".to_string(),
-        "Machine learning is fun!".to_string(),
-        "I love open source software.".to_string(),
-        "Tomorrow will be a sunny day.".to_string(),
-    ];
-    let embedding = runner.generate_embeddings(&sentences)?;
-    println!("Embedding Results: {:?}", embedding);
-    Ok(())
-}
-fn chat(runner: &Runner) -> Result<(), Box<dyn std::error::Error>> {
-    let conversation = Conversation::from_message(
-        MessageBuilder::default()
-            .content("Hello, who are you?".to_string())
-            .role(MessageRole::User)
-            .build()
-            .unwrap(),
-    );
-    //let model_id = runner.load_model_name("models--Qwen--Qwen3-1.7B-MLX-4bit", None)?;
-    let model_id = runner.load_model_name("models-llama-3.1-8B-Instruct-4bit", None)?;
-    let text = runner.generate_text(&model_id, &conversation, None, None)?;
-    println!("Chat Response: {}", text.0);
-    Ok(())
-}
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
-    let runner = Runner::new().unwrap();
 
-    match (|| {
-        // embedding(&runner)?;
-        similarity(&runner)?;
-        //chat(&runner)?;
-        Ok::<(), Box<dyn std::error::Error>>(())
-    })() {
-        Err(e) => println!("{e}"),
-        Ok(_) => (),
+    match try_main() {
+        Ok(true) => (),
+        Ok(false) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("❌ Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Whether no metric regressed from the baseline.
+fn try_main() -> Result<bool> {
+    let cli = Cli::parse();
+    let quantization = cli.bits.map(|bits| ModelQuantization {
+        bits,
+        group_size: cli.group_size,
+    });
+    // Read first, a missing baseline should not wait for the whole benchmark.
+    let baseline = cli.baseline.as_deref().map(Report::read).transpose()?;
+
+    let runner = Runner::new()?;
+    let model_id = runner.load_model_name(&cli.model, quantization, None)?;
+    let model = runner
+        .list_models()?
+        .into_iter()
+        .find(|model| model.id == model_id)
+        .ok_or_else(|| format!("model {} is not installed", cli.model))?;
+
+    let config = BenchConfig {
+        prompt_lengths: cli.prompt_lengths.clone(),
+        max_tokens: cli.max_tokens,
+        ignore_eos: cli.ignore_eos,
+        warmup: cli.warmup,
+        repetitions: cli.repetitions,
+        quantized_on_load: quantization.filter(|_| model.quantization.is_none()),
+    };
+    if let Some(baseline) = &baseline {
+        check_comparable(baseline, &model, &config)?;
+    }
+
+    let bench = Bench::new(&runner, model_id, cli.max_tokens, cli.ignore_eos)?;
+    let mut lengths = Vec::with_capacity(cli.prompt_lengths.len());
+    for &prompt_length in &cli.prompt_lengths {
+        let length = bench.run_length(prompt_length, cli.warmup, cli.repetitions)?;
+        let summary = &length.summary;
+        println!(
+            "{} tokens: prefill {:.1} tok/s, decode {:.1} tok/s, TTFT {:.3}s, peak memory {:.2} GB, cache {:.2} MB",
+            prompt_length,
+            summary.prefill_tps,
+            summary.decode_tps,
+            summary.ttft_secs,
+            summary.peak_memory as f64 / 1e9,
+            summary.cache_bytes as f64 / 1e6
+        );
+        if !cli.ignore_eos && summary.generated_tokens < cli.max_tokens {
+            eprintln!(
+                "⚠️  {} tokens: generation ended after {} of {} tokens, use --ignore-eos for a fixed length",
+                prompt_length, summary.generated_tokens, cli.max_tokens
+            );
+        }
+        lengths.push(length);
+    }
+
+    let mut report = Report::new(model, config, lengths);
+    if cli.embedding {
+        let embedding = run_embedding(&runner, cli.warmup, cli.repetitions)?;
+        println!(
+            "Embedding: {} inputs in {:.3}s, {:.1} inputs/s",
+            embedding.inputs, embedding.duration_secs, embedding.inputs_per_sec
+        );
+        report.embedding = Some(embedding);
+    }
+    if cli.similarity {
+        let similarity = run_similarity(&runner, cli.warmup, cli.repetitions)?;
+        println!(
+            "Similarity: {} inputs in {:.3}s, {:.1} inputs/s",
+            similarity.inputs, similarity.duration_secs, similarity.inputs_per_sec
+        );
+        report.similarity = Some(similarity);
+    }
+    report.write(&cli.output)?;
+    println!("Report written to {}", cli.output.display());
+
+    let Some(baseline) = baseline else {
+        return Ok(true);
+    };
+    let regressions = compare(&baseline, &report, cli.tolerance)?;
+    if regressions.is_empty() {
+        println!("No regression from the baseline");
+        return Ok(true);
+    }
+    eprintln!("Regressions from the baseline:");
+    for regression in &regressions {
+        eprintln!("  {}", regression);
     }
+    Ok(false)
 }
@@ -0,0 +1,371 @@
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use sn_core::types::message_stats::MessageStats;
+use sn_core::types::model_info::ModelInfo;
+use sn_core::types::model_quantization::ModelQuantization;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunStats {
+    pub prompt_tokens: usize,
+    pub generated_tokens: usize,
+    pub prefill_tps: f64,
+    pub decode_tps: f64,
+    /// Time to the first token, in seconds.
+    pub ttft_secs: f64,
+    /// Peak memory used by MLX, in bytes.
+    pub peak_memory: u64,
+    /// Size of the KV cache at the end of the run, in bytes.
+    pub cache_bytes: u64,
+}
+
+impl From<&MessageStats> for RunStats {
+    fn from(stats: &MessageStats) -> RunStats {
+        RunStats {
+            prompt_tokens: stats.prompt_tokens,
+            generated_tokens: stats.generated_tokens,
+            prefill_tps: stats.prompt_tps,
+            decode_tps: stats.generation_tps,
+            ttft_secs: stats.prefill_duration,
+            peak_memory: stats.peak_memory,
+            cache_bytes: stats.cache_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LengthReport {
+    pub prompt_length: usize,
+    /// Median of the runs for rates and timings, their maximum for memory.
+    pub summary: RunStats,
+    pub runs: Vec<RunStats>,
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2.0,
+        len => values[len / 2],
+    }
+}
+
+impl LengthReport {
+    pub fn new(prompt_length: usize, runs: Vec<RunStats>) -> LengthReport {
+        let median_of = |metric: fn(&RunStats) -> f64| median(runs.iter().map(metric).collect());
+        let max_of = |metric: fn(&RunStats) -> u64| runs.iter().map(metric).max().unwrap_or(0);
+        let summary = RunStats {
+            prompt_tokens: runs.first().map_or(prompt_length, |run| run.prompt_tokens),
+            generated_tokens: median_of(|run| run.generated_tokens as f64) as usize,
+            prefill_tps: median_of(|run| run.prefill_tps),
+            decode_tps: median_of(|run| run.decode_tps),
+            ttft_secs: median_of(|run| run.ttft_secs),
+            peak_memory: max_of(|run| run.peak_memory),
+            cache_bytes: max_of(|run| run.cache_bytes),
+        };
+        LengthReport {
+            prompt_length,
+            summary,
+            runs,
+        }
+    }
+}
+
+/// Timing of the embedding driver over a batch of inputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriverReport {
+    pub inputs: usize,
+    /// Median of the runs, in seconds.
+    pub duration_secs: f64,
+    pub inputs_per_sec: f64,
+    pub runs: Vec<f64>,
+}
+
+impl DriverReport {
+    pub fn new(inputs: usize, runs: Vec<f64>) -> DriverReport {
+        let duration_secs = median(runs.clone());
+        let inputs_per_sec = match duration_secs {
+            0.0 => 0.0,
+            duration => inputs as f64 / duration,
+        };
+        DriverReport {
+            inputs,
+            duration_secs,
+            inputs_per_sec,
+            runs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchConfig {
+    pub prompt_lengths: Vec<usize>,
+    pub max_tokens: usize,
+    /// End of sequence tokens did not stop the runs, each one decoded `max_tokens`.
+    #[serde(default)]
+    pub ignore_eos: bool,
+    pub warmup: usize,
+    pub repetitions: usize,
+    /// Quantization applied on load to a full precision model.
+    pub quantized_on_load: Option<ModelQuantization>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+    pub os: String,
+    pub arch: String,
+    pub model: ModelInfo,
+    pub config: BenchConfig,
+    pub lengths: Vec<LengthReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<DriverReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<DriverReport>,
+}
+
+impl Report {
+    pub fn new(model: ModelInfo, config: BenchConfig, lengths: Vec<LengthReport>) -> Report {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Report {
+            created_at,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            model,
+            config,
+            lengths,
+            embedding: None,
+            similarity: None,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Report> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Name of a metric, whether a higher value is better and how to read it.
+type Metric = (&'static str, bool, fn(&RunStats) -> f64);
+
+/// Metrics compared against a baseline.
+static METRICS: [Metric; 4] = [
+    ("prefill_tps", true, |stats| stats.prefill_tps),
+    ("decode_tps", true, |stats| stats.decode_tps),
+    ("ttft_secs", false, |stats| stats.ttft_secs),
+    ("peak_memory", false, |stats| stats.peak_memory as f64),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub prompt_length: usize,
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = match self.baseline {
+            0.0 => 0.0,
+            baseline => (self.current - baseline) / baseline * 100.0,
+        };
+        write!(
+            f,
+            "{} at {} tokens: {:.2} -> {:.2} ({:+.1}%)",
+            self.metric, self.prompt_length, self.baseline, self.current, change
+        )
+    }
+}
+
+/// Fails when a run of `model` with `config` does not measure the same thing as
+/// `baseline`: another model, quantization, number of generated tokens or EOS handling.
+pub fn check_comparable(baseline: &Report, model: &ModelInfo, config: &BenchConfig) -> Result<()> {
+    let differences = [
+        (
+            "model",
+            baseline.model.alias != model.alias
+                || baseline.model.quantization != model.quantization,
+        ),
+        (
+            "max_tokens",
+            baseline.config.max_tokens != config.max_tokens,
+        ),
+        (
+            "ignore_eos",
+            baseline.config.ignore_eos != config.ignore_eos,
+        ),
+        (
+            "quantized_on_load",
+            baseline.config.quantized_on_load != config.quantized_on_load,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, differs)| *differs)
+    .map(|(name, _)| name)
+    .collect::<Vec<&str>>();
+    if !differences.is_empty() {
+        return Err(format!(
+            "the baseline was run with another {}",
+            differences.join(", ")
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Metrics of `current` worse than `baseline` by more than `tolerance`, a fraction of
+/// the baseline value. Prompt lengths missing from either report are not compared.
+pub fn compare(baseline: &Report, current: &Report, tolerance: f64) -> Result<Vec<Regression>> {
+    check_comparable(baseline, &current.model, &current.config)?;
+    let mut regressions = Vec::new();
+    for length in &current.lengths {
+        let Some(base) = baseline
+            .lengths
+            .iter()
+            .find(|base| base.prompt_length == length.prompt_length)
+        else {
+            continue;
+        };
+        for (metric, higher_is_better, value) in &METRICS {
+            let (base_value, current_value) = (value(&base.summary), value(&length.summary));
+            let regressed = if *higher_is_better {
+                current_value < base_value * (1.0 - tolerance)
+            } else {
+                current_value > base_value * (1.0 + tolerance)
+            };
+            if regressed {
+                regressions.push(Regression {
+                    prompt_length: length.prompt_length,
+                    metric,
+                    baseline: base_value,
+                    current: current_value,
+                });
+            }
+        }
+    }
+    Ok(regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(prefill_tps: f64, decode_tps: f64, ttft_secs: f64, peak_memory: u64) -> RunStats {
+        RunStats {
+            prompt_tokens: 512,
+            generated_tokens: 128,
+            prefill_tps,
+            decode_tps,
+            ttft_secs,
+            peak_memory,
+            cache_bytes: 1 << 20,
+        }
+    }
+
+    fn report(lengths: Vec<LengthReport>) -> Report {
+        let model = ModelInfo {
+            id: "1".to_string(),
+            alias: "Qwen3-1.7B-4bit".to_string(),
+            path: "Qwen3-1.7B-4bit".to_string(),
+            architecture: "qwen3".to_string(),
+            parameters: 1_700_000_000,
+            quantization: None,
+            context_length: Some(40960),
+            has_chat_template: true,
+        };
+        let config = BenchConfig {
+            prompt_lengths: lengths.iter().map(|length| length.prompt_length).collect(),
+            max_tokens: 128,
+            ignore_eos: true,
+            warmup: 1,
+            repetitions: 3,
+            quantized_on_load: None,
+        };
+        Report::new(model, config, lengths)
+    }
+
+    #[test]
+    fn test_compare_against_baseline() {
+        let summary = LengthReport::new(
+            512,
+            vec![
+                run(1000.0, 50.0, 0.5, 300),
+                run(900.0, 40.0, 0.6, 200),
+                run(1100.0, 45.0, 0.4, 100),
+            ],
+        )
+        .summary;
+        assert_eq!(summary, run(1000.0, 45.0, 0.5, 300));
+
+        let baseline = report(vec![
+            LengthReport::new(512, vec![run(1000.0, 50.0, 0.5, 1000)]),
+            LengthReport::new(2048, vec![run(800.0, 40.0, 2.5, 2000)]),
+        ]);
+        let current = report(vec![
+            LengthReport::new(128, vec![run(10.0, 1.0, 9.0, 9000)]),
+            LengthReport::new(512, vec![run(970.0, 40.0, 0.5, 1200)]),
+        ]);
+        assert_eq!(
+            compare(&baseline, &current, 0.05).unwrap(),
+            vec![
+                Regression {
+                    prompt_length: 512,
+                    metric: "decode_tps",
+                    baseline: 50.0,
+                    current: 40.0,
+                },
+                Regression {
+                    prompt_length: 512,
+                    metric: "peak_memory",
+                    baseline: 1000.0,
+                    current: 1200.0,
+                },
+            ]
+        );
+        assert!(compare(&baseline, &baseline, 0.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_refuses_other_settings() {
+        let baseline = report(vec![LengthReport::new(
+            512,
+            vec![run(1000.0, 50.0, 0.5, 1000)],
+        )]);
+        let mut current = baseline.clone();
+        current.config.max_tokens = 256;
+        current.config.ignore_eos = false;
+        current.config.quantized_on_load = Some(ModelQuantization {
+            bits: 4,
+            group_size: 64,
+        });
+        let err = compare(&baseline, &current, 0.05).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "the baseline was run with another max_tokens, ignore_eos, quantized_on_load"
+        );
+
+        let mut current = baseline.clone();
+        current.model.alias = "Qwen3-8B-4bit".to_string();
+        assert!(compare(&baseline, &current, 0.05).is_err());
+    }
+
+    #[test]
+    fn test_driver_report_median() {
+        let report = DriverReport::new(4, vec![0.4, 0.1, 0.2]);
+        assert_eq!(report.duration_secs, 0.2);
+        assert_eq!(report.inputs_per_sec, 20.0);
+        assert_eq!(DriverReport::new(4, Vec::new()).inputs_per_sec, 0.0);
+    }
+}
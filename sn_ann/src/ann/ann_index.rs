@@ -97,6 +97,15 @@ impl AnnIndex {
         results
     }
 
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Empties the index and switches it to vectors of `dim`.
+    pub fn reset(&mut self, dim: usize) {
+        *self = AnnIndex::new(dim);
+    }
+
     pub fn status(&self, partition: i32) -> i32 {
         match self.last_checkpoint_info.get(&partition) {
             Some(status) => *status,
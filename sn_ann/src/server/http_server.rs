@@ -1,7 +1,7 @@
 use crate::{
     ann::AnnIndex,
     error::{ErrorAnn, Result},
-    server::{app_state::AppState, embedding, partition, ping},
+    server::{app_state::AppState, embedding, index, partition, ping},
 };
 use http::StatusCode;
 use sn_core::server::defauft_config::{
@@ -28,6 +28,7 @@ pub async fn http_server_ann(ann_idx: AnnIndex) -> Result<()> {
     let app_state = Arc::new(AppState::new(ann_idx));
     let routes_api = axum::Router::new()
         .merge(embedding::route::routes())
+        .merge(index::route::routes())
         .merge(partition::route::routes())
         .merge(ping::route::routes())
         .with_state(app_state.clone());
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    Json,
+};
+use serde_json::json;
+use sn_core::{
+    server::payload::ann::{
+        index_status_response::IndexStatusResponse, reset_index_request::ResetIndexRequest,
+    },
+    utils::rw_lock::RwLockExt,
+};
+use tracing::info;
+
+use crate::{error::ResultAPI, server::app_state::AppState};
+
+pub async fn get_index_status(State(state): State<Arc<AppState>>) -> ResultAPI {
+    let dim = state.ann_idx.read_lock("get_index_status")?.dim();
+    Ok(Json(json!(IndexStatusResponse { dim })))
+}
+
+pub async fn reset_index(
+    State(state): State<Arc<AppState>>,
+    payload: std::result::Result<Json<ResetIndexRequest>, JsonRejection>,
+) -> ResultAPI {
+    let dim = payload?.0.dim;
+    info!("Resetting ANN index with dimension {}", dim);
+    state
+        .ann_idx
+        .write_lock_mut("reset_index_request")?
+        .reset(dim);
+    Ok(Json(json!({ "status": "ok", "dim": dim })))
+}
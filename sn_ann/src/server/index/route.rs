@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+
+use crate::server::{
+    app_state::AppState,
+    index::controller::{get_index_status, reset_index},
+};
+
+pub fn routes() -> axum::Router<Arc<AppState>> {
+    axum::Router::new()
+        .route("/index/status", get(get_index_status))
+        .route("/index/reset", post(reset_index))
+}
@@ -1,5 +1,6 @@
 pub(crate) mod app_state;
 pub(crate) mod embedding;
 pub(crate) mod http_server;
+pub(crate) mod index;
 pub(crate) mod partition;
 pub(crate) mod ping;
@@ -0,0 +1,130 @@
+use std::sync::{Arc, RwLock};
+
+use crate::error::{ErrorBackend, Result};
+use crate::utils::stream_channel::StreamChannel;
+use crossbeam::channel::{Receiver, Sender};
+use sn_core::server::payload::backend::completion_request::CompletionRequest;
+use sn_core::server::payload::backend::completion_response::CompletionResponse;
+use sn_core::server::payload::backend::fim_request::FimRequest;
+use sn_core::server::payload::backend::score_request::ScoreRequest;
+use sn_core::server::payload::backend::score_response::ScoreResponse;
+use sn_core::types::stream_data::StreamData;
+use sn_core::utils::rw_lock::RwLockExt;
+use sn_inference::error::Result as InferenceResult;
+use sn_inference::model::model_runtime::GenerateTextResult;
+use sn_inference::runner::Runner;
+use tracing::error;
+
+pub enum CompletionOutput {
+    Json(CompletionResponse),
+    Streaming(Receiver<StreamData>),
+}
+
+/// Raw completions, generated from the prompt as sent without chat template nor
+/// conversation.
+#[derive(Clone, Debug)]
+pub struct CompletionService {
+    runner: Arc<RwLock<Runner>>,
+}
+
+impl CompletionService {
+    pub fn new(runner: Arc<RwLock<Runner>>) -> CompletionService {
+        CompletionService { runner }
+    }
+
+    pub async fn complete(&self, req: CompletionRequest) -> Result<CompletionOutput> {
+        if req.prompt.is_empty() {
+            return Err(ErrorBackend::RequiredInput("prompt".to_string()));
+        }
+        let model_id = req.model_id.clone();
+        let max_tokens = req.max_tokens();
+        self.generate(model_id, &req.stream, move |runner, tx| {
+            runner.generate_completion(
+                &req.model_id,
+                &req.prompt,
+                req.add_special_tokens.unwrap_or(true),
+                Some(max_tokens),
+                false,
+                req.session_id,
+                req.adapter.as_deref(),
+                tx,
+            )
+        })
+        .await
+    }
+
+    pub async fn fim(&self, req: FimRequest) -> Result<CompletionOutput> {
+        if req.prompt.prefix.is_empty() && req.prompt.suffix.is_empty() {
+            return Err(ErrorBackend::RequiredInput("prefix".to_string()));
+        }
+        let model_id = req.model_id.clone();
+        self.generate(model_id, &req.stream, move |runner, tx| {
+            runner.generate_fim(
+                &req.model_id,
+                &req.prompt,
+                req.session_id,
+                req.adapter.as_deref(),
+                tx,
+            )
+        })
+        .await
+    }
+
+    pub async fn score(&self, req: ScoreRequest) -> Result<ScoreResponse> {
+        if req.continuations.is_empty() {
+            return Err(ErrorBackend::RequiredInput("continuations".to_string()));
+        }
+        let runner = self.runner.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("reading runner for score")?;
+            let scores = guard.score(
+                &req.model_id,
+                &req.context,
+                &req.continuations,
+                req.adapter.as_deref(),
+            )?;
+            Ok::<_, ErrorBackend>(ScoreResponse {
+                model_id: req.model_id,
+                scores,
+            })
+        });
+        task.await?
+    }
+
+    async fn generate<F>(
+        &self,
+        model_id: Arc<str>,
+        stream: &Option<bool>,
+        generate: F,
+    ) -> Result<CompletionOutput>
+    where
+        F: FnOnce(&Runner, Option<Arc<Sender<StreamData>>>) -> InferenceResult<GenerateTextResult>
+            + Send
+            + 'static,
+    {
+        let stream = StreamChannel::new(stream);
+        let tx = stream.as_ref().map(|stream| stream.tx.clone());
+        let runner = self.runner.clone();
+
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("reading runner for generate_completion")?;
+            let result = generate(&guard, tx.clone());
+            if let (Err(e), Some(tx)) = (&result, tx) {
+                error!("{}", e);
+                let error = format!("Failed to generate completion: {}", e);
+                let _ = tx.send(StreamData::for_stream_error(error));
+            }
+            let result = result?;
+            Ok::<_, ErrorBackend>(CompletionResponse {
+                model_id,
+                text: result.content,
+                stats: result.stats,
+            })
+        });
+
+        match stream {
+            Some(stream) => Ok(CompletionOutput::Streaming(stream.rx)),
+            None => Ok(CompletionOutput::Json(task.await??)),
+        }
+    }
+}
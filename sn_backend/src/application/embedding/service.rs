@@ -1,15 +1,24 @@
 use std::sync::{Arc, RwLock};
 
 use crate::clients::ann::AnnClient;
+use crate::domain::embedding::entity::Convert;
 use crate::domain::embedding::repository::EmbeddingRepository;
+use crate::domain::message::repository::MessageRepository;
 use crate::error::{ErrorBackend, Result};
 use crate::use_cases::message::generate_embedding_use_case::GenerateEmbeddingUseCase;
+use sn_core::server::payload::backend::embeddings_request::EmbeddingsRequest;
+use sn_core::server::payload::backend::embeddings_response::{EmbeddingData, EmbeddingsResponse};
+use sn_core::server::payload::backend::rerank_request::RerankRequest;
+use sn_core::server::payload::backend::rerank_response::RerankResponse;
 use sn_core::types::message::Message;
+use sn_core::utils::rw_lock::RwLockExt;
 use sn_inference::runner::Runner;
+use tracing::{info, warn};
 
 #[derive(Clone, Debug)]
 pub struct EmbeddingService {
     repo_embedding: Arc<EmbeddingRepository>,
+    repo_message: Arc<MessageRepository>,
     runner: Arc<RwLock<Runner>>,
     ann_client: Arc<AnnClient>,
 }
@@ -17,6 +26,7 @@ pub struct EmbeddingService {
 impl EmbeddingService {
     pub fn new(
         repo_embedding: Arc<EmbeddingRepository>,
+        repo_message: Arc<MessageRepository>,
         runner: Arc<RwLock<Runner>>,
         ann_client: Arc<AnnClient>,
     ) -> Self {
@@ -24,6 +34,7 @@ impl EmbeddingService {
             runner,
             ann_client,
             repo_embedding,
+            repo_message,
         }
     }
 
@@ -33,12 +44,138 @@ impl EmbeddingService {
             .ok_or_else(|| ErrorBackend::ConversationNotFound)?;
         let use_case = GenerateEmbeddingUseCase::new(self.runner.clone());
         let embeddings = use_case.generate_message_embeddings(&message).await?;
-        let _ = self
-            .repo_embedding
-            .create(&conversation_id, &message.id, &embeddings)
-            .await?;
+        // A continued message keeps its row, its embedding is replaced.
+        match self.repo_embedding.find_by_message_id(&message.id).await? {
+            Some(embedding) => {
+                self.repo_embedding
+                    .update_data(embedding, &embeddings)
+                    .await?;
+            }
+            None => {
+                self.repo_embedding
+                    .create(&conversation_id, &message.id, &embeddings)
+                    .await?;
+            }
+        }
         Ok(())
     }
+    pub async fn embeddings(&self, req: EmbeddingsRequest) -> Result<EmbeddingsResponse> {
+        if req.input.is_empty() {
+            return Err(ErrorBackend::RequiredInput("input".to_string()));
+        }
+        let runner = self.runner.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("generating embeddings")?;
+            let model = driver_name(&guard)?;
+            let embeddings = guard.generate_embeddings(&req.input, &req.options)?;
+            let dimensions = embeddings.shape().last().copied().unwrap_or_default() as usize;
+            let data = embeddings
+                .try_as_slice::<f32>()
+                .map_err(|e| ErrorBackend::FailedToRunModel(e.to_string()))?
+                .chunks(dimensions.max(1))
+                .enumerate()
+                .map(|(index, embedding)| EmbeddingData {
+                    index,
+                    embedding: embedding.to_vec(),
+                })
+                .collect();
+            Ok::<_, ErrorBackend>(EmbeddingsResponse {
+                model,
+                dimensions,
+                data,
+            })
+        });
+        task.await?
+    }
+
+    pub async fn rerank(&self, req: RerankRequest) -> Result<RerankResponse> {
+        if req.documents.is_empty() {
+            return Err(ErrorBackend::RequiredInput("documents".to_string()));
+        }
+        let runner = self.runner.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("reranking documents")?;
+            let model = driver_name(&guard)?;
+            let scores =
+                guard.generate_similarity(&vec![req.query], &req.documents, &req.options)?;
+            let scores = scores
+                .try_as_slice::<f32>()
+                .map_err(|e| ErrorBackend::FailedToRunModel(e.to_string()))?;
+            Ok::<_, ErrorBackend>(RerankResponse::rank(
+                model,
+                req.documents,
+                scores,
+                req.top_n,
+            ))
+        });
+        task.await?
+    }
+
+    /// Generate again the embeddings stored with another dimension than the current
+    /// driver, then rebuild the ANN store with them, also when the store itself has
+    /// another dimension, e.g. after it restarted. Returns the number of embeddings
+    /// generated again.
+    pub async fn reindex_embeddings(&self) -> Result<usize> {
+        let dim = {
+            let context = "reading driver of the runner";
+            match self.runner.read_lock(context)?.get_driver() {
+                Some(driver) => driver.embedding_dim,
+                None => return Ok(0),
+            }
+        };
+        let stale = self.repo_embedding.find_all_with_other_dim(dim).await?;
+        if !stale.is_empty() {
+            info!(
+                "Re-indexing {} embedding(s) with dimension {}",
+                stale.len(),
+                dim
+            );
+        }
+        let use_case = GenerateEmbeddingUseCase::new(self.runner.clone());
+        let mut count = 0;
+        for embedding in stale {
+            let Some(message) = self.repo_message.find_by_id(&embedding.message_id).await? else {
+                continue;
+            };
+            let embeddings = use_case
+                .generate_content_embeddings(&message.content)
+                .await?;
+            self.repo_embedding
+                .update_data(embedding, &embeddings)
+                .await?;
+            count += 1;
+        }
+
+        let ann_dim = match self.ann_client.get_index_status().await {
+            Ok(status) => status.dim,
+            Err(e) => {
+                warn!("Failed to read the ANN store status: {}", e);
+                return Ok(count);
+            }
+        };
+        if count == 0 && ann_dim == dim {
+            return Ok(0);
+        }
+
+        // The ANN store only holds vectors of one dimension, it is rebuilt from the rows
+        // of that dimension, the ones that could not be generated again are left out.
+        info!(
+            "Rebuilding the ANN store with dimension {} (was {})",
+            dim, ann_dim
+        );
+        let items = self
+            .repo_embedding
+            .find_all_with_dim(dim)
+            .await?
+            .into_vec_ann()?;
+        if let Err(e) = self.ann_client.reset_index(dim).await {
+            warn!("Failed to reset the ANN store: {}", e);
+            return Ok(count);
+        }
+        self.ann_client.embedding_insert_bulk(items).await?;
+        Ok(count)
+    }
+
     async fn sync_embeddings(conversation_id: i32, client_ann: &AnnClient) -> Result<()> {
         // let last_ann_message = client_ann.get_partition_status(conversation_id).await?;
         // let last_ann_message_id = last_ann_message.last_vector_id;
@@ -57,3 +194,10 @@ impl EmbeddingService {
         Ok(())
     }
 }
+
+fn driver_name(runner: &Runner) -> Result<String> {
+    let driver = runner
+        .get_driver()
+        .ok_or(sn_inference::error::Error::DriverNotLoaded)?;
+    Ok(driver.name)
+}
@@ -7,7 +7,11 @@ use crate::{
 };
 use futures::future::join_all;
 use sn_core::{
-    server::payload::backend::generate_text_request::GenerateTextRequest, types::message::Message,
+    server::payload::backend::{
+        continue_message_request::ContinueMessageRequest,
+        generate_text_request::GenerateTextRequest,
+    },
+    types::{generate_text_options::GenerateTextOptions, message::Message},
 };
 use sn_inference::runner::Runner;
 use std::sync::{Arc, RwLock};
@@ -20,6 +24,7 @@ use crate::{
 
 #[derive(Clone, Debug)]
 pub struct MessageService {
+    repo_message: Arc<MessageRepository>,
     service_conversation: Arc<ConversationService>,
     service_background: Arc<MessageBackgroundService>,
     runner: Arc<RwLock<Runner>>,
@@ -38,6 +43,7 @@ impl MessageService {
             service_embedding,
         ));
         MessageService {
+            repo_message,
             service_conversation,
             runner,
             service_background,
@@ -52,7 +58,56 @@ impl MessageService {
 
         let _ = agg.add_user_message(&req)?;
         let result = use_case
-            .generate(stream.as_ref(), agg, req.model_id.clone(), req.session_id)
+            .generate(
+                stream.as_ref(),
+                agg,
+                req.model_id.clone(),
+                req.options(),
+                req.session_id,
+                req.adapter.clone(),
+            )
+            .await?;
+
+        let result = self.service_background.clone().execute(result);
+
+        Ok(result)
+    }
+
+    /// Continue a stored assistant message, the generated text is appended to it.
+    pub async fn continue_message(
+        &self,
+        req: ContinueMessageRequest,
+    ) -> Result<GenerateTextOutput> {
+        let message = self
+            .repo_message
+            .find_by_id(&req.message_id)
+            .await?
+            .ok_or(ErrorBackend::MessageNotFound(req.message_id))?;
+        let mut messages = self
+            .repo_message
+            .find_all_by_conversation_id(&message.conversation_id)
+            .await?;
+        if let Some(idx) = messages.iter().position(|m| m.id == message.id) {
+            messages.truncate(idx + 1);
+        }
+        let messages: Vec<Message> = messages.into_iter().map(|m| m.into_message()).collect();
+
+        let model_id = req.model_id.unwrap_or_else(|| message.model_id.into());
+        let stream = StreamChannel::new(&req.stream);
+        let mut agg = MessageAggregate::new(Some(message.conversation_id));
+        let use_case = GenerateTextUseCase::new(self.runner.clone());
+
+        agg.continue_message(model_id.clone(), messages)?;
+        let options = GenerateTextOptions {
+            enable_thinking: req.enable_thinking,
+            max_tokens: req.max_tokens,
+            continue_final_message: true,
+            ..GenerateTextOptions::default()
+        };
+        // The whole conversation is rendered again, so it is not generated on the cache
+        // of the session.
+        let result = use_case
+            .generate(stream.as_ref(), agg, model_id, options, None, req.adapter)
             .await?;
 
         let result = self.service_background.clone().execute(result);
@@ -140,16 +195,20 @@ impl MessageBackgroundService {
         }
     }
 
-    async fn handle_persist_message(&self, agg: MessageAggregate) -> Result<(Message, Message)> {
+    async fn handle_persist_message(&self, agg: MessageAggregate) -> Result<Vec<Message>> {
         debug!("Persisting messages to the database...");
-        if let (
-            Some(assistant_message),
-            Some(user_message),
-            Some(conversation_id),
-            Some(model_id),
-        ) = (
+        if let (Some(assistant_message), Some(message_id)) =
+            (agg.get_assistant_message(), agg.get_continued_message_id())
+        {
+            let message = self
+                .repo_message
+                .update_continued(&message_id, assistant_message)
+                .await?;
+            // Its content changed, so does its embedding.
+            return Ok(vec![message.into_message()]);
+        }
+        if let (Some(assistant_message), Some(conversation_id), Some(model_id)) = (
             agg.get_assistant_message(),
-            agg.get_user_message(),
             agg.get_conversation_id(),
             agg.get_model_id(),
         ) {
@@ -157,13 +216,18 @@ impl MessageBackgroundService {
                 .repo_message
                 .create(
                     &conversation_id,
-                    assistant_message.content,
-                    assistant_message.stats,
-                    user_message.content,
+                    assistant_message,
+                    agg.get_user_message().map(|m| m.content),
+                    agg.get_tool_messages()
+                        .into_iter()
+                        .map(|m| m.content)
+                        .collect(),
                     model_id.to_string(),
                 )
                 .await?;
-            return Ok((user.into_message(), assistant.into_message()));
+            let mut messages: Vec<Message> = user.map(|m| m.into_message()).into_iter().collect();
+            messages.push(assistant.into_message());
+            return Ok(messages);
         }
         return Err(ErrorBackend::FailedToPersist(
             "failed to persist message".to_string(),
@@ -179,7 +243,7 @@ impl MessageBackgroundService {
             let conversation_id = &agg.get_conversation_id().ok_or_else(|| {
                 ErrorBackend::MessageBackgroundParamNotFound("conversation_id".to_string())
             })?;
-            let (user_message, assistant_message) = self.handle_persist_message(agg).await?;
+            let messages = self.handle_persist_message(agg).await?;
 
             self.service_conversation
                 .generate_name(model_id.clone(), conversation_id)
@@ -187,7 +251,7 @@ impl MessageBackgroundService {
                 .err()
                 .map(|e| error!("Failed to generate conversation name: {}", e));
 
-            let futures = vec![user_message.clone(), assistant_message.clone()]
+            let futures = messages
                 .into_iter()
                 .map(|m| self.service_embedding.generate_embedding(m));
 
@@ -1,3 +1,4 @@
+pub(crate) mod completion;
 pub(crate) mod conversation;
 pub(crate) mod embedding;
 pub(crate) mod message;
@@ -1,10 +1,15 @@
-use crate::error::Result;
+use crate::error::{ErrorBackend, Result};
 use std::sync::{Arc, RwLock};
 
 use sn_core::{
     server::payload::backend::{
+        adapter_request::AdapterRequest, adapter_response::AdapterResponse,
+        convert_model_request::ConvertModelRequest, convert_model_response::ConvertModelResponse,
+        driver_request::DriverRequest, driver_response::DriverResponse,
+        import_model_request::ImportModelRequest, import_model_response::ImportModelResponse,
         list_running_model_response::ListRunningModelResponse, run_model_request::RunModelRequest,
     },
+    types::model_info::ModelInfo,
     utils::rw_lock::RwLockExt,
 };
 use sn_inference::runner::Runner;
@@ -46,16 +51,17 @@ impl ModelService {
             .map(|model| ListRunningModelResponse {
                 id: model.id.clone(),
                 name: model.name.clone(),
+                quantization: model.quantization,
             })
             .collect::<Vec<_>>();
         Ok(models)
     }
 
-    pub async fn list_models(&self) -> Result<Vec<String>> {
-        let models_installed: Vec<String> = {
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let models_installed: Vec<ModelInfo> = {
             let context = "reading models installed of the runner";
             let guard = &self.runner;
-            guard.read_lock(context)?.scan_model_installed()?
+            guard.read_lock(context)?.list_models()?
         };
         Ok(models_installed)
     }
@@ -67,4 +73,83 @@ impl ModelService {
 
         Ok(result)
     }
+
+    pub async fn convert_model(&self, req: ConvertModelRequest) -> Result<ConvertModelResponse> {
+        let runner = self.runner.clone();
+        info!(
+            "Converting model {} with quantization {}",
+            req.model_name, req.quantization
+        );
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("converting model")?;
+            let response = guard.convert_model(
+                &req.model_name,
+                req.output_name.as_deref(),
+                req.quantization,
+            )?;
+            Ok::<_, ErrorBackend>(response)
+        });
+        task.await?
+    }
+
+    pub async fn import_model(&self, req: ImportModelRequest) -> Result<ImportModelResponse> {
+        let runner = self.runner.clone();
+        info!(
+            "Importing model {} from the Hugging Face cache",
+            req.repo_id
+        );
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("importing model")?;
+            let response =
+                guard.import_model(&req.repo_id, req.alias.as_deref(), req.revision.as_deref())?;
+            Ok::<_, ErrorBackend>(response)
+        });
+        task.await?
+    }
+
+    pub async fn list_adapters(&self, model_id: &str) -> Result<Vec<AdapterResponse>> {
+        let context = "reading adapters of the runner";
+        Ok(self.runner.read_lock(context)?.list_adapters(model_id)?)
+    }
+
+    pub async fn load_adapter(
+        &self,
+        model_id: String,
+        req: AdapterRequest,
+    ) -> Result<AdapterResponse> {
+        let runner = self.runner.clone();
+        info!("Loading adapter {} on model {}", req.name, model_id);
+        let task = tokio::task::spawn_blocking(move || {
+            let guard = runner.read_lock("loading adapter")?;
+            Ok::<_, ErrorBackend>(guard.load_adapter(&model_id, &req.name)?)
+        });
+        task.await?
+    }
+
+    pub async fn unload_adapter(&self, model_id: &str, req: AdapterRequest) -> Result<()> {
+        let context = "unloading adapter";
+        info!("Unloading adapter {} from model {}", req.name, model_id);
+        self.runner
+            .read_lock(context)?
+            .unload_adapter(model_id, &req.name)?;
+        Ok(())
+    }
+
+    pub async fn get_driver(&self) -> Result<DriverResponse> {
+        let context = "reading driver of the runner";
+        let driver = self.runner.read_lock(context)?.get_driver();
+        Ok(driver.ok_or(sn_inference::error::Error::DriverNotLoaded)?)
+    }
+
+    /// Load the new driver while the current one keeps serving embeddings, then swap them.
+    pub async fn set_driver(&self, req: DriverRequest) -> Result<DriverResponse> {
+        let runner = self.runner.clone();
+        info!("Switching driver to {}", req.name);
+        let task = tokio::task::spawn_blocking(move || {
+            let driver = runner.read_lock("loading driver")?.load_driver(&req.name)?;
+            let response = runner.write_lock("swapping driver")?.set_driver(driver)?;
+            Ok::<_, ErrorBackend>(response)
+        });
+        task.await?
+    }
 }
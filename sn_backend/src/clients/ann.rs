@@ -5,7 +5,9 @@ use reqwest::{Client, Response};
 use sn_core::server::defauft_config::{
     DEFAULT_SERVER_ANN_HOST, DEFAULT_SERVER_ANN_PORT, DEFAULT_SERVER_ANN_PROTOCOL,
 };
+use sn_core::server::payload::ann::index_status_response::IndexStatusResponse;
 use sn_core::server::payload::ann::partition_status_response::PartitionStatusResponse;
+use sn_core::server::payload::ann::reset_index_request::ResetIndexRequest;
 use sn_core::server::payload::ann::search_request::SearchRequest;
 use sn_core::server::payload::ann::search_response::SearchResponse;
 use sn_core::types::ann_item::AnnItem;
@@ -64,6 +66,21 @@ impl AnnClient {
         Ok(result)
     }
 
+    pub async fn get_index_status(&self) -> Result<IndexStatusResponse> {
+        let url = format!("{}/api/index/status", self.base_url);
+        let result = self.client.get(&url).send().await;
+        let result = self.handle_response(result).await?;
+        let result = serde_json::from_str::<IndexStatusResponse>(&result)?;
+        Ok(result)
+    }
+
+    pub async fn reset_index(&self, dim: usize) -> Result<String> {
+        let url = format!("{}/api/index/reset", self.base_url);
+        let payload = ResetIndexRequest { dim };
+        let result = self.client.post(&url).json(&payload).send().await;
+        Ok(self.handle_response(result).await?)
+    }
+
     pub async fn embedding_insert_bulk(&self, items: Vec<AnnItem>) -> Result<String> {
         let url = format!("{}/api/embedding/insert_bulk", self.base_url);
         let result = self.client.post(&url).json(&items).send().await;
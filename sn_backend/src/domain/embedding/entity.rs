@@ -14,6 +14,7 @@ pub struct Model {
     pub conversation_id: i32,
     pub created_at: NaiveDateTime,
     pub data: Json,
+    pub dim: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
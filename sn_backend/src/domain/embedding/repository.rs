@@ -2,9 +2,11 @@ use std::sync::Arc;
 
 use crate::domain;
 use crate::error::Result;
-use sea_orm::sqlx::types::chrono::NaiveDateTime;
 use sea_orm::ActiveValue::Set;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::sqlx::types::chrono::NaiveDateTime;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+};
 use serde_json::json;
 
 #[derive(Clone, Debug)]
@@ -28,11 +30,61 @@ impl EmbeddingRepository {
             conversation_id: Set(*conversation_id),
             message_id: Set(*message_id),
             data: Set(data),
+            dim: Set(Some(emb.len() as i32)),
             ..Default::default()
         };
         Ok(Some(new_embedding.insert(self.db.as_ref()).await?))
     }
 
+    pub async fn update_data(
+        &self,
+        embedding: domain::embedding::entity::Model,
+        emb: &[f32],
+    ) -> Result<domain::embedding::entity::Model> {
+        let mut embedding: domain::embedding::entity::ActiveModel = embedding.into();
+        embedding.data = Set(json!(emb));
+        embedding.dim = Set(Some(emb.len() as i32));
+        Ok(embedding.update(self.db.as_ref()).await?)
+    }
+
+    pub async fn find_by_message_id(
+        &self,
+        message_id: &i32,
+    ) -> Result<Option<domain::embedding::entity::Model>> {
+        let embedding = domain::embedding::entity::Entity::find()
+            .filter(domain::embedding::entity::Column::MessageId.eq(*message_id))
+            .one(self.db.as_ref())
+            .await?;
+        Ok(embedding)
+    }
+
+    pub async fn find_all_with_dim(
+        &self,
+        dim: usize,
+    ) -> Result<Vec<domain::embedding::entity::Model>> {
+        let embeddings = domain::embedding::entity::Entity::find()
+            .filter(domain::embedding::entity::Column::Dim.eq(dim as i32))
+            .all(self.db.as_ref())
+            .await?;
+        Ok(embeddings)
+    }
+
+    /// Embeddings produced with another dimension than `dim`, or before it was recorded.
+    pub async fn find_all_with_other_dim(
+        &self,
+        dim: usize,
+    ) -> Result<Vec<domain::embedding::entity::Model>> {
+        let embeddings = domain::embedding::entity::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(domain::embedding::entity::Column::Dim.is_null())
+                    .add(domain::embedding::entity::Column::Dim.ne(dim as i32)),
+            )
+            .all(self.db.as_ref())
+            .await?;
+        Ok(embeddings)
+    }
+
     pub async fn find_all_embeddings_after_message_id(
         &self,
         message_id: &i32,
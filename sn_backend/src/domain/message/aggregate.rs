@@ -16,6 +16,10 @@ pub struct MessageAggregate {
     messages: Vec<Message>,
     assistant_message: Option<Message>,
     user_message: Option<Message>,
+    /// Results of tool calls sent with this turn, stored with it.
+    tool_messages: Vec<Message>,
+    /// Assistant message the generation continues, a prefill or a stored message.
+    continued_message: Option<Message>,
     conversation_id: Option<i32>,
     model_id: Option<Arc<str>>,
 }
@@ -27,6 +31,8 @@ impl MessageAggregate {
             messages: vec![],
             assistant_message: None,
             user_message: None,
+            tool_messages: vec![],
+            continued_message: None,
             model_id: None,
         }
     }
@@ -41,22 +47,74 @@ impl MessageAggregate {
 
     pub fn add_user_message(&mut self, req: &GenerateTextRequest) -> Result<()> {
         self.model_id = Some(req.model_id.clone());
-        let message = MessageBuilder::default()
-            .content(req.prompt.clone())
-            .role(MessageRole::User)
-            .build()
-            .map_err(|e| ErrorBackend::Core(e.into()))?;
-        self.messages.push(message.clone());
-        self.user_message = Some(message);
+        self.messages.extend(req.messages.iter().cloned());
+        self.tool_messages = req
+            .messages
+            .iter()
+            .filter(|message| message.role == MessageRole::Tool)
+            .cloned()
+            .collect();
+        if !req.prompt.is_empty() || req.messages.is_empty() {
+            let message = MessageBuilder::default()
+                .content(req.prompt.clone())
+                .role(MessageRole::User)
+                .build()
+                .map_err(|e| ErrorBackend::Core(e.into()))?;
+            self.messages.push(message.clone());
+            self.user_message = Some(message);
+        }
+        if let Some(prefill) = req.prefill() {
+            let message = MessageBuilder::default()
+                .content(prefill.to_string())
+                .role(MessageRole::Assistant)
+                .build()
+                .map_err(|e| ErrorBackend::Core(e.into()))?;
+            self.messages.push(message.clone());
+            self.continued_message = Some(message);
+        }
+        Ok(())
+    }
+
+    /// Continue the stored assistant message ending `messages`, the conversation up to it.
+    pub fn continue_message(&mut self, model_id: Arc<str>, messages: Vec<Message>) -> Result<()> {
+        let message = match messages.last() {
+            Some(message) if message.role == MessageRole::Assistant => message.clone(),
+            _ => {
+                return Err(ErrorBackend::InvalidRequest(
+                    "Only assistant messages can be continued".to_string(),
+                ));
+            }
+        };
+        self.model_id = Some(model_id);
+        self.messages = messages;
+        self.continued_message = Some(message);
         Ok(())
     }
 
+    /// Add the generated answer, appended to the continued message when there is one.
     pub fn add_assistant_message(&mut self, res: GenerateTextResult) -> Result<()> {
-        let (content, stats) = res;
+        let (id, content, reasoning) = match &self.continued_message {
+            Some(continued) => {
+                self.messages.pop();
+                let reasoning = match (continued.reasoning_content.clone(), res.reasoning) {
+                    (Some(continued), Some(reasoning)) => Some(continued + &reasoning),
+                    (continued, reasoning) => continued.or(reasoning),
+                };
+                (
+                    continued.id,
+                    continued.content.clone() + &res.content,
+                    reasoning,
+                )
+            }
+            None => (0, res.content, res.reasoning),
+        };
         let message = MessageBuilder::default()
+            .id(id)
             .content(content)
+            .reasoning_content(reasoning)
             .role(MessageRole::Assistant)
-            .stats(stats)
+            .stats(res.stats)
+            .tool_calls((!res.tool_calls.is_empty()).then_some(res.tool_calls))
             .build()
             .map_err(|e| ErrorBackend::Core(e.into()))?;
         self.assistant_message = Some(message.clone());
@@ -84,4 +142,16 @@ impl MessageAggregate {
     pub fn get_user_message(&self) -> Option<Message> {
         self.user_message.clone()
     }
+
+    pub fn get_tool_messages(&self) -> Vec<Message> {
+        self.tool_messages.clone()
+    }
+
+    /// Id of the stored message the generation continued.
+    pub fn get_continued_message_id(&self) -> Option<i32> {
+        self.continued_message
+            .as_ref()
+            .map(|message| message.id)
+            .filter(|id| *id != 0)
+    }
 }
@@ -13,6 +13,8 @@ pub struct Model {
     pub id: i32,
     pub role: String,
     pub content: String,
+    pub reasoning: Option<String>,
+    pub tool_calls: Option<Json>,
     pub generation_duration: Option<f64>,
     pub prompt_tps: Option<f64>,
     pub generation_tps: Option<f64>,
@@ -54,7 +56,14 @@ pub trait IntoMessage {
 impl IntoMessage for Model {
     fn into_message(self) -> Message {
         sn_core::types::message::MessageBuilder::default()
+            .id(self.id)
             .content(self.content.clone())
+            .reasoning_content(self.reasoning.clone())
+            .tool_calls(
+                self.tool_calls
+                    .clone()
+                    .and_then(|tool_calls| serde_json::from_value(tool_calls).ok()),
+            )
             .role(MessageRole::try_from(self.role.as_str()).unwrap_or(MessageRole::User))
             .conversation_id(Some(self.conversation_id))
             .build()
@@ -1,12 +1,11 @@
 use std::sync::Arc;
 
 use crate::domain;
-use crate::error::Result;
+use crate::error::{ErrorBackend, Result};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
 };
-use sn_core::types::message::MessageRole;
-use sn_core::types::message_stats::MessageStats;
+use sn_core::types::message::{Message, MessageRole};
 
 #[derive(Clone, Debug)]
 pub struct MessageRepository {
@@ -24,31 +23,55 @@ impl MessageRepository {
         Ok(message)
     }
 
+    /// Store the answer with the user message it follows, a turn answering tool results
+    /// has none but stores the `tool` messages carrying them instead.
     pub async fn create(
         &self,
         conversation_id: &i32,
-        assistant_content: String,
-        assistant_stats: Option<MessageStats>,
-        user_content: String,
+        assistant_message: Message,
+        user_content: Option<String>,
+        tool_contents: Vec<String>,
         model_id: String,
     ) -> Result<(
-        domain::message::entity::Model,
+        Option<domain::message::entity::Model>,
         domain::message::entity::Model,
     )> {
-        let new_message_user = domain::message::entity::ActiveModel {
-            conversation_id: Set(*conversation_id),
-            content: Set(user_content),
-            role: Set(MessageRole::User.to_string()),
-            model_id: Set(model_id.clone()),
-            ..Default::default()
+        let new_message_user = match user_content {
+            Some(user_content) => {
+                let new_message_user = domain::message::entity::ActiveModel {
+                    conversation_id: Set(*conversation_id),
+                    content: Set(user_content),
+                    role: Set(MessageRole::User.to_string()),
+                    model_id: Set(model_id.clone()),
+                    ..Default::default()
+                };
+                Some(new_message_user.insert(self.db.as_ref()).await?)
+            }
+            None => None,
         };
-        let new_message_user = new_message_user.insert(self.db.as_ref()).await?;
 
-        let stats = &assistant_stats.unwrap_or_default();
+        for tool_content in tool_contents {
+            let new_message_tool = domain::message::entity::ActiveModel {
+                conversation_id: Set(*conversation_id),
+                content: Set(tool_content),
+                role: Set(MessageRole::Tool.to_string()),
+                model_id: Set(model_id.clone()),
+                ..Default::default()
+            };
+            new_message_tool.insert(self.db.as_ref()).await?;
+        }
+
+        let stats = &assistant_message.stats.unwrap_or_default();
+        let tool_calls = match assistant_message.tool_calls {
+            Some(tool_calls) => Some(serde_json::to_value(tool_calls)?),
+            None => None,
+        };
 
         let new_message_assistant = domain::message::entity::ActiveModel {
             conversation_id: Set(*conversation_id),
-            content: Set(assistant_content),
+            content: Set(assistant_message.content),
+            reasoning: Set(assistant_message.reasoning_content),
+            tool_calls: Set(tool_calls),
             prompt_tps: Set(Some(stats.prompt_tps)),
             generation_tps: Set(Some(stats.generation_tps)),
             generation_duration: Set(Some(stats.generation_duration)),
@@ -60,6 +83,34 @@ impl MessageRepository {
         Ok((new_message_user, new_message_assistant))
     }
 
+    /// Replace a stored message with its continuation: content, reasoning, tool calls and
+    /// stats of the last generation.
+    pub async fn update_continued(
+        &self,
+        id: &i32,
+        assistant_message: Message,
+    ) -> Result<domain::message::entity::Model> {
+        let message = domain::message::entity::Entity::find_by_id(*id)
+            .one(self.db.as_ref())
+            .await?
+            .ok_or_else(|| ErrorBackend::MessageNotFound(*id))?;
+
+        let stats = &assistant_message.stats.unwrap_or_default();
+        let tool_calls = match assistant_message.tool_calls {
+            Some(tool_calls) => Some(serde_json::to_value(tool_calls)?),
+            None => None,
+        };
+        let mut message: domain::message::entity::ActiveModel = message.into();
+        message.content = Set(assistant_message.content);
+        message.reasoning = Set(assistant_message.reasoning_content);
+        message.tool_calls = Set(tool_calls);
+        message.prompt_tps = Set(Some(stats.prompt_tps));
+        message.generation_tps = Set(Some(stats.generation_tps));
+        message.generation_duration = Set(Some(stats.generation_duration));
+        let updated_message = message.update(self.db.as_ref()).await?;
+        Ok(updated_message)
+    }
+
     pub async fn find_all_by_conversation_id(
         &self,
         conversation_id: &i32,
@@ -67,6 +118,8 @@ impl MessageRepository {
         let messages = domain::message::entity::Entity::find()
             .filter(domain::message::entity::Column::ConversationId.eq(*conversation_id))
             .order_by_asc(domain::message::entity::Column::CreatedAt)
+            // Messages of one turn are stored within the same second.
+            .order_by_asc(domain::message::entity::Column::Id)
             .all(self.db.as_ref())
             .await?;
         Ok(messages)
@@ -1,12 +1,20 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table_init;
+mod m20250901_000001_add_embedding_dim;
+mod m20250915_000001_add_message_reasoning;
+mod m20250920_000001_add_message_tool_calls;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table_init::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table_init::Migration),
+            Box::new(m20250901_000001_add_embedding_dim::Migration),
+            Box::new(m20250915_000001_add_message_reasoning::Migration),
+            Box::new(m20250920_000001_add_message_tool_calls::Migration),
+        ]
     }
 }
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Dimension of the driver that produced the embedding, null for the ones stored
+        // before it was recorded
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Embedding::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Embedding::Dim).integer().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Embedding::Table)
+                    .drop_column(Embedding::Dim)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Embedding {
+    Table,
+    Dim,
+}
@@ -0,0 +1,44 @@
+use crate::application::completion::service::CompletionOutput;
+use crate::error::{ResultAPI, ResultAPIStream};
+use crate::server::app_state::AppState;
+use crate::utils::sse_response_builder::SseResponseBuilder;
+use axum::Json;
+use axum::extract::State;
+use axum::extract::rejection::JsonRejection;
+use axum::response::IntoResponse;
+use serde_json::json;
+use sn_core::server::payload::backend::completion_request::CompletionRequest;
+use sn_core::server::payload::backend::fim_request::FimRequest;
+use sn_core::server::payload::backend::score_request::ScoreRequest;
+use std::sync::Arc;
+
+pub async fn completions_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<CompletionRequest>, JsonRejection>,
+) -> ResultAPIStream {
+    let req = req?.0;
+    match state.service_completion.complete(req).await? {
+        CompletionOutput::Json(response) => Ok(Json(json!(response)).into_response()),
+        CompletionOutput::Streaming(receiver) => SseResponseBuilder::new(receiver).build(),
+    }
+}
+
+pub async fn fim_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<FimRequest>, JsonRejection>,
+) -> ResultAPIStream {
+    let req = req?.0;
+    match state.service_completion.fim(req).await? {
+        CompletionOutput::Json(response) => Ok(Json(json!(response)).into_response()),
+        CompletionOutput::Streaming(receiver) => SseResponseBuilder::new(receiver).build(),
+    }
+}
+
+pub async fn score_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<ScoreRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let response = state.service_completion.score(req).await?;
+    Ok(Json(json!(response)))
+}
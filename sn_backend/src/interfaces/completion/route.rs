@@ -0,0 +1,20 @@
+use crate::{
+    interfaces::completion::controller::{completions_handler, fim_handler, score_handler},
+    server::app_state::AppState,
+};
+use axum::routing::post;
+use sn_core::server::routes::BackendApiCompletion;
+use std::sync::Arc;
+
+pub fn routes() -> axum::Router<Arc<AppState>> {
+    axum::Router::new()
+        .route(
+            BackendApiCompletion::Completions.path().as_str(),
+            post(completions_handler),
+        )
+        .route(BackendApiCompletion::Fim.path().as_str(), post(fim_handler))
+        .route(
+            BackendApiCompletion::Score.path().as_str(),
+            post(score_handler),
+        )
+}
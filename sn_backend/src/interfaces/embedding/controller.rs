@@ -0,0 +1,27 @@
+use crate::error::ResultAPI;
+use crate::server::app_state::AppState;
+use axum::Json;
+use axum::extract::State;
+use axum::extract::rejection::JsonRejection;
+use serde_json::json;
+use sn_core::server::payload::backend::embeddings_request::EmbeddingsRequest;
+use sn_core::server::payload::backend::rerank_request::RerankRequest;
+use std::sync::Arc;
+
+pub async fn embeddings_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<EmbeddingsRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let response = state.service_embedding.embeddings(req).await?;
+    Ok(Json(json!(response)))
+}
+
+pub async fn rerank_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<RerankRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let response = state.service_embedding.rerank(req).await?;
+    Ok(Json(json!(response)))
+}
@@ -0,0 +1,19 @@
+use crate::{
+    interfaces::embedding::controller::{embeddings_handler, rerank_handler},
+    server::app_state::AppState,
+};
+use axum::routing::post;
+use sn_core::server::routes::BackendApiEmbedding;
+use std::sync::Arc;
+
+pub fn routes() -> axum::Router<Arc<AppState>> {
+    axum::Router::new()
+        .route(
+            BackendApiEmbedding::Embeddings.path().as_str(),
+            post(embeddings_handler),
+        )
+        .route(
+            BackendApiEmbedding::Rerank.path().as_str(),
+            post(rerank_handler),
+        )
+}
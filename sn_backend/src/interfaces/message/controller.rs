@@ -7,6 +7,7 @@ use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::Json;
 use serde_json::json;
+use sn_core::server::payload::backend::continue_message_request::ContinueMessageRequest;
 use sn_core::server::payload::backend::generate_text_request::GenerateTextRequest;
 use std::sync::Arc;
 
@@ -69,6 +70,19 @@ pub async fn generate_text_handler(
 ) -> ResultAPIStream {
     let req = req?.0;
     let result = state.service_message.generate_text(req).await?;
+    generate_text_response(result)
+}
+
+pub async fn continue_message_handler(
+    State(state): State<Arc<AppState>>,
+    req: std::result::Result<Json<ContinueMessageRequest>, JsonRejection>,
+) -> ResultAPIStream {
+    let req = req?.0;
+    let result = state.service_message.continue_message(req).await?;
+    generate_text_response(result)
+}
+
+fn generate_text_response(result: GenerateTextOutput) -> ResultAPIStream {
     match result {
         GenerateTextOutput::Json(agg) => {
             if let Some(message_assistant) = agg.get_assistant_message() {
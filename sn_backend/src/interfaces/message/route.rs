@@ -1,11 +1,19 @@
-use crate::{interfaces::message::controller::generate_text_handler, server::app_state::AppState};
+use crate::{
+    interfaces::message::controller::{continue_message_handler, generate_text_handler},
+    server::app_state::AppState,
+};
 use axum::routing::post;
 use sn_core::server::routes::BackendApiMessage;
 use std::sync::Arc;
 
 pub fn routes() -> axum::Router<Arc<AppState>> {
-    axum::Router::new().route(
-        BackendApiMessage::Generate.path().as_str(),
-        post(generate_text_handler),
-    )
+    axum::Router::new()
+        .route(
+            BackendApiMessage::Generate.path().as_str(),
+            post(generate_text_handler),
+        )
+        .route(
+            BackendApiMessage::Continue.path().as_str(),
+            post(continue_message_handler),
+        )
 }
@@ -1,4 +1,6 @@
+pub(crate) mod completion;
 pub(crate) mod conversation;
+pub(crate) mod embedding;
 pub(crate) mod message;
 pub(crate) mod model;
 pub(crate) mod session;
@@ -2,20 +2,25 @@ use crate::domain::model::value_object::RunModelOutput;
 use crate::error::{ErrorBackend, ResultAPI, ResultAPIStream};
 use crate::server::app_state::AppState;
 use crate::utils::sse_response_builder::SseResponseBuilder;
+use axum::Json;
 use axum::extract::rejection::JsonRejection;
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::response::IntoResponse;
-use axum::Json;
 use serde_json::json;
+use sn_core::server::payload::backend::adapter_request::AdapterRequest;
+use sn_core::server::payload::backend::convert_model_request::ConvertModelRequest;
+use sn_core::server::payload::backend::driver_request::DriverRequest;
+use sn_core::server::payload::backend::import_model_request::ImportModelRequest;
 use sn_core::server::payload::backend::run_model_request::RunModelRequest;
 use sn_core::server::payload::backend::run_model_response::{
     RunModelAction, RunModelResponse, RunModelResponseJson,
 };
 use std::sync::Arc;
+use tracing::{error, info};
 
 pub async fn list_models_handler(State(state): State<Arc<AppState>>) -> ResultAPI {
     let models_installed = state.service_model.list_models().await?;
-    Ok(Json(models_installed.into()))
+    Ok(Json(json!(models_installed)))
 }
 
 pub async fn list_running_models_handler(State(state): State<Arc<AppState>>) -> ResultAPI {
@@ -60,3 +65,73 @@ pub async fn stop_model_handler(
         status: RunModelAction::Stop,
     })))
 }
+
+pub async fn convert_model_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<ConvertModelRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let response = state.service_model.convert_model(req).await?;
+    Ok(Json(json!(response)))
+}
+
+pub async fn import_model_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<ImportModelRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let response = state.service_model.import_model(req).await?;
+    Ok(Json(json!(response)))
+}
+
+pub async fn get_driver_handler(State(state): State<Arc<AppState>>) -> ResultAPI {
+    let driver = state.service_model.get_driver().await?;
+    Ok(Json(json!(driver)))
+}
+
+pub async fn set_driver_handler(
+    State(state): State<Arc<AppState>>,
+    req: Result<Json<DriverRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let driver = state.service_model.set_driver(req).await?;
+    if driver.previous_embedding_dim.is_some() {
+        let service_embedding = state.service_embedding.clone();
+        tokio::spawn(async move {
+            match service_embedding.reindex_embeddings().await {
+                Ok(count) => info!("Re-indexed {} embedding(s)", count),
+                Err(e) => error!("Failed to re-index embeddings: {}", e),
+            }
+        });
+    }
+    Ok(Json(json!(driver)))
+}
+
+pub async fn list_adapters_handler(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+) -> ResultAPI {
+    let adapters = state.service_model.list_adapters(&model_id).await?;
+    Ok(Json(json!(adapters)))
+}
+
+pub async fn load_adapter_handler(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+    req: Result<Json<AdapterRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let adapter = state.service_model.load_adapter(model_id, req).await?;
+    Ok(Json(json!(adapter)))
+}
+
+pub async fn unload_adapter_handler(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+    req: Result<Json<AdapterRequest>, JsonRejection>,
+) -> ResultAPI {
+    let req = req?.0;
+    let name = req.name.clone();
+    state.service_model.unload_adapter(&model_id, req).await?;
+    Ok(Json(json!({ "name": name })))
+}
@@ -1,11 +1,13 @@
 use crate::{
     interfaces::model::controller::{
-        list_models_handler, list_running_models_handler, run_model_handler, stop_model_handler,
+        convert_model_handler, get_driver_handler, import_model_handler, list_adapters_handler,
+        list_models_handler, list_running_models_handler, load_adapter_handler, run_model_handler,
+        set_driver_handler, stop_model_handler, unload_adapter_handler,
     },
     server::app_state::AppState,
 };
 use axum::routing::{get, post};
-use sn_core::server::routes::BackendApiModel;
+use sn_core::server::routes::{BackendApiAdapter, BackendApiModel};
 use std::sync::Arc;
 
 pub fn routes() -> axum::Router<Arc<AppState>> {
@@ -26,4 +28,28 @@ pub fn routes() -> axum::Router<Arc<AppState>> {
             BackendApiModel::Stop.path().as_str(),
             post(stop_model_handler),
         )
+        .route(
+            BackendApiModel::Convert.path().as_str(),
+            post(convert_model_handler),
+        )
+        .route(
+            BackendApiModel::Import.path().as_str(),
+            post(import_model_handler),
+        )
+        .route(
+            BackendApiModel::Driver.path().as_str(),
+            get(get_driver_handler).post(set_driver_handler),
+        )
+        .route(
+            BackendApiAdapter::List.path(None).as_str(),
+            get(list_adapters_handler),
+        )
+        .route(
+            BackendApiAdapter::Load.path(None).as_str(),
+            post(load_adapter_handler),
+        )
+        .route(
+            BackendApiAdapter::Unload.path(None).as_str(),
+            post(unload_adapter_handler),
+        )
 }
@@ -4,9 +4,9 @@ use std::sync::{Arc, RwLock};
 
 use crate::{
     application::{
-        conversation::service::ConversationService, embedding::service::EmbeddingService,
-        message::service::MessageService, model::service::ModelService,
-        session::service::SessionService,
+        completion::service::CompletionService, conversation::service::ConversationService,
+        embedding::service::EmbeddingService, message::service::MessageService,
+        model::service::ModelService, session::service::SessionService,
     },
     clients::ann::AnnClient,
     domain::{
@@ -26,6 +26,7 @@ pub struct AppState {
     pub service_message: Arc<MessageService>,
     pub service_embedding: Arc<EmbeddingService>,
     pub service_model: Arc<ModelService>,
+    pub service_completion: Arc<CompletionService>,
 }
 
 impl AppState {
@@ -39,8 +40,10 @@ impl AppState {
         let repo_embedding = Arc::new(EmbeddingRepository::new(db.clone()));
 
         let service_model = Arc::new(ModelService::new(runner.clone()));
+        let service_completion = Arc::new(CompletionService::new(runner.clone()));
         let service_embedding = Arc::new(EmbeddingService::new(
             repo_embedding,
+            repo_message.clone(),
             runner.clone(),
             client_ann.clone(),
         ));
@@ -66,6 +69,7 @@ impl AppState {
             service_embedding,
             service_conversation,
             service_model,
+            service_completion,
         }
     }
 }
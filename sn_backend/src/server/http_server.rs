@@ -1,25 +1,63 @@
 use crate::clients::ann::AnnClient;
 use crate::error::{ErrorBackend, Result};
 use crate::infrastructure::db::connection::get_connection;
-use crate::interfaces::{conversation, message, model, session};
+use crate::interfaces::{completion, conversation, embedding, message, model, session};
 use crate::server::app_state::AppState;
 use axum::http::StatusCode;
 use sn_core::server::defauft_config::{
     DEFAULT_SERVER_BACKEND_HOST, DEFAULT_SERVER_BACKEND_PORT, DEFAULT_SERVER_BACKEND_PROTOCOL,
 };
 use sn_core::server::routes::print_all_backend_api_paths;
+use sn_core::utils::rw_lock::RwLockExt;
 use sn_inference::runner::Runner;
 use std::env;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tower_http::trace::{
     DefaultMakeSpan, DefaultOnFailure, DefaultOnRequest, DefaultOnResponse, TraceLayer,
 };
 use tracing::{error, info, Level};
+
+const IDLE_MODELS_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Simple fallback handler for unmatched routes.
 async fn fallback() -> (StatusCode, &'static str) {
     (StatusCode::NOT_FOUND, "Not Found")
 }
 
+/// Periodically unloads the models left idle longer than `SANAGA_MODEL_IDLE_TTL`.
+fn spawn_idle_models_unloader(runner: Arc<RwLock<Runner>>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_MODELS_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let runner = runner.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                runner
+                    .read_lock("unloading idle models")?
+                    .unload_idle_models()
+                    .map_err(ErrorBackend::from)
+            })
+            .await;
+            if let Ok(Err(e)) = result {
+                error!("Failed to unload idle models: {}", e);
+            }
+        }
+    });
+}
+
+/// Generates again the embeddings stored with another dimension than the driver loaded
+/// on start, e.g. after `SANAGA_DRIVER_MODEL` changed.
+fn spawn_embeddings_reindex(app_state: Arc<AppState>) {
+    tokio::spawn(async move {
+        match app_state.service_embedding.reindex_embeddings().await {
+            Ok(0) => {}
+            Ok(count) => info!("Re-indexed {} embedding(s)", count),
+            Err(e) => error!("Failed to re-index embeddings: {}", e),
+        }
+    });
+}
+
 /// Starts the HTTP server using Axum and shared Runner and DB state.
 ///
 /// # Arguments
@@ -44,13 +82,17 @@ pub async fn http_server_backend(runner: Arc<RwLock<Runner>>) -> Result<()> {
     let port = env::var("SERVER_BACKEND_PORT").unwrap_or(String::from(DEFAULT_SERVER_BACKEND_PORT));
     let protocol = env::var("SERVER_BACKEND_PROTOCOL")
         .unwrap_or(String::from(DEFAULT_SERVER_BACKEND_PROTOCOL));
+    spawn_idle_models_unloader(runner.clone());
     // Initialize shared application state
     let app_state = Arc::new(AppState::new(runner, db, ann));
+    spawn_embeddings_reindex(app_state.clone());
     let routes_api = axum::Router::new()
         .merge(model::route::routes())
         .merge(message::route::routes())
         .merge(session::route::routes())
         .merge(conversation::route::routes())
+        .merge(embedding::route::routes())
+        .merge(completion::route::routes())
         .with_state(app_state.clone());
 
     print_all_backend_api_paths();
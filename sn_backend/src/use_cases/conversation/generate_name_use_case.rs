@@ -1,7 +1,10 @@
 use crate::error::Result;
 use std::sync::{Arc, RwLock};
 
-use sn_core::{types::conversation::Conversation, utils::rw_lock::RwLockExt};
+use sn_core::{
+    types::{conversation::Conversation, generate_text_options::GenerateTextOptions},
+    utils::rw_lock::RwLockExt,
+};
 use sn_inference::runner::Runner;
 
 use crate::domain::conversation::aggregate::ConversationAggregate;
@@ -22,9 +25,16 @@ impl GenerateNameUseCase {
             "resume with with 4 words only: {}",
             message
         ));
-        let generate_text_result = guard.generate_text(&model_id, &conversation, None, None)?;
+        let generate_text_result = guard.generate_text(
+            &model_id,
+            &conversation,
+            &GenerateTextOptions::default(),
+            None,
+            None,
+            None,
+        )?;
         let name = generate_text_result
-            .0
+            .content
             .trim()
             .replace('\n', "")
             .replace('\r', "");
@@ -1,5 +1,8 @@
 use crate::error::{ErrorBackend, Result};
-use sn_core::{types::message::Message, utils::rw_lock::RwLockExt};
+use sn_core::{
+    types::{embedding_options::EmbeddingOptions, message::Message},
+    utils::rw_lock::RwLockExt,
+};
 use sn_inference::runner::Runner;
 use std::sync::{Arc, RwLock};
 
@@ -12,10 +15,14 @@ impl GenerateEmbeddingUseCase {
         GenerateEmbeddingUseCase { runner }
     }
     pub async fn generate_message_embeddings(&self, message: &Message) -> Result<Vec<f32>> {
+        self.generate_content_embeddings(&message.content).await
+    }
+
+    pub async fn generate_content_embeddings(&self, content: &str) -> Result<Vec<f32>> {
         let embeddings = self
             .runner
             .read_lock("read runner for generate embeddings")?
-            .generate_embeddings(&vec![message.content.clone()])
+            .generate_embeddings(&vec![content.to_string()], &EmbeddingOptions::default())
             .map_err(|e| ErrorBackend::Inference(e))?;
         let embeddings = embeddings.as_slice::<f32>().to_vec();
         Ok(embeddings)
@@ -3,7 +3,10 @@ use crate::{
     error::{ErrorBackend, Result},
     utils::stream_channel::StreamChannel,
 };
-use sn_core::{types::stream_data::StreamData, utils::rw_lock::RwLockExt};
+use sn_core::{
+    types::{generate_text_options::GenerateTextOptions, stream_data::StreamData},
+    utils::rw_lock::RwLockExt,
+};
 use sn_inference::runner::Runner;
 use std::sync::{Arc, RwLock};
 use tracing::error;
@@ -24,7 +27,9 @@ impl GenerateTextUseCase {
         stream: Option<&StreamChannel>,
         mut agg: MessageAggregate,
         model_id: Arc<str>,
+        options: GenerateTextOptions,
         session_id: Option<i32>,
+        adapter: Option<String>,
     ) -> Result<GenerateTextOutput> {
         let rx = match stream {
             Some(stream) => Some(stream.rx.clone()),
@@ -40,8 +45,14 @@ impl GenerateTextUseCase {
             let tx_err = tx.clone();
             let guard = runner.read_lock("reading runner for generate_text")?;
             let conversation = agg.to_conversation_core()?;
-            let generate_text_result =
-                guard.generate_text(&model_id, &conversation, session_id, tx);
+            let generate_text_result = guard.generate_text(
+                &model_id,
+                &conversation,
+                &options,
+                session_id,
+                adapter.as_deref(),
+                tx,
+            );
             if let (Err(e), Some(tx_err)) = (&generate_text_result, tx_err) {
                 error!("{}", e);
                 let error = format!("Failed to generate text: {}", e);
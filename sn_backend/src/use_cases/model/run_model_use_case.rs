@@ -31,6 +31,7 @@ impl RunModelUseCase {
         req: RunModelRequest,
     ) -> Result<RunModelOutput> {
         let model_name = req.get_model_name()?;
+        let quantization = req.get_quantization();
         let rx = match stream {
             Some(stream) => Some(stream.rx.clone()),
             None => None,
@@ -43,7 +44,8 @@ impl RunModelUseCase {
         let task = tokio::spawn(async move {
             let tx_err = tx.clone();
             let guard = runner.read_lock("launching model")?;
-            let run_model_result = guard.load_model_name(model_name.as_ref(), tx.clone());
+            let run_model_result =
+                guard.load_model_name(model_name.as_ref(), quantization, tx.clone());
 
             if let (Err(e), Some(tx_err)) = (&run_model_result, tx_err) {
                 error!("{}", e);
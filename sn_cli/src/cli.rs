@@ -33,6 +33,11 @@ pub enum ModelCommands {
         name: Option<String>,
         #[arg()]
         model: Option<String>,
+        /// Quantize a full precision model in memory to this number of bits.
+        #[arg(long)]
+        bits: Option<i32>,
+        #[arg(long, default_value_t = 64)]
+        group_size: i32,
     },
     Stop {
         #[arg(short, long)]
@@ -40,4 +45,33 @@ pub enum ModelCommands {
         #[arg()]
         model: Option<String>,
     },
+    /// Quantize a full precision model into a new installed model.
+    Convert {
+        #[arg()]
+        model: String,
+        #[arg(long)]
+        bits: i32,
+        #[arg(long, default_value_t = 64)]
+        group_size: i32,
+        /// Name of the converted model, defaults to `<model>-<bits>bit`.
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Install a model from the local Hugging Face hub cache without copying its weights.
+    Import {
+        /// Repository id, e.g. `Qwen/Qwen3-1.7B-MLX-4bit`.
+        #[arg()]
+        repo_id: String,
+        /// Name of the installed model, defaults to the repository name.
+        #[arg(short, long)]
+        alias: Option<String>,
+        #[arg(long)]
+        revision: Option<String>,
+    },
+    /// Show the driver model used for embeddings, or switch to another one.
+    Driver {
+        /// Model in the driver directory, or alias or id of an installed model.
+        #[arg()]
+        name: Option<String>,
+    },
 }
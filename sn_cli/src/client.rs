@@ -1,7 +1,10 @@
 use crate::error::{ErrorCli, Result};
 use reqwest::{Client, Response};
+use sn_core::server::payload::backend::convert_model_request::ConvertModelRequest;
 use sn_core::server::payload::backend::create_session_request::CreateSessionRequest;
+use sn_core::server::payload::backend::driver_request::DriverRequest;
 use sn_core::server::payload::backend::generate_text_request::GenerateTextRequest;
+use sn_core::server::payload::backend::import_model_request::ImportModelRequest;
 use sn_core::server::payload::backend::run_model_request::RunModelRequest;
 use sn_core::server::routes::{
     BackendApiMessage, BackendApiModel, BackendApiSession, BackendConversationApi,
@@ -91,6 +94,59 @@ impl CliClient {
             .error_for_status()?;
         Ok(result)
     }
+    pub async fn convert_model(&self, json: &ConvertModelRequest) -> Result<String> {
+        let url = format!(
+            "{}{}",
+            self.base_url_api,
+            BackendApiModel::Convert.path().as_str()
+        );
+        let result = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!(json))
+            .send()
+            .await;
+        Ok(self.handle_response(result).await?)
+    }
+    pub async fn get_driver(&self) -> Result<String> {
+        let url = format!(
+            "{}{}",
+            self.base_url_api,
+            BackendApiModel::Driver.path().as_str()
+        );
+        let result = self.client.get(&url).send().await;
+        Ok(self.handle_response(result).await?)
+    }
+
+    pub async fn set_driver(&self, json: &DriverRequest) -> Result<String> {
+        let url = format!(
+            "{}{}",
+            self.base_url_api,
+            BackendApiModel::Driver.path().as_str()
+        );
+        let result = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!(json))
+            .send()
+            .await;
+        Ok(self.handle_response(result).await?)
+    }
+
+    pub async fn import_model(&self, json: &ImportModelRequest) -> Result<String> {
+        let url = format!(
+            "{}{}",
+            self.base_url_api,
+            BackendApiModel::Import.path().as_str()
+        );
+        let result = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!(json))
+            .send()
+            .await;
+        Ok(self.handle_response(result).await?)
+    }
     pub async fn send_prompt(&self, json: &GenerateTextRequest) -> Result<Response> {
         let url = format!(
             "{}{}",
@@ -0,0 +1,28 @@
+use crate::client::CliClient;
+use crate::error::{ErrorCli, Result};
+use sn_core::server::payload::backend::convert_model_request::ConvertModelRequest;
+use sn_core::server::payload::backend::convert_model_response::ConvertModelResponse;
+use sn_core::types::model_quantization::ModelQuantization;
+
+pub async fn handle(
+    cli_client: &CliClient,
+    model_name: String,
+    output_name: Option<String>,
+    quantization: ModelQuantization,
+) -> Result<()> {
+    println!("Converting {} to {}...", model_name, quantization);
+    let response = cli_client
+        .convert_model(&ConvertModelRequest {
+            model_name: model_name.clone(),
+            output_name,
+            quantization,
+        })
+        .await
+        .map_err(|e| ErrorCli::FailedToConvertModel(model_name.clone(), e.to_string()))?;
+    let response: ConvertModelResponse = serde_json::from_str(&response)?;
+    println!(
+        "Model {} written to {} ({} shard(s))",
+        response.model_name, response.path, response.num_shards
+    );
+    Ok(())
+}
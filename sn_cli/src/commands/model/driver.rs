@@ -0,0 +1,26 @@
+use crate::client::CliClient;
+use crate::error::{ErrorCli, Result};
+use sn_core::server::payload::backend::driver_request::DriverRequest;
+use sn_core::server::payload::backend::driver_response::DriverResponse;
+
+pub async fn handle(cli_client: &CliClient, name: Option<String>) -> Result<()> {
+    let response = match name {
+        Some(name) => cli_client
+            .set_driver(&DriverRequest { name: name.clone() })
+            .await
+            .map_err(|e| ErrorCli::FailedToSetDriver(name.clone(), e.to_string()))?,
+        None => cli_client.get_driver().await?,
+    };
+    let response: DriverResponse = serde_json::from_str(&response)?;
+    println!(
+        "Driver {} ({}), embedding dimension {}",
+        response.name, response.path, response.embedding_dim
+    );
+    if let Some(previous) = response.previous_embedding_dim {
+        println!(
+            "Embedding dimension changed from {}, stored embeddings are being re-indexed",
+            previous
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::client::CliClient;
+use crate::error::{ErrorCli, Result};
+use sn_core::server::payload::backend::import_model_request::ImportModelRequest;
+use sn_core::server::payload::backend::import_model_response::ImportModelResponse;
+
+pub async fn handle(
+    cli_client: &CliClient,
+    repo_id: String,
+    alias: Option<String>,
+    revision: Option<String>,
+) -> Result<()> {
+    let response = cli_client
+        .import_model(&ImportModelRequest {
+            repo_id: repo_id.clone(),
+            alias,
+            revision,
+        })
+        .await
+        .map_err(|e| ErrorCli::FailedToImportModel(repo_id.clone(), e.to_string()))?;
+    let response: ImportModelResponse = serde_json::from_str(&response)?;
+    println!(
+        "Model {} imported as {} ({} file(s) linked from {})",
+        repo_id, response.model_name, response.num_files, response.snapshot_path
+    );
+    Ok(())
+}
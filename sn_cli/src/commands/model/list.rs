@@ -1,18 +1,43 @@
 use crate::client::CliClient;
 use crate::error::Result;
+use sn_core::types::model_info::ModelInfo;
 
 pub async fn handle(cli_client: &CliClient) -> Result<()> {
     let response = cli_client.list_model().await?;
-    let models: Vec<String> =
+    let models: Vec<ModelInfo> =
         serde_json::from_str(&response).map_err(|e| sn_core::error::ErrorCore::from(e))?;
     match models.len() {
         0 => {
             println!("No models installed found.");
         }
         _ => {
-            println!("name\n======");
+            println!(
+                "{:<12} {:<40} {:<24} {:<8} {:<12} {:<8} {:<8}",
+                "id", "name", "architecture", "params", "quantization", "context", "chat"
+            );
+            println!(
+                "{:-<12} {:-<40} {:-<24} {:-<8} {:-<12} {:-<8} {:-<8}",
+                "", "", "", "", "", "", ""
+            );
             for model in models {
-                println!("{}", model);
+                let quantization = model
+                    .quantization
+                    .map(|q| q.to_string())
+                    .unwrap_or_else(|| "none".to_string());
+                let context = model
+                    .context_length
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                println!(
+                    "{:<12} {:<40} {:<24} {:<8} {:<12} {:<8} {:<8}",
+                    model.id,
+                    model.alias,
+                    model.architecture,
+                    model.parameters_label(),
+                    quantization,
+                    context,
+                    if model.has_chat_template { "yes" } else { "no" }
+                );
             }
         }
     };
@@ -1,3 +1,6 @@
+pub(crate) mod convert;
+pub(crate) mod driver;
+pub(crate) mod import;
 pub(crate) mod list;
 pub(crate) mod ps;
 pub(crate) mod run;
@@ -1,6 +1,7 @@
 use crate::client::CliClient;
 use crate::error::Result;
 use serde_json::Value;
+use sn_core::types::model_quantization::ModelQuantization;
 use std::collections::HashMap;
 
 pub async fn handle(cli_client: &CliClient) -> Result<()> {
@@ -12,15 +13,20 @@ pub async fn handle(cli_client: &CliClient) -> Result<()> {
             println!("No models running.");
         }
         _ => {
-            println!("{:<10} {:<40}", "id", "name");
-            println!("{:-<10} {:-<40}", "", "");
+            println!("{:<12} {:<40} {:<12}", "id", "name", "quantization");
+            println!("{:-<12} {:-<40} {:-<12}", "", "", "");
             for model in models {
                 let id = model.get("id").and_then(Value::as_str).unwrap_or("unknown");
                 let name = model
                     .get("name")
                     .and_then(Value::as_str)
                     .unwrap_or("unknown");
-                println!("{:<10} {:<40}", id, name);
+                let quantization = model
+                    .get("quantization")
+                    .and_then(|q| serde_json::from_value::<ModelQuantization>(q.clone()).ok())
+                    .map(|q| q.to_string())
+                    .unwrap_or_else(|| "none".to_string());
+                println!("{:<12} {:<40} {:<12}", id, name, quantization);
             }
         }
     };
@@ -4,9 +4,14 @@ use crate::prompt::prompt::simple_prompt;
 use crate::utils::stream_response_bytes::stream_response_bytes;
 use indicatif::{ProgressBar, ProgressStyle};
 use sn_core::server::payload::backend::run_model_request::RunModelRequest;
+use sn_core::types::model_quantization::ModelQuantization;
 use sn_core::types::stream_data::{StreamData, StreamDataContent};
 
-pub async fn handle(cli_client: &CliClient, model_name: Option<String>) -> Result<()> {
+pub async fn handle(
+    cli_client: &CliClient,
+    model_name: Option<String>,
+    quantization: Option<ModelQuantization>,
+) -> Result<()> {
     if let Some(model_name) = model_name {
         let mut model_id = None;
         let mut pb: Option<ProgressBar> = None;
@@ -15,6 +20,7 @@ pub async fn handle(cli_client: &CliClient, model_name: Option<String>) -> Resul
             .run_model(&RunModelRequest::Start {
                 model_name: model_name.clone(),
                 stream: Some(true),
+                quantization,
             })
             .await
             .map_err(|e| ErrorCli::FailedToRunModel(model_name.clone(), e.to_string()))?;
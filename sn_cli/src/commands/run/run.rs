@@ -28,7 +28,7 @@ pub async fn handler(cli_client: &CliClient) -> Result<()> {
     match ans {
         Ok(model) => {
             // Run model selection handler
-            commands::model::run::handle(cli_client, Some(model)).await?;
+            commands::model::run::handle(cli_client, Some(model), None).await?;
         }
         Err(InquireError::OperationCanceled) => {}
         Err(e) => {
@@ -21,6 +21,15 @@ pub enum ErrorCli {
     #[error("Failed to stop model {0}: {1}")]
     FailedToStopModel(String, String),
 
+    #[error("Failed to convert model {0}: {1}")]
+    FailedToConvertModel(String, String),
+
+    #[error("Failed to import model {0}: {1}")]
+    FailedToImportModel(String, String),
+
+    #[error("Failed to set driver {0}: {1}")]
+    FailedToSetDriver(String, String),
+
     #[error("Model {0} is not compatible with the current version of sn")]
     UnExpectedRunResponse(String),
 
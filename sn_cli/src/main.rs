@@ -2,6 +2,7 @@ use crate::cli::{Cli, Commands, ModelCommands};
 use crate::client::CliClient;
 use crate::error::Result;
 use clap::Parser;
+use sn_core::types::model_quantization::ModelQuantization;
 mod cli;
 mod client;
 mod commands;
@@ -26,12 +27,35 @@ async fn try_main() -> Result<()> {
         Commands::Model(model_commands) => match model_commands {
             ModelCommands::List { .. } => commands::model::list::handle(&cli_client).await?,
             ModelCommands::PS { .. } => commands::model::ps::handle(&cli_client).await?,
-            ModelCommands::Run { model, .. } => {
-                commands::model::run::handle(&cli_client, model).await?
+            ModelCommands::Run {
+                model,
+                bits,
+                group_size,
+                ..
+            } => {
+                let quantization = bits.map(|bits| ModelQuantization { bits, group_size });
+                commands::model::run::handle(&cli_client, model, quantization).await?
             }
             ModelCommands::Stop { model, .. } => {
                 commands::model::stop::handle(&cli_client, model).await?
             }
+            ModelCommands::Convert {
+                model,
+                bits,
+                group_size,
+                output,
+            } => {
+                let quantization = ModelQuantization { bits, group_size };
+                commands::model::convert::handle(&cli_client, model, output, quantization).await?
+            }
+            ModelCommands::Import {
+                repo_id,
+                alias,
+                revision,
+            } => commands::model::import::handle(&cli_client, repo_id, alias, revision).await?,
+            ModelCommands::Driver { name } => {
+                commands::model::driver::handle(&cli_client, name).await?
+            }
         },
     }
 
@@ -23,6 +23,8 @@ struct ResponseInfo {
 }
 
 const INFO_QUIT_PROMPT: &str = "Type 'exit' or 'quit' to exit the prompt.";
+const REASONING_STYLE: &str = "\x1b[2m";
+const RESET_STYLE: &str = "\x1b[0m";
 
 fn handle_response_stream_data(stream_data: &StreamData, response_info: &mut ResponseInfo) {
     if !stream_data.error.is_empty() {
@@ -34,6 +36,11 @@ fn handle_response_stream_data(stream_data: &StreamData, response_info: &mut Res
         StreamDataContent::String(content) => {
             typewriter(&content, 5);
         }
+        StreamDataContent::Reasoning(content) => {
+            print!("{}", REASONING_STYLE);
+            typewriter(&content, 5);
+            print!("{}", RESET_STYLE);
+        }
         StreamDataContent::TextGeneratedMetadataResponseSSE(content) => {
             response_info.metadata.conversation_id = content.conversation_id;
             response_info.metadata.generation_tps = content.generation_tps;
@@ -97,6 +104,12 @@ pub async fn simple_prompt(cli_client: &CliClient, model_id: Arc<str>) -> Result
                 stream: Some(true),
                 conversation_id: last_response_info.metadata.conversation_id,
                 session_id,
+                adapter: None,
+                tools: None,
+                enable_thinking: None,
+                messages: Vec::new(),
+                prefill: None,
+                max_tokens: None,
             })
             .await?;
 
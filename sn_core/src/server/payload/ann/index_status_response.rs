@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// Dimension of the vectors the ANN store accepts, it starts with a default one after
+/// a restart.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct IndexStatusResponse {
+    pub dim: usize,
+}
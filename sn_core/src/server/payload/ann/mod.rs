@@ -1,3 +1,5 @@
+pub mod index_status_response;
 pub mod partition_status_response;
+pub mod reset_index_request;
 pub mod search_request;
 pub mod search_response;
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// Drop every vector of the ANN store and accept vectors of `dim` from now on, used
+/// when the embedding model changed dimension.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ResetIndexRequest {
+    pub dim: usize,
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AdapterRequest {
+    /// Name of the adapter directory under `adapters/`.
+    pub name: String,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AdapterResponse {
+    pub name: String,
+    pub path: String,
+    pub rank: i32,
+    pub scale: f32,
+    pub num_layers: usize,
+}
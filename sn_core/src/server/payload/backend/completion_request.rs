@@ -0,0 +1,52 @@
+use crate::types::completion_prompt::CompletionPrompt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Tokens generated when a request sets no `max_tokens`, base models rarely end a raw
+/// completion by themselves.
+pub const DEFAULT_COMPLETION_MAX_TOKENS: usize = 256;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CompletionRequest {
+    pub model_id: Arc<str>,
+    /// Text or token ids generated from as they are, no chat template is applied.
+    pub prompt: CompletionPrompt,
+    /// Let the tokenizer add its special tokens, e.g. the BOS, to a text prompt. True
+    /// when unset.
+    #[serde(default)]
+    pub add_special_tokens: Option<bool>,
+    /// Most tokens generated, `DEFAULT_COMPLETION_MAX_TOKENS` when unset.
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    #[serde(default)]
+    pub stream: Option<bool>,
+    #[serde(default)]
+    pub session_id: Option<i32>,
+    /// LoRA adapter loaded on the model to generate with.
+    #[serde(default)]
+    pub adapter: Option<String>,
+}
+
+impl CompletionRequest {
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens.unwrap_or(DEFAULT_COMPLETION_MAX_TOKENS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_tokens_defaults_to_a_limit() {
+        let req: CompletionRequest =
+            serde_json::from_str(r#"{"model_id": "1", "prompt": "Once upon a time"}"#).unwrap();
+        assert_eq!(req.max_tokens(), DEFAULT_COMPLETION_MAX_TOKENS);
+
+        let req: CompletionRequest = serde_json::from_str(
+            r#"{"model_id": "1", "prompt": "Once upon a time", "max_tokens": 32}"#,
+        )
+        .unwrap();
+        assert_eq!(req.max_tokens(), 32);
+    }
+}
@@ -0,0 +1,11 @@
+use crate::types::message_stats::MessageStats;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CompletionResponse {
+    pub model_id: Arc<str>,
+    /// Generated text, without the prompt.
+    pub text: String,
+    pub stats: Option<MessageStats>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContinueMessageRequest {
+    /// Stored assistant message to continue, e.g. one cut short by `max_tokens`.
+    pub message_id: i32,
+    /// Model to continue with, the one that wrote the message when unset.
+    #[serde(default)]
+    pub model_id: Option<Arc<str>>,
+    #[serde(default)]
+    pub stream: Option<bool>,
+    /// LoRA adapter loaded on the model to generate with.
+    #[serde(default)]
+    pub adapter: Option<String>,
+    /// Passed to the chat template, Qwen3 answers without reasoning when false.
+    #[serde(default)]
+    pub enable_thinking: Option<bool>,
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+}
@@ -0,0 +1,10 @@
+use crate::types::model_quantization::ModelQuantization;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConvertModelRequest {
+    pub model_name: String,
+    /// Name of the converted model, defaults to `{model_name}-{bits}bit`.
+    pub output_name: Option<String>,
+    pub quantization: ModelQuantization,
+}
@@ -0,0 +1,10 @@
+use crate::types::model_quantization::ModelQuantization;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConvertModelResponse {
+    pub model_name: String,
+    pub path: String,
+    pub quantization: ModelQuantization,
+    pub num_shards: usize,
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DriverRequest {
+    /// Model in the driver directory, or alias or id of an installed model.
+    pub name: String,
+}
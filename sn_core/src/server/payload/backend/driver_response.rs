@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DriverResponse {
+    pub name: String,
+    pub path: String,
+    pub embedding_dim: usize,
+    /// Dimension of the previous driver when it differs, stored embeddings need to be
+    /// computed again.
+    pub previous_embedding_dim: Option<usize>,
+}
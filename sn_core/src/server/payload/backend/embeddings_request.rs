@@ -0,0 +1,9 @@
+use crate::types::embedding_options::EmbeddingOptions;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmbeddingsRequest {
+    pub input: Vec<String>,
+    #[serde(flatten)]
+    pub options: EmbeddingOptions,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmbeddingData {
+    /// Position of the input in the request.
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmbeddingsResponse {
+    /// Driver model that produced the embeddings.
+    pub model: String,
+    pub dimensions: usize,
+    pub data: Vec<EmbeddingData>,
+}
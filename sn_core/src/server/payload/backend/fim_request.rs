@@ -0,0 +1,17 @@
+use crate::types::fim_prompt::FimPrompt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FimRequest {
+    pub model_id: Arc<str>,
+    #[serde(flatten)]
+    pub prompt: FimPrompt,
+    #[serde(default)]
+    pub stream: Option<bool>,
+    #[serde(default)]
+    pub session_id: Option<i32>,
+    /// LoRA adapter loaded on the model to generate with.
+    #[serde(default)]
+    pub adapter: Option<String>,
+}
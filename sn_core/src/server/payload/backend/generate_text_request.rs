@@ -1,9 +1,14 @@
+use crate::types::generate_text_options::GenerateTextOptions;
+use crate::types::message::Message;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GenerateTextRequest {
     pub model_id: Arc<str>,
+    /// Can be left empty when the turn ends with the results of tool calls.
+    #[serde(default)]
     pub prompt: String,
     #[serde(default)] // default to false if not present
     pub stream: Option<bool>,
@@ -11,4 +16,39 @@ pub struct GenerateTextRequest {
     pub conversation_id: Option<i32>,
     #[serde(default)]
     pub session_id: Option<i32>,
+    /// LoRA adapter loaded on the model to generate with.
+    #[serde(default)]
+    pub adapter: Option<String>,
+    /// JSON schemas of the tools the model may call.
+    #[serde(default)]
+    pub tools: Option<Vec<Value>>,
+    /// Passed to the chat template, Qwen3 answers without reasoning when false.
+    #[serde(default)]
+    pub enable_thinking: Option<bool>,
+    /// Messages of this turn sent before the prompt: the assistant message holding the
+    /// tool calls and the `tool` messages carrying their results.
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Start of the answer, the model continues it instead of starting a fresh turn.
+    #[serde(default)]
+    pub prefill: Option<String>,
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+}
+
+impl GenerateTextRequest {
+    pub fn options(&self) -> GenerateTextOptions {
+        GenerateTextOptions {
+            tools: self.tools.clone(),
+            enable_thinking: self.enable_thinking,
+            max_tokens: self.max_tokens,
+            continue_final_message: self.prefill().is_some(),
+        }
+    }
+
+    pub fn prefill(&self) -> Option<&str> {
+        self.prefill
+            .as_deref()
+            .filter(|prefill| !prefill.is_empty())
+    }
 }
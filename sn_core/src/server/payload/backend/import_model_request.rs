@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ImportModelRequest {
+    /// Hugging Face repository id, e.g. `Qwen/Qwen3-1.7B-MLX-4bit`.
+    pub repo_id: String,
+    /// Name the model is installed under, defaults to the repository name.
+    pub alias: Option<String>,
+    /// Branch, tag or commit hash of the cached snapshot, defaults to `main`.
+    pub revision: Option<String>,
+}
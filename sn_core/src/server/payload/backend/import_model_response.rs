@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ImportModelResponse {
+    pub model_name: String,
+    pub path: String,
+    pub snapshot_path: String,
+    pub num_files: usize,
+}
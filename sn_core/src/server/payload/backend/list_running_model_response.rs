@@ -1,7 +1,9 @@
+use crate::types::model_quantization::ModelQuantization;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ListRunningModelResponse {
     pub name: String,
     pub id: String,
+    pub quantization: Option<ModelQuantization>,
 }
@@ -1,8 +1,26 @@
+pub mod adapter_request;
+pub mod adapter_response;
+pub mod completion_request;
+pub mod completion_response;
+pub mod continue_message_request;
+pub mod convert_model_request;
+pub mod convert_model_response;
 pub mod create_session_request;
+pub mod driver_request;
+pub mod driver_response;
+pub mod embeddings_request;
+pub mod embeddings_response;
+pub mod fim_request;
 pub mod generate_text_request;
 pub mod generate_text_response;
+pub mod import_model_request;
+pub mod import_model_response;
 pub mod list_running_model_response;
+pub mod rerank_request;
+pub mod rerank_response;
 pub mod run_model_metadata_response_sse;
 pub mod run_model_request;
 pub mod run_model_response;
+pub mod score_request;
+pub mod score_response;
 pub mod text_generated_metadata_response_sse;
@@ -0,0 +1,12 @@
+use crate::types::embedding_options::EmbeddingOptions;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RerankRequest {
+    pub query: String,
+    pub documents: Vec<String>,
+    /// Number of documents returned, all of them when unset.
+    pub top_n: Option<usize>,
+    #[serde(flatten)]
+    pub options: EmbeddingOptions,
+}
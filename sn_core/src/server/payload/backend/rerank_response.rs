@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RerankResult {
+    /// Position of the document in the request.
+    pub index: usize,
+    pub score: f32,
+    pub document: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RerankResponse {
+    /// Driver model that scored the documents.
+    pub model: String,
+    pub results: Vec<RerankResult>,
+}
+
+impl RerankResponse {
+    /// Documents sorted by score, best first, keeping the `top_n` first.
+    pub fn rank(
+        model: String,
+        documents: Vec<String>,
+        scores: &[f32],
+        top_n: Option<usize>,
+    ) -> RerankResponse {
+        let mut results: Vec<RerankResult> = documents
+            .into_iter()
+            .zip(scores.iter().copied())
+            .enumerate()
+            .map(|(index, (document, score))| RerankResult {
+                index,
+                score,
+                document,
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        if let Some(top_n) = top_n {
+            results.truncate(top_n);
+        }
+        RerankResponse { model, results }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_sorts_by_score() {
+        let documents = vec![
+            "Paris is in France.".to_string(),
+            "Beijing is the capital of China.".to_string(),
+            "China is in Asia.".to_string(),
+        ];
+        let response =
+            RerankResponse::rank("driver".to_string(), documents, &[0.1, 0.8, 0.5], Some(2));
+        let indices: Vec<usize> = response.results.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(
+            response.results[0].document,
+            "Beijing is the capital of China."
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{ErrorCore, Result};
+use crate::types::model_quantization::ModelQuantization;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -6,6 +7,9 @@ pub enum RunModelRequest {
     Start {
         model_name: String,
         stream: Option<bool>,
+        /// Quantize a full precision checkpoint in memory once loaded.
+        #[serde(default)]
+        quantization: Option<ModelQuantization>,
     },
     Stop {
         id: String,
@@ -20,6 +24,13 @@ impl RunModelRequest {
         }
     }
 
+    pub fn get_quantization(&self) -> Option<ModelQuantization> {
+        match self {
+            RunModelRequest::Start { quantization, .. } => *quantization,
+            RunModelRequest::Stop { .. } => None,
+        }
+    }
+
     pub fn get_id(&self) -> Result<String> {
         match self {
             RunModelRequest::Start { model_name, .. } => Err(ErrorCore::InvalidAction(format!(
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScoreRequest {
+    pub model_id: Arc<str>,
+    /// Text the continuations are conditioned on, may be empty to score them alone.
+    #[serde(default)]
+    pub context: String,
+    pub continuations: Vec<String>,
+    /// LoRA adapter loaded on the model to score with.
+    #[serde(default)]
+    pub adapter: Option<String>,
+}
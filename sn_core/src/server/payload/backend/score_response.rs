@@ -0,0 +1,9 @@
+use crate::types::continuation_score::ContinuationScore;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScoreResponse {
+    pub model_id: Arc<str>,
+    pub scores: Vec<ContinuationScore>,
+}
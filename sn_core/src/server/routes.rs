@@ -27,12 +27,14 @@ impl BackendApiSession {
 #[derive(Debug, Clone)]
 pub enum BackendApiMessage {
     Generate,
+    Continue,
 }
 
 impl BackendApiMessage {
     pub fn path(&self) -> ApiPath {
         match self {
             BackendApiMessage::Generate => ApiPath::Static("/v1/message/generate"),
+            BackendApiMessage::Continue => ApiPath::Static("/v1/message/continue"),
         }
     }
 }
@@ -60,6 +62,9 @@ pub enum BackendApiModel {
     ListRunning,
     Run,
     Stop,
+    Convert,
+    Import,
+    Driver,
 }
 
 impl BackendApiModel {
@@ -69,6 +74,65 @@ impl BackendApiModel {
             BackendApiModel::ListRunning => ApiPath::Static("/v1/models/ps"),
             BackendApiModel::Run => ApiPath::Static("/v1/models/run"),
             BackendApiModel::Stop => ApiPath::Static("/v1/models/stop"),
+            BackendApiModel::Convert => ApiPath::Static("/v1/models/convert"),
+            BackendApiModel::Import => ApiPath::Static("/v1/models/import"),
+            BackendApiModel::Driver => ApiPath::Static("/v1/models/driver"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BackendApiEmbedding {
+    Embeddings,
+    Rerank,
+}
+
+impl BackendApiEmbedding {
+    pub fn path(&self) -> ApiPath {
+        match self {
+            BackendApiEmbedding::Embeddings => ApiPath::Static("/v1/embeddings"),
+            BackendApiEmbedding::Rerank => ApiPath::Static("/v1/rerank"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BackendApiCompletion {
+    Completions,
+    Fim,
+    Score,
+}
+
+impl BackendApiCompletion {
+    pub fn path(&self) -> ApiPath {
+        match self {
+            BackendApiCompletion::Completions => ApiPath::Static("/v1/completions"),
+            BackendApiCompletion::Fim => ApiPath::Static("/v1/completions/fim"),
+            BackendApiCompletion::Score => ApiPath::Static("/v1/score"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BackendApiAdapter {
+    List,
+    Load,
+    Unload,
+}
+
+impl BackendApiAdapter {
+    pub fn path(&self, model_id: Option<&str>) -> ApiPath {
+        let model_id = model_id.unwrap_or("{model_id}");
+        match self {
+            BackendApiAdapter::List => {
+                ApiPath::Dynamic(format!("/v1/models/{}/adapters", model_id))
+            }
+            BackendApiAdapter::Load => {
+                ApiPath::Dynamic(format!("/v1/models/{}/adapters/load", model_id))
+            }
+            BackendApiAdapter::Unload => {
+                ApiPath::Dynamic(format!("/v1/models/{}/adapters/unload", model_id))
+            }
         }
     }
 }
@@ -80,7 +144,7 @@ pub fn print_all_backend_api_paths() {
     }
 
     // Messages
-    for message in [BackendApiMessage::Generate].iter() {
+    for message in [BackendApiMessage::Generate, BackendApiMessage::Continue].iter() {
         println!("/api/{}", message.path().as_str());
     }
 
@@ -90,12 +154,42 @@ pub fn print_all_backend_api_paths() {
         BackendApiModel::ListRunning,
         BackendApiModel::Run,
         BackendApiModel::Stop,
+        BackendApiModel::Convert,
+        BackendApiModel::Import,
+        BackendApiModel::Driver,
     ]
     .iter()
     {
         println!("/api/{}", model.path().as_str());
     }
 
+    // Embeddings
+    for embedding in [BackendApiEmbedding::Embeddings, BackendApiEmbedding::Rerank].iter() {
+        println!("/api/{}", embedding.path().as_str());
+    }
+
+    // Completions
+    for completion in [
+        BackendApiCompletion::Completions,
+        BackendApiCompletion::Fim,
+        BackendApiCompletion::Score,
+    ]
+    .iter()
+    {
+        println!("/api/{}", completion.path().as_str());
+    }
+
+    // Adapters
+    for adapter in [
+        BackendApiAdapter::List,
+        BackendApiAdapter::Load,
+        BackendApiAdapter::Unload,
+    ]
+    .iter()
+    {
+        println!("/api/{}", adapter.path(None).as_str());
+    }
+
     // Conversations
     for conversation in [BackendConversationApi::List].iter() {
         println!("/api/{}", conversation.path(None).as_str());
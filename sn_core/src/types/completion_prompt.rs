@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Prompt of a raw completion, generated from without any chat template: either text
+/// or the ids it was already tokenized to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CompletionPrompt {
+    Text(String),
+    Ids(Vec<u32>),
+}
+
+impl CompletionPrompt {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            CompletionPrompt::Text(text) => text.is_empty(),
+            CompletionPrompt::Ids(ids) => ids.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_prompt_text_or_ids() {
+        let prompt: CompletionPrompt = serde_json::from_str("\"Once upon a time\"").unwrap();
+        assert_eq!(
+            prompt,
+            CompletionPrompt::Text("Once upon a time".to_string())
+        );
+        let prompt: CompletionPrompt = serde_json::from_str("[9707, 11, 1879]").unwrap();
+        assert_eq!(prompt, CompletionPrompt::Ids(vec![9707, 11, 1879]));
+        assert!(CompletionPrompt::Ids(vec![]).is_empty());
+    }
+}
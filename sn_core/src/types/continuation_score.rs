@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TokenLogprob {
+    pub id: u32,
+    pub token: String,
+    /// Natural log of the probability of the token given the ones before it.
+    pub logprob: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ContinuationScore {
+    /// Position of the continuation in the request.
+    pub index: usize,
+    pub tokens: Vec<TokenLogprob>,
+    /// Log-likelihood of the continuation, the sum over its tokens.
+    pub logprob: f32,
+    /// Each token was the most likely one, what multiple-choice accuracy counts.
+    pub greedy: bool,
+    pub perplexity: f32,
+}
+
+impl ContinuationScore {
+    pub fn new(index: usize, tokens: Vec<TokenLogprob>, greedy: bool) -> ContinuationScore {
+        let logprob: f32 = tokens.iter().map(|token| token.logprob).sum();
+        let perplexity = match tokens.len() {
+            0 => 1.0,
+            len => (-logprob / len as f32).exp(),
+        };
+        ContinuationScore {
+            index,
+            tokens,
+            logprob,
+            greedy,
+            perplexity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continuation_score_totals() {
+        let tokens = vec![
+            TokenLogprob {
+                id: 12366,
+                token: " Paris".to_string(),
+                logprob: -0.5,
+            },
+            TokenLogprob {
+                id: 13,
+                token: ".".to_string(),
+                logprob: -1.5,
+            },
+        ];
+        let score = ContinuationScore::new(1, tokens, true);
+        assert_eq!(score.logprob, -2.0);
+        assert!((score.perplexity - 1.0_f32.exp()).abs() < 1e-6);
+
+        let score = ContinuationScore::new(0, Vec::new(), true);
+        assert_eq!((score.logprob, score.perplexity), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+/// Instruction prepended to queries when none is given, documents get none.
+pub const DEFAULT_QUERY_INSTRUCTION: &str =
+    "Instruct: Given a web search query, retrieve relevant passages that answer the query\nQuery: ";
+
+/// How the hidden states of a sequence are reduced to a single vector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingPooling {
+    /// Hidden state of the last non padding token, for decoder models.
+    #[default]
+    Last,
+    /// Average of the hidden states of the non padding tokens.
+    Mean,
+    /// Hidden state of the first token, for encoder models.
+    Cls,
+}
+
+/// Whether the inputs are searched for or searched in, which picks the instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingInputType {
+    Query,
+    #[default]
+    Document,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingOptions {
+    pub pooling: EmbeddingPooling,
+    /// L2 normalise the vectors, after truncation.
+    pub normalize: bool,
+    /// Keep only the first `dimensions` values (Matryoshka), the full vector when unset.
+    pub dimensions: Option<usize>,
+    /// Tokens kept per input, below the context of the model.
+    pub max_length: Option<usize>,
+    pub input_type: EmbeddingInputType,
+    pub query_instruction: Option<String>,
+    pub document_instruction: Option<String>,
+}
+
+impl Default for EmbeddingOptions {
+    fn default() -> Self {
+        EmbeddingOptions {
+            pooling: EmbeddingPooling::default(),
+            normalize: true,
+            dimensions: None,
+            max_length: None,
+            input_type: EmbeddingInputType::default(),
+            query_instruction: Some(DEFAULT_QUERY_INSTRUCTION.to_string()),
+            document_instruction: None,
+        }
+    }
+}
+
+impl EmbeddingOptions {
+    pub fn instruction(&self, input_type: EmbeddingInputType) -> Option<&str> {
+        match input_type {
+            EmbeddingInputType::Query => self.query_instruction.as_deref(),
+            EmbeddingInputType::Document => self.document_instruction.as_deref(),
+        }
+    }
+
+    /// `inputs` with the instruction of `input_type` prepended.
+    pub fn apply_instruction(
+        &self,
+        inputs: &[String],
+        input_type: EmbeddingInputType,
+    ) -> Vec<String> {
+        match self.instruction(input_type) {
+            Some(instruction) => inputs
+                .iter()
+                .map(|input| format!("{}{}", instruction, input))
+                .collect(),
+            None => inputs.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_options_defaults() {
+        let options: EmbeddingOptions =
+            serde_json::from_str(r#"{"pooling": "mean", "dimensions": 256}"#).unwrap();
+        assert_eq!(options.pooling, EmbeddingPooling::Mean);
+        assert_eq!(options.dimensions, Some(256));
+        assert!(options.normalize);
+
+        let inputs = vec!["Explain gravity".to_string()];
+        assert_eq!(
+            options.apply_instruction(&inputs, EmbeddingInputType::Query)[0],
+            format!("{}Explain gravity", DEFAULT_QUERY_INSTRUCTION)
+        );
+        assert_eq!(
+            options.apply_instruction(&inputs, EmbeddingInputType::Document),
+            inputs
+        );
+    }
+}
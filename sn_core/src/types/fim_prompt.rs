@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Code around the cursor of a fill-in-the-middle completion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FimPrompt {
+    /// Code before the cursor.
+    pub prefix: String,
+    /// Code after the cursor.
+    #[serde(default)]
+    pub suffix: String,
+    /// Start of the infill already written, the generation continues from it.
+    #[serde(default)]
+    pub middle: Option<String>,
+}
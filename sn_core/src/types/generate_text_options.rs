@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerateTextOptions {
+    /// JSON schemas of the tools the model may call.
+    pub tools: Option<Vec<Value>>,
+    /// Passed to the chat template, Qwen3 answers without reasoning when false.
+    pub enable_thinking: Option<bool>,
+    /// Most tokens to generate, the answer is cut short past it.
+    pub max_tokens: Option<usize>,
+    /// Continue the assistant message ending the conversation instead of starting a new
+    /// turn.
+    pub continue_final_message: bool,
+}
@@ -1,5 +1,6 @@
 use crate::error::{ErrorCore, Result};
 use crate::types::message_stats::MessageStats;
+use crate::types::tool::ToolCall;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use tracing::error;
@@ -12,11 +13,20 @@ pub struct Message {
     #[builder(default)]
     pub conversation_id: Option<i32>,
     pub content: String,
+    /// What the assistant wrote while reasoning, kept out of `content`.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
     pub role: MessageRole,
     #[builder(default)]
     pub stats: Option<MessageStats>,
     #[builder(default)]
     pub embeddings: Vec<f32>,
+    /// Tools the assistant called, left out when there is none as templates test for
+    /// the presence of the key.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
 }
 
 impl Message {
@@ -49,6 +59,9 @@ pub enum MessageRole {
     Assistant,
     #[serde(rename = "system")]
     System,
+    /// Result of a tool call, sent back to the model.
+    #[serde(rename = "tool")]
+    Tool,
 }
 
 impl MessageRole {
@@ -57,6 +70,7 @@ impl MessageRole {
             MessageRole::User => String::from("user"),
             MessageRole::Assistant => String::from("assistant"),
             MessageRole::System => String::from("system"),
+            MessageRole::Tool => String::from("tool"),
         }
     }
 }
@@ -68,6 +82,7 @@ impl TryFrom<&str> for MessageRole {
             "user" => Ok(MessageRole::User),
             "assistant" => Ok(MessageRole::Assistant),
             "system" => Ok(MessageRole::System),
+            "tool" => Ok(MessageRole::Tool),
             _ => {
                 let format_err = format!("Unknown message role: {}", role);
                 error!(format_err);
@@ -108,6 +123,7 @@ mod tests {
         assert_eq!(MessageRole::User.to_string(), "user");
         assert_eq!(MessageRole::Assistant.to_string(), "assistant");
         assert_eq!(MessageRole::System.to_string(), "system");
+        assert_eq!(MessageRole::Tool.to_string(), "tool");
     }
 
     #[test]
@@ -121,6 +137,7 @@ mod tests {
             MessageRole::try_from("system").unwrap(),
             MessageRole::System
         );
+        assert_eq!(MessageRole::try_from("tool").unwrap(), MessageRole::Tool);
     }
 
     #[test]
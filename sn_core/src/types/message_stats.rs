@@ -5,6 +5,19 @@ pub struct MessageStats {
     pub generation_duration: f64,
     pub prompt_tps: f64,
     pub generation_tps: f64,
+    #[serde(default)]
+    pub prompt_tokens: usize,
+    #[serde(default)]
+    pub generated_tokens: usize,
+    /// Time to the first token, in seconds.
+    #[serde(default)]
+    pub prefill_duration: f64,
+    /// Peak memory used by MLX during the generation, in bytes.
+    #[serde(default)]
+    pub peak_memory: u64,
+    /// Size of the KV cache once generated, in bytes.
+    #[serde(default)]
+    pub cache_bytes: u64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -12,6 +25,9 @@ pub struct MessageStatsBuilder {
     total_generated_tokens: f64,
     generation_duration: f64,
     prefill_duration: f64,
+    prompt_tokens: usize,
+    peak_memory: u64,
+    cache_bytes: u64,
 }
 
 impl MessageStatsBuilder {
@@ -40,18 +56,56 @@ impl MessageStatsBuilder {
         self
     }
 
+    pub fn with_prompt_tokens(&mut self, prompt_tokens: usize) -> &mut MessageStatsBuilder {
+        self.prompt_tokens = prompt_tokens;
+        self
+    }
+
+    pub fn with_peak_memory(&mut self, peak_memory: u64) -> &mut MessageStatsBuilder {
+        self.peak_memory = peak_memory;
+        self
+    }
+
+    pub fn with_cache_bytes(&mut self, cache_bytes: u64) -> &mut MessageStatsBuilder {
+        self.cache_bytes = cache_bytes;
+        self
+    }
+
     pub fn build(&self) -> MessageStats {
         let generation_tps = self.total_generated_tokens / self.generation_duration;
 
         let prompt_tps = match self.prefill_duration {
             0.0 => 0.0,
-            duration => self.total_generated_tokens / duration,
+            duration => self.prompt_tokens as f64 / duration,
         };
 
         MessageStats {
             generation_tps,
             prompt_tps,
             generation_duration: self.generation_duration,
+            prompt_tokens: self.prompt_tokens,
+            generated_tokens: self.total_generated_tokens as usize,
+            prefill_duration: self.prefill_duration,
+            peak_memory: self.peak_memory,
+            cache_bytes: self.cache_bytes,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_tps_counts_prompt_tokens() {
+        let stats = MessageStatsBuilder::new()
+            .with_prompt_tokens(512)
+            .with_prefill_duration(0.5)
+            .with_total_generated_tokens(64.0)
+            .with_generation_duration(2.0)
+            .build();
+        assert_eq!(stats.prompt_tps, 1024.0);
+        assert_eq!(stats.generation_tps, 32.0);
+        assert_eq!(stats.generated_tokens, 64);
+    }
+}
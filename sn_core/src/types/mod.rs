@@ -1,9 +1,16 @@
 pub mod ann_item;
+pub mod completion_prompt;
+pub mod continuation_score;
 pub mod conversation;
 pub mod document;
+pub mod embedding_options;
+pub mod fim_prompt;
+pub mod generate_text_options;
 pub mod message;
 pub mod message_pair;
 pub mod message_stats;
+pub mod model_info;
+pub mod model_quantization;
 pub mod session;
 pub mod stream_data;
 pub mod tool;
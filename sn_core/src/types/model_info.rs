@@ -0,0 +1,32 @@
+use crate::types::model_quantization::ModelQuantization;
+use serde::{Deserialize, Serialize};
+
+/// An installed model as recorded in the model registry.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ModelInfo {
+    /// Assigned once on registration and kept across restarts.
+    pub id: String,
+    /// Directory name under the models path, used to run the model.
+    pub alias: String,
+    pub path: String,
+    pub architecture: String,
+    /// Number of parameters of the unquantized model.
+    pub parameters: u64,
+    pub quantization: Option<ModelQuantization>,
+    pub context_length: Option<i32>,
+    pub has_chat_template: bool,
+}
+
+impl ModelInfo {
+    /// Parameter count rounded for display, e.g. `1.7B` or `596M`.
+    pub fn parameters_label(&self) -> String {
+        let parameters = self.parameters as f64;
+        if parameters >= 1e9 {
+            format!("{:.1}B", parameters / 1e9)
+        } else if parameters >= 1e6 {
+            format!("{:.0}M", parameters / 1e6)
+        } else {
+            format!("{}", self.parameters)
+        }
+    }
+}
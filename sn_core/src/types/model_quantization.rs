@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const SUPPORTED_QUANTIZATION_BITS: [i32; 5] = [2, 3, 4, 6, 8];
+pub const SUPPORTED_QUANTIZATION_GROUP_SIZES: [i32; 3] = [32, 64, 128];
+
+/// Affine quantization applied to the linear and embedding layers of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelQuantization {
+    pub bits: i32,
+    pub group_size: i32,
+}
+
+impl ModelQuantization {
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_QUANTIZATION_BITS.contains(&self.bits)
+            && SUPPORTED_QUANTIZATION_GROUP_SIZES.contains(&self.group_size)
+    }
+}
+
+impl fmt::Display for ModelQuantization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}bit-g{}", self.bits, self.group_size)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::server::payload::backend::run_model_metadata_response_sse::RunModelMetadataResponseSSE;
 use crate::server::payload::backend::run_model_response::RunModelResponseSSE;
 use crate::server::payload::backend::text_generated_metadata_response_sse::TextGeneratedMetadataResponseSSE;
+use crate::types::tool::ToolCall;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::error;
@@ -12,6 +13,9 @@ pub enum StreamDataContent {
     RunModelResponseSSE(RunModelResponseSSE),
     TextGeneratedMetadataResponseSSE(TextGeneratedMetadataResponseSSE),
     RunModelMetadataResponseSSE(RunModelMetadataResponseSSE),
+    ToolCalls(Vec<ToolCall>),
+    /// Text the model wrote while reasoning, apart from the answer.
+    Reasoning(String),
 }
 
 impl Default for StreamDataContent {
@@ -68,6 +72,20 @@ impl StreamData {
         }
     }
 
+    pub fn for_reasoning(content: String) -> Self {
+        StreamData {
+            content: StreamDataContent::Reasoning(content),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_tool_calls(content: Vec<ToolCall>) -> Self {
+        StreamData {
+            content: StreamDataContent::ToolCalls(content),
+            ..Default::default()
+        }
+    }
+
     pub fn to_json(&self) -> Value {
         serde_json::to_value(self).unwrap_or(Value::Null)
     }
@@ -1,18 +1,40 @@
-/*
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 #[derive(Debug, Clone)]
+pub enum Tool {
+    Schema(Value),
+    Function(fn() -> Value), // Placeholder for runtime callables
+}
+
+/// A call to one of the tools given to the model, in the shape chat templates expect
+/// on the `tool_calls` of an assistant message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolCall {
+    #[serde(rename = "type", default = "default_tool_call_type")]
+    pub kind: String,
     pub function: ToolFunction,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolFunction {
     pub name: String,
-    pub arguments: HashMap<String, String>,
-}*/
-use serde_json::Value;
+    #[serde(default)]
+    pub arguments: Value,
+}
 
-#[derive(Debug, Clone)]
-pub enum Tool {
-    Schema(Value),
-    Function(fn() -> Value), // Placeholder for runtime callables
+fn default_tool_call_type() -> String {
+    "function".to_string()
+}
+
+impl ToolCall {
+    pub fn new(name: &str, arguments: Value) -> ToolCall {
+        ToolCall {
+            kind: default_tool_call_type(),
+            function: ToolFunction {
+                name: name.to_string(),
+                arguments,
+            },
+        }
+    }
 }
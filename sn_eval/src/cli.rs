@@ -0,0 +1,97 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Evaluate installed models on local JSONL task files.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// JSONL task files, each named after its file.
+    #[arg(required = true)]
+    pub tasks: Vec<PathBuf>,
+    /// Alias or id of an installed model, repeat it to compare models. A `name@bits`
+    /// suffix quantizes this model only, so one checkpoint can be compared at several bits.
+    #[arg(short, long = "model", required = true, value_parser = parse_model_spec)]
+    pub models: Vec<ModelSpec>,
+    /// Quantize full precision models in memory to this number of bits, unless their
+    /// `--model` sets other bits.
+    #[arg(long)]
+    pub bits: Option<i32>,
+    #[arg(long, default_value_t = 64)]
+    pub group_size: i32,
+    /// Evaluate the first samples of each task only.
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+    /// Most tokens generated for a generative sample.
+    #[arg(long, default_value_t = 256)]
+    pub max_tokens: usize,
+    /// Let reasoning models think before answering generative samples.
+    #[arg(long)]
+    pub thinking: bool,
+    #[arg(short, long, default_value = "eval_report.json")]
+    pub output: PathBuf,
+}
+
+/// A model to evaluate, with the bits it is quantized to on load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelSpec {
+    pub name: String,
+    pub bits: Option<i32>,
+}
+
+fn parse_model_spec(value: &str) -> Result<ModelSpec, String> {
+    let (name, bits) = match value.rsplit_once('@') {
+        Some((name, bits)) => {
+            let bits = bits
+                .parse::<i32>()
+                .map_err(|_| format!("invalid bits in {}, expected name@bits", value))?;
+            (name, Some(bits))
+        }
+        None => (value, None),
+    };
+    if name.is_empty() {
+        return Err(format!("missing model name in {}", value));
+    }
+    Ok(ModelSpec {
+        name: name.to_string(),
+        bits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_model_spec() {
+        let spec = |name: &str, bits: Option<i32>| ModelSpec {
+            name: name.to_string(),
+            bits,
+        };
+        assert_eq!(parse_model_spec("qwen3-8b"), Ok(spec("qwen3-8b", None)));
+        assert_eq!(
+            parse_model_spec("qwen3-8b@4"),
+            Ok(spec("qwen3-8b", Some(4)))
+        );
+        assert!(parse_model_spec("qwen3-8b@four").is_err());
+        assert!(parse_model_spec("@4").is_err());
+
+        let cli = Cli::try_parse_from([
+            "sn_eval",
+            "task.jsonl",
+            "-m",
+            "qwen3-8b",
+            "-m",
+            "qwen3-8b@4",
+            "--bits",
+            "8",
+        ])
+        .unwrap();
+        assert_eq!(
+            (cli.models, cli.bits),
+            (
+                vec![spec("qwen3-8b", None), spec("qwen3-8b", Some(4))],
+                Some(8)
+            )
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+pub type Result<T> = std::result::Result<T, ErrorEval>;
+
+#[derive(Error, Debug)]
+pub enum ErrorEval {
+    #[error(transparent)]
+    Core(#[from] sn_core::error::ErrorCore),
+
+    #[error(transparent)]
+    Inference(#[from] sn_inference::error::Error),
+
+    #[error("I/O error: {0}")]
+    IO(#[from] std::io::Error),
+
+    #[error("Failed to write report: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid sample at {0}:{1}: {2}")]
+    InvalidSample(String, usize, String),
+
+    #[error("Model {0} is not installed")]
+    ModelNotInstalled(String),
+}
@@ -0,0 +1,77 @@
+use crate::error::Result;
+use crate::report::TaskReport;
+use crate::task::{Sample, Task, TaskKind, best_choices, matches_answer};
+use sn_core::types::conversation::Conversation;
+use sn_core::types::generate_text_options::GenerateTextOptions;
+use sn_core::types::message::{MessageBuilder, MessageRole};
+use sn_inference::runner::Runner;
+use std::time::Instant;
+
+pub struct Evaluator<'a> {
+    runner: &'a Runner,
+    model_id: String,
+    options: GenerateTextOptions,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(runner: &'a Runner, model_id: String, options: GenerateTextOptions) -> Self {
+        Evaluator {
+            runner,
+            model_id,
+            options,
+        }
+    }
+
+    pub fn evaluate(&self, task: &Task) -> Result<TaskReport> {
+        let start = Instant::now();
+        let mut correct = 0;
+        let mut correct_norm = 0;
+        for sample in &task.samples {
+            let (is_correct, is_correct_norm) = self.evaluate_sample(sample)?;
+            correct += is_correct as usize;
+            correct_norm += is_correct_norm as usize;
+        }
+        let correct_norm = (task.kind == TaskKind::MultipleChoice).then_some(correct_norm);
+        Ok(TaskReport::new(
+            task,
+            correct,
+            correct_norm,
+            start.elapsed(),
+        ))
+    }
+
+    /// Whether the sample is answered right, and right by the normalised metric.
+    fn evaluate_sample(&self, sample: &Sample) -> Result<(bool, bool)> {
+        match sample {
+            Sample::MultipleChoice {
+                context,
+                choices,
+                answer,
+            } => {
+                let scores = self.runner.score(&self.model_id, context, choices, None)?;
+                let (best, best_norm) = best_choices(&scores, choices);
+                Ok((best == *answer, best_norm == *answer))
+            }
+            Sample::Generative {
+                prompt,
+                answer,
+                pattern,
+            } => {
+                let message = MessageBuilder::default()
+                    .content(prompt.clone())
+                    .role(MessageRole::User)
+                    .build()?;
+                let result = self.runner.generate_text(
+                    &self.model_id,
+                    &Conversation::from_message(message),
+                    &self.options,
+                    None,
+                    None,
+                    None,
+                )?;
+                let is_correct = matches_answer(&result.content, answer, pattern.as_ref());
+                Ok((is_correct, is_correct))
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::cli::Cli;
+use crate::error::{ErrorEval, Result};
+use crate::evaluator::Evaluator;
+use crate::report::{ModelReport, Report};
+use crate::task::Task;
+use clap::Parser;
+use sn_core::types::generate_text_options::GenerateTextOptions;
+use sn_core::types::model_quantization::ModelQuantization;
+use sn_inference::runner::Runner;
+use std::time::Instant;
+mod cli;
+mod error;
+mod evaluator;
+mod report;
+mod task;
+
+fn main() {
+    if let Err(err) = try_main() {
+        eprintln!("❌ Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn try_main() -> Result<()> {
+    let cli = Cli::parse();
+    let tasks = cli
+        .tasks
+        .iter()
+        .map(|path| Task::load(path, cli.limit))
+        .collect::<Result<Vec<Task>>>()?;
+    let options = GenerateTextOptions {
+        enable_thinking: Some(cli.thinking),
+        max_tokens: Some(cli.max_tokens),
+        ..GenerateTextOptions::default()
+    };
+
+    let mut runner = Runner::new()?;
+    let mut models = Vec::new();
+    for spec in &cli.models {
+        let quantization = spec.bits.or(cli.bits).map(|bits| ModelQuantization {
+            bits,
+            group_size: cli.group_size,
+        });
+        models.push(evaluate_model(
+            &runner,
+            &spec.name,
+            quantization,
+            &options,
+            &tasks,
+        )?);
+        // Models are evaluated one after the other, each with the whole memory budget. This
+        // also lets the next row load the same model at other bits.
+        runner.unload_model(&models[models.len() - 1].model.id);
+    }
+
+    Report::new(models).write(&cli.output)?;
+    println!("Report written to {}", cli.output.display());
+    Ok(())
+}
+
+fn evaluate_model(
+    runner: &Runner,
+    name: &str,
+    quantization: Option<ModelQuantization>,
+    options: &GenerateTextOptions,
+    tasks: &[Task],
+) -> Result<ModelReport> {
+    let start = Instant::now();
+    let model_id = runner.load_model_name(name, quantization, None)?;
+    let load_duration_secs = start.elapsed().as_secs_f64();
+    let model = runner
+        .list_models()?
+        .into_iter()
+        .find(|model| model.id == model_id)
+        .ok_or_else(|| ErrorEval::ModelNotInstalled(name.to_string()))?;
+
+    let quantized_on_load = quantization.filter(|_| model.quantization.is_none());
+    let label = match quantized_on_load {
+        Some(quantization) => format!("{}@{}", model.alias, quantization),
+        None => model.alias.clone(),
+    };
+    let evaluator = Evaluator::new(runner, model_id, options.clone());
+    let mut reports = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let report = evaluator.evaluate(task)?;
+        println!(
+            "{} {}: {:.3} ({}/{}) in {:.1}s",
+            label,
+            report.name,
+            report.accuracy,
+            report.correct,
+            report.samples,
+            report.duration_secs
+        );
+        reports.push(report);
+    }
+    Ok(ModelReport {
+        quantized_on_load,
+        model,
+        load_duration_secs,
+        tasks: reports,
+    })
+}
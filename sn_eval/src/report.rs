@@ -0,0 +1,109 @@
+use crate::error::Result;
+use crate::task::{Task, TaskKind};
+use serde::Serialize;
+use sn_core::types::model_info::ModelInfo;
+use sn_core::types::model_quantization::ModelQuantization;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskReport {
+    pub name: String,
+    pub kind: TaskKind,
+    pub samples: usize,
+    pub correct: usize,
+    pub accuracy: f64,
+    /// Accuracy when choices are compared by log-likelihood per byte.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy_norm: Option<f64>,
+    pub duration_secs: f64,
+    pub samples_per_sec: f64,
+}
+
+impl TaskReport {
+    pub fn new(
+        task: &Task,
+        correct: usize,
+        correct_norm: Option<usize>,
+        duration: Duration,
+    ) -> TaskReport {
+        let samples = task.samples.len();
+        let ratio = |count: usize| match samples {
+            0 => 0.0,
+            samples => count as f64 / samples as f64,
+        };
+        let duration_secs = duration.as_secs_f64();
+        TaskReport {
+            name: task.name.clone(),
+            kind: task.kind,
+            samples,
+            correct,
+            accuracy: ratio(correct),
+            accuracy_norm: correct_norm.map(ratio),
+            duration_secs,
+            samples_per_sec: match duration_secs {
+                0.0 => 0.0,
+                secs => samples as f64 / secs,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelReport {
+    pub model: ModelInfo,
+    /// Quantization applied on load to a full precision model.
+    pub quantized_on_load: Option<ModelQuantization>,
+    pub load_duration_secs: f64,
+    pub tasks: Vec<TaskReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+    pub models: Vec<ModelReport>,
+}
+
+impl Report {
+    pub fn new(models: Vec<ModelReport>) -> Report {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Report { created_at, models }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Sample;
+
+    #[test]
+    fn test_task_report_accuracy() {
+        let sample = Sample::MultipleChoice {
+            context: "Sky:".to_string(),
+            choices: vec![" blue".to_string(), " green".to_string()],
+            answer: 0,
+        };
+        let task = Task {
+            name: "colors".to_string(),
+            kind: TaskKind::MultipleChoice,
+            samples: vec![sample; 4],
+        };
+        let report = TaskReport::new(&task, 3, Some(2), Duration::from_secs(2));
+        assert_eq!(report.accuracy, 0.75);
+        assert_eq!(report.accuracy_norm, Some(0.5));
+        assert_eq!(report.samples_per_sec, 2.0);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["kind"], "multiple_choice");
+    }
+}
@@ -0,0 +1,244 @@
+use crate::error::{ErrorEval, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sn_core::types::continuation_score::ContinuationScore;
+use std::fs;
+use std::path::Path;
+
+/// A line of a task file. Samples with choices are scored by log-likelihood, the
+/// others are generated and matched against their answer.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum SampleLine {
+    MultipleChoice {
+        context: String,
+        choices: Vec<String>,
+        /// Index of the right choice.
+        answer: usize,
+    },
+    Generative {
+        prompt: String,
+        answer: String,
+        /// Extracts the answer from the generation, with its first group when it has
+        /// one. The whole generation is compared otherwise.
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Sample {
+    MultipleChoice {
+        context: String,
+        choices: Vec<String>,
+        answer: usize,
+    },
+    Generative {
+        prompt: String,
+        answer: String,
+        pattern: Option<Regex>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    MultipleChoice,
+    Generative,
+}
+
+impl Sample {
+    pub fn kind(&self) -> TaskKind {
+        match self {
+            Sample::MultipleChoice { .. } => TaskKind::MultipleChoice,
+            Sample::Generative { .. } => TaskKind::Generative,
+        }
+    }
+
+    fn from_line(line: SampleLine) -> std::result::Result<Sample, String> {
+        match line {
+            SampleLine::MultipleChoice {
+                context,
+                choices,
+                answer,
+            } => {
+                if answer >= choices.len() {
+                    return Err(format!(
+                        "answer {} out of {} choices",
+                        answer,
+                        choices.len()
+                    ));
+                }
+                Ok(Sample::MultipleChoice {
+                    context,
+                    choices,
+                    answer,
+                })
+            }
+            SampleLine::Generative {
+                prompt,
+                answer,
+                pattern,
+            } => {
+                let pattern = pattern
+                    .map(|pattern| Regex::new(&pattern))
+                    .transpose()
+                    .map_err(|e| e.to_string())?;
+                Ok(Sample::Generative {
+                    prompt,
+                    answer,
+                    pattern,
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub name: String,
+    pub kind: TaskKind,
+    pub samples: Vec<Sample>,
+}
+
+impl Task {
+    /// Load a JSONL task file, named after the file. Its samples are all of one kind.
+    pub fn load(path: &Path, limit: Option<usize>) -> Result<Task> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content = fs::read_to_string(path)?;
+        let invalid = |line: usize, reason: String| {
+            ErrorEval::InvalidSample(path.display().to_string(), line, reason)
+        };
+
+        let mut samples = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if limit.is_some_and(|limit| samples.len() >= limit) {
+                break;
+            }
+            let line: SampleLine =
+                serde_json::from_str(line).map_err(|e| invalid(idx + 1, e.to_string()))?;
+            let sample = Sample::from_line(line).map_err(|e| invalid(idx + 1, e))?;
+            if let Some(first) = samples.first().map(Sample::kind)
+                && first != sample.kind()
+            {
+                let reason = "samples of another kind than the first one".to_string();
+                return Err(invalid(idx + 1, reason));
+            }
+            samples.push(sample);
+        }
+        let kind = match samples.first() {
+            Some(sample) => sample.kind(),
+            None => return Err(invalid(0, "no sample".to_string())),
+        };
+        Ok(Task {
+            name,
+            kind,
+            samples,
+        })
+    }
+}
+
+/// Whether a generation gives `answer`, once trimmed.
+pub fn matches_answer(generation: &str, answer: &str, pattern: Option<&Regex>) -> bool {
+    let generation = match pattern {
+        Some(pattern) => match pattern.captures(generation) {
+            Some(captures) => captures.get(1).or_else(|| captures.get(0)),
+            None => None,
+        }
+        .map(|found| found.as_str()),
+        None => Some(generation),
+    };
+    generation.is_some_and(|generation| generation.trim() == answer.trim())
+}
+
+/// The most likely choice, and the most likely per byte (`acc_norm`), which does not
+/// favour short choices.
+pub fn best_choices(scores: &[ContinuationScore], choices: &[String]) -> (usize, usize) {
+    let best = |score: &dyn Fn(&ContinuationScore) -> f32| {
+        scores
+            .iter()
+            .max_by(|a, b| score(a).total_cmp(&score(b)))
+            .map(|best| best.index)
+            .unwrap_or_default()
+    };
+    let by_byte = |score: &ContinuationScore| {
+        let len = choices
+            .get(score.index)
+            .map(String::len)
+            .unwrap_or_default();
+        score.logprob / len.max(1) as f32
+    };
+    (best(&|score| score.logprob), best(&by_byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_task(dir: &Path, contents: &str) -> std::path::PathBuf {
+        let path = dir.join("arithmetic.jsonl");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_generative_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_task(
+            dir.path(),
+            "{\"prompt\": \"2 + 2?\", \"answer\": \"4\", \"pattern\": \"answer is (\\\\d+)\"}\n\n{\"prompt\": \"3 + 3?\", \"answer\": \"6\"}\n",
+        );
+        let task = Task::load(&path, None).unwrap();
+        assert_eq!(
+            (task.name.as_str(), task.kind, task.samples.len()),
+            ("arithmetic", TaskKind::Generative, 2)
+        );
+        let Sample::Generative {
+            answer, pattern, ..
+        } = &task.samples[0]
+        else {
+            panic!("expected a generative sample");
+        };
+        assert_eq!(answer, "4");
+        assert!(matches_answer("The answer is 4.", answer, pattern.as_ref()));
+    }
+
+    #[test]
+    fn test_load_rejects_answer_out_of_choices() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_task(
+            dir.path(),
+            "{\"context\": \"Sky:\", \"choices\": [\" blue\"], \"answer\": 1}\n",
+        );
+        assert!(Task::load(&path, None).is_err());
+    }
+
+    #[test]
+    fn test_matches_answer() {
+        let pattern = Regex::new(r"answer is (\d+)").unwrap();
+        assert!(matches_answer("The answer is 4.", "4", Some(&pattern)));
+        assert!(!matches_answer("The answer is 5.", "4", Some(&pattern)));
+        assert!(matches_answer(" 4\n", "4", None));
+    }
+
+    #[test]
+    fn test_best_choices() {
+        let choices = vec![" a".to_string(), " much longer".to_string()];
+        let scores = vec![
+            ContinuationScore::new(0, Vec::new(), false),
+            ContinuationScore::new(1, Vec::new(), false),
+        ];
+        let scores: Vec<ContinuationScore> = scores
+            .into_iter()
+            .zip([-2.0, -3.0])
+            .map(|(score, logprob)| ContinuationScore { logprob, ..score })
+            .collect();
+        assert_eq!(best_choices(&scores, &choices), (0, 1));
+    }
+}
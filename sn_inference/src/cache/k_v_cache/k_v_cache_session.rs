@@ -4,6 +4,7 @@ use crate::cache::k_v_cache::k_v_cache::ArcCacheList;
 pub struct KvCacheSession {
     pub cache: ArcCacheList,
     pub session_id: i32,
+    pub model_id: String,
 }
 
 impl KvCacheSession {}
@@ -1,45 +1,43 @@
+use crate::chat_template::environment::create_environment;
 use crate::config::config::Config;
-use crate::error::Result;
-use minijinja::Environment;
+use crate::error::{Error, Result};
+use crate::token::token_reasoning_parser::REASONING_START;
 use serde_json::{Value, json};
 use sn_core::types::conversation::Conversation;
 use sn_core::types::document::Document;
+use sn_core::types::message::MessageRole;
 use sn_core::types::tool::Tool;
 use std::collections::HashMap;
 
-fn convert_python_to_rust_template(template: &str) -> String {
-    template
-        // remove think from template & messages (used for Qwen3)
-        .replace(
-            "message.content.split('</think>')[-1].lstrip('\\n')",
-            "(message.content | split('</think>'))[-1] | trim",
-        )
-        .replace(
-            "message.content.startswith('<tool_response>')",
-            "message.content[:15] == '<tool_response>'",
-        )
-        .replace(
-            "message.content.endswith('</tool_response>')",
-            "message.content[-16:] == '</tool_response>'",
-        )
-}
-
 #[derive(Debug, Clone)]
 pub struct ChatTemplate {
     name: String,
     template: String,
+    bos_token: Option<String>,
+    eos_token: Option<String>,
 }
 
 impl ChatTemplate {
     pub fn new(config: &Config) -> Result<Self> {
-        //chat_template: &str
-        let chat_template = config.tokenizer_custom.get_chat_template();
-        let template = convert_python_to_rust_template(chat_template);
+        let tokenizer_custom = &config.tokenizer_custom;
+        Ok(ChatTemplate::from_template(
+            tokenizer_custom.get_chat_template(),
+            tokenizer_custom.bos_token.clone(),
+            tokenizer_custom.eos_token.clone(),
+        ))
+    }
 
-        Ok(ChatTemplate {
-            template,
+    pub fn from_template(
+        template: &str,
+        bos_token: Option<String>,
+        eos_token: Option<String>,
+    ) -> Self {
+        ChatTemplate {
+            template: template.to_string(),
             name: "chat".to_string(),
-        })
+            bos_token,
+            eos_token,
+        }
     }
 
     fn render_chat_template(
@@ -47,10 +45,10 @@ impl ChatTemplate {
         conversations: &Conversation,
         tools: Option<&[Tool]>,
         documents: Option<&[Document]>,
-        add_generation_prompt: Option<bool>,
-        // other args like add_generation_prompt, kwargs could be added here
+        add_generation_prompt: bool,
+        enable_thinking: Option<bool>,
     ) -> Result<String> {
-        let mut env = Environment::new();
+        let mut env = create_environment();
         env.add_template(self.name.as_str(), self.template.as_str())?;
 
         // Compile template once
@@ -60,6 +58,12 @@ impl ChatTemplate {
         let mut context = HashMap::new();
         let messages = &conversations.messages;
         context.insert("messages", json!(messages));
+        context.insert("add_generation_prompt", json!(add_generation_prompt));
+        context.insert("bos_token", json!(self.bos_token.as_deref().unwrap_or("")));
+        context.insert("eos_token", json!(self.eos_token.as_deref().unwrap_or("")));
+        if let Some(enable_thinking) = enable_thinking {
+            context.insert("enable_thinking", json!(enable_thinking));
+        }
 
         // Tools
         if let Some(tools) = tools {
@@ -70,7 +74,7 @@ impl ChatTemplate {
                     Tool::Function(_) => None,
                 })
                 .collect();
-            context.insert("custom_tools", json!(tools_json));
+            context.insert("tools", json!(tools_json));
         }
 
         // Documents
@@ -82,22 +86,374 @@ impl ChatTemplate {
             context.insert("documents", json!(docs_json));
         }
 
-        if let Some(add_generation_prompt) = add_generation_prompt {
-            context.insert(
-                "add_generation_prompt",
-                json!({"add_generation_prompt": add_generation_prompt}),
+        Ok(template.render(context)?)
+    }
+
+    pub fn apply_chat_template(
+        &self,
+        conversations: &Conversation,
+        tools: Option<&[Tool]>,
+        documents: Option<&[Document]>,
+        enable_thinking: Option<bool>,
+    ) -> Result<String> {
+        self.render_chat_template(&conversations, tools, documents, true, enable_thinking)
+    }
+
+    /// Render the conversation up to the end of its final assistant message, leaving the
+    /// turn open for the model to continue it. As `transformers` does, the prompt is cut
+    /// right after the message content, found in the rendered text. A message cut off
+    /// while reasoning has no content yet, the reasoning is opened again instead.
+    pub fn continue_final_message(
+        &self,
+        conversations: &Conversation,
+        tools: Option<&[Tool]>,
+        documents: Option<&[Document]>,
+        enable_thinking: Option<bool>,
+    ) -> Result<String> {
+        let message = match conversations.messages.last() {
+            Some(message) if message.role == MessageRole::Assistant => message,
+            _ => {
+                return Err(Error::TemplateError(
+                    "the final message to continue is not an assistant message".to_string(),
+                ));
+            }
+        };
+        let rendered =
+            self.render_chat_template(conversations, tools, documents, false, enable_thinking)?;
+        if Self::continues_reasoning(conversations) {
+            let reasoning = message.reasoning_content.as_deref().unwrap_or_default();
+            return self.reopen_reasoning(
+                conversations,
+                &rendered,
+                reasoning.trim(),
+                tools,
+                documents,
+                enable_thinking,
             );
         }
 
-        Ok(template.render(context)?)
+        let content = message.content.as_str();
+        let trimmed = content.trim();
+        let start = match rendered.rfind(trimmed) {
+            Some(start) if !trimmed.is_empty() => start,
+            _ => {
+                return Err(Error::TemplateError(
+                    "the final message to continue is not in the rendered chat".to_string(),
+                ));
+            }
+        };
+        let mut end = start + trimmed.len();
+        // Keep the trailing spaces of the message when the template does.
+        let trailing = &content[content.trim_end().len()..];
+        if rendered[end..].starts_with(trailing) {
+            end += trailing.len();
+        }
+        Ok(rendered[..end].to_string())
     }
 
-    pub fn apply_chat_template(
+    /// Whether the final message was cut off while reasoning, before any content.
+    pub fn continues_reasoning(conversations: &Conversation) -> bool {
+        conversations.messages.last().is_some_and(|message| {
+            message.role == MessageRole::Assistant
+                && message.content.trim().is_empty()
+                && message
+                    .reasoning_content
+                    .as_deref()
+                    .is_some_and(|reasoning| !reasoning.trim().is_empty())
+        })
+    }
+
+    /// The prompt ending inside the reasoning of the final message. Templates that render
+    /// the reasoning are cut right after it, for the others the reasoning is written after
+    /// the generation prompt.
+    fn reopen_reasoning(
         &self,
         conversations: &Conversation,
+        rendered: &str,
+        reasoning: &str,
         tools: Option<&[Tool]>,
         documents: Option<&[Document]>,
+        enable_thinking: Option<bool>,
     ) -> Result<String> {
-        self.render_chat_template(&conversations, tools, documents, Some(true))
+        if let Some(start) = rendered.rfind(reasoning)
+            && rendered[..start].trim_end().ends_with(REASONING_START)
+        {
+            return Ok(rendered[..start + reasoning.len()].to_string());
+        }
+        let mut previous = conversations.clone();
+        previous.messages.pop();
+        let mut prompt =
+            self.render_chat_template(&previous, tools, documents, true, enable_thinking)?;
+        if !prompt.trim_end().ends_with(REASONING_START) {
+            prompt.push_str(REASONING_START);
+            prompt.push('\n');
+        }
+        prompt.push_str(reasoning);
+        Ok(prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sn_core::types::message::{MessageBuilder, MessageRole};
+    use sn_core::types::tool::ToolCall;
+
+    fn conversation(messages: &[(MessageRole, &str)]) -> Conversation {
+        Conversation {
+            messages: messages
+                .iter()
+                .map(|(role, content)| {
+                    MessageBuilder::default()
+                        .role(role.clone())
+                        .content(content.to_string())
+                        .build()
+                        .unwrap()
+                })
+                .collect(),
+            ..Conversation::default()
+        }
+    }
+
+    fn weather_tool() -> Tool {
+        Tool::Schema(json!({
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "description": "Get the weather of a city",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "city": { "type": "string", "description": "Name of the city" }
+                    },
+                    "required": ["city"]
+                }
+            }
+        }))
+    }
+
+    fn template(source: &str, bos_token: &str, eos_token: &str) -> ChatTemplate {
+        ChatTemplate::from_template(
+            source,
+            Some(bos_token.to_string()),
+            Some(eos_token.to_string()),
+        )
+    }
+
+    // Expected strings rendered with `transformers` `apply_chat_template`.
+    #[test]
+    fn test_llama3_template() {
+        let template = template(include_str!("_t_llama3"), "<|begin_of_text|>", "<|eot_id|>");
+        let rendered = template
+            .apply_chat_template(
+                &conversation(&[(MessageRole::User, "Hello")]),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\nCutting Knowledge Date: December 2023\nToday Date: 26 Jul 2024\n\n<|eot_id|><|start_header_id|>user<|end_header_id|>\n\nHello<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+
+        let rendered = template
+            .apply_chat_template(
+                &conversation(&[
+                    (MessageRole::System, "You are helpful."),
+                    (MessageRole::User, "What is the weather in Paris?"),
+                ]),
+                Some(&[weather_tool()]),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\nEnvironment: ipython\nCutting Knowledge Date: December 2023\nToday Date: 26 Jul 2024\n\nYou are helpful.<|eot_id|><|start_header_id|>user<|end_header_id|>\n\nGiven the following functions, please respond with a JSON for a function call with its proper arguments that best answers the given prompt.\n\nRespond in the format {\"name\": function name, \"parameters\": dictionary of argument name and its value}.Do not use variables.\n\n{\n    \"type\": \"function\",\n    \"function\": {\n        \"name\": \"get_weather\",\n        \"description\": \"Get the weather of a city\",\n        \"parameters\": {\n            \"type\": \"object\",\n            \"properties\": {\n                \"city\": {\n                    \"type\": \"string\",\n                    \"description\": \"Name of the city\"\n                }\n            },\n            \"required\": [\n                \"city\"\n            ]\n        }\n    }\n}\n\nWhat is the weather in Paris?<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_qwen3_template() {
+        let template = template(include_str!("_t_qwen3"), "", "<|im_end|>");
+        let rendered = template
+            .apply_chat_template(
+                &conversation(&[
+                    (MessageRole::System, "You are helpful."),
+                    (MessageRole::User, "Hi"),
+                    (
+                        MessageRole::Assistant,
+                        "<think>\nGreeting.\n</think>\n\nHello!",
+                    ),
+                    (MessageRole::User, "Weather in Paris?"),
+                ]),
+                Some(&[weather_tool()]),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<|im_start|>system\nYou are helpful.\n\n# Tools\n\nYou may call one or more functions to assist with the user query.\n\nYou are provided with function signatures within <tools></tools> XML tags:\n<tools>\n{\"type\": \"function\", \"function\": {\"name\": \"get_weather\", \"description\": \"Get the weather of a city\", \"parameters\": {\"type\": \"object\", \"properties\": {\"city\": {\"type\": \"string\", \"description\": \"Name of the city\"}}, \"required\": [\"city\"]}}}\n</tools>\n\nFor each function call, return a json object with function name and arguments within <tool_call></tool_call> XML tags:\n<tool_call>\n{\"name\": <function-name>, \"arguments\": <args-json-object>}\n</tool_call><|im_end|>\n<|im_start|>user\nHi<|im_end|>\n<|im_start|>assistant\nHello!<|im_end|>\n<|im_start|>user\nWeather in Paris?<|im_end|>\n<|im_start|>assistant\n"
+        );
+
+        let rendered = template
+            .apply_chat_template(
+                &conversation(&[(MessageRole::User, "Hi")]),
+                None,
+                None,
+                Some(false),
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<|im_start|>user\nHi<|im_end|>\n<|im_start|>assistant\n<think>\n\n</think>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_qwen3_template_tool_results() {
+        let template = template(include_str!("_t_qwen3"), "", "<|im_end|>");
+        let mut conversation = conversation(&[
+            (MessageRole::User, "Weather in Paris?"),
+            (MessageRole::Assistant, ""),
+            (MessageRole::Tool, "{\"temperature\": 21}"),
+        ]);
+        conversation.messages[1].tool_calls =
+            Some(vec![ToolCall::new("get_weather", json!({"city": "Paris"}))]);
+        let rendered = template
+            .apply_chat_template(&conversation, Some(&[weather_tool()]), None, None)
+            .unwrap();
+        assert!(rendered.ends_with(
+            "<|im_start|>user\nWeather in Paris?<|im_end|>\n<|im_start|>assistant\n<tool_call>\n{\"name\": \"get_weather\", \"arguments\": {\"city\": \"Paris\"}}\n</tool_call><|im_end|>\n<|im_start|>user\n<tool_response>\n{\"temperature\": 21}\n</tool_response><|im_end|>\n<|im_start|>assistant\n"
+        ));
+    }
+
+    #[test]
+    fn test_continue_final_message() {
+        let conversation = conversation(&[
+            (MessageRole::User, "Write a haiku"),
+            (MessageRole::Assistant, "Autumn moon "),
+        ]);
+        let qwen3 = template(include_str!("_t_qwen3"), "", "<|im_end|>");
+        let rendered = qwen3
+            .continue_final_message(&conversation, None, None, None)
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<|im_start|>user\nWrite a haiku<|im_end|>\n<|im_start|>assistant\nAutumn moon "
+        );
+
+        // The template trims the message, so does the prompt.
+        let llama3 = template(include_str!("_t_llama3"), "<|begin_of_text|>", "<|eot_id|>");
+        let rendered = llama3
+            .continue_final_message(&conversation, None, None, None)
+            .unwrap();
+        assert!(rendered.ends_with(
+            "Write a haiku<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\nAutumn moon"
+        ));
+
+        let rendered = llama3.continue_final_message(
+            &self::conversation(&[(MessageRole::User, "Write a haiku")]),
+            None,
+            None,
+            None,
+        );
+        assert!(rendered.is_err());
+    }
+
+    #[test]
+    fn test_continue_final_message_cut_off_while_reasoning() {
+        let mut conversation = conversation(&[
+            (MessageRole::User, "Write a haiku"),
+            (MessageRole::Assistant, ""),
+        ]);
+        conversation.messages[1].reasoning_content = Some("Five syllables, then".to_string());
+        assert!(ChatTemplate::continues_reasoning(&conversation));
+
+        let qwen3 = template(include_str!("_t_qwen3"), "", "<|im_end|>");
+        let rendered = qwen3
+            .continue_final_message(&conversation, None, None, None)
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<|im_start|>user\nWrite a haiku<|im_end|>\n<|im_start|>assistant\n<think>\nFive syllables, then"
+        );
+
+        // The template leaves the reasoning out, it is written after the generation prompt.
+        let llama3 = template(include_str!("_t_llama3"), "<|begin_of_text|>", "<|eot_id|>");
+        let rendered = llama3
+            .continue_final_message(&conversation, None, None, None)
+            .unwrap();
+        assert!(rendered.ends_with(
+            "Write a haiku<|eot_id|><|start_header_id|>assistant<|end_header_id|>\n\n<think>\nFive syllables, then"
+        ));
+
+        conversation.messages[1].reasoning_content = None;
+        assert!(!ChatTemplate::continues_reasoning(&conversation));
+        assert!(
+            qwen3
+                .continue_final_message(&conversation, None, None, None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_mistral_template() {
+        let template = template(include_str!("_t_mistral"), "<s>", "</s>");
+        let rendered = template
+            .apply_chat_template(
+                &conversation(&[
+                    (MessageRole::User, "Hello"),
+                    (MessageRole::Assistant, "Hi"),
+                    (MessageRole::User, "How are you?"),
+                ]),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<s>[INST] Hello [/INST]Hi</s>[INST] How are you? [/INST]"
+        );
+
+        let rendered = template.apply_chat_template(
+            &conversation(&[(MessageRole::User, "Hello"), (MessageRole::User, "Again")]),
+            None,
+            None,
+            None,
+        );
+        assert!(rendered.is_err());
+    }
+
+    #[test]
+    fn test_gemma_template() {
+        let template = template(include_str!("_t_gemma"), "<bos>", "<eos>");
+        let rendered = template
+            .apply_chat_template(
+                &conversation(&[
+                    (MessageRole::User, "Hello"),
+                    (MessageRole::Assistant, "Hi there "),
+                    (MessageRole::User, "How are you?"),
+                ]),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "<bos><start_of_turn>user\nHello<end_of_turn>\n<start_of_turn>model\nHi there<end_of_turn>\n<start_of_turn>user\nHow are you?<end_of_turn>\n<start_of_turn>model\n"
+        );
+
+        let rendered = template.apply_chat_template(
+            &conversation(&[(MessageRole::System, "You are helpful.")]),
+            None,
+            None,
+            None,
+        );
+        assert!(rendered.is_err());
     }
 }
@@ -0,0 +1,295 @@
+use minijinja::value::{Kwargs, ValueKind};
+use minijinja::{Environment, Error, ErrorKind, State, Value};
+use serde::Serialize;
+use std::io;
+
+/// Environment matching the one `transformers` renders chat templates with: blocks
+/// are trimmed, the Python methods templates call on strings, lists and dicts are
+/// available, as well as `raise_exception`, `strftime_now` and a `tojson` producing
+/// the output of `json.dumps`, keys kept in their insertion order.
+pub fn create_environment<'source>() -> Environment<'source> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_unknown_method_callback(call_python_method);
+    env.add_function("raise_exception", raise_exception);
+    env.add_function("strftime_now", strftime_now);
+    env.add_filter("tojson", tojson);
+    env
+}
+
+fn raise_exception(message: String) -> Result<Value, Error> {
+    Err(Error::new(ErrorKind::InvalidOperation, message))
+}
+
+fn strftime_now(format: String) -> String {
+    chrono::Local::now().format(&format).to_string()
+}
+
+/// `json.dumps(value, ensure_ascii=False, indent=indent)`, `", "` and `": "` separate
+/// items without indent.
+fn tojson(value: Value, indent: Option<usize>, kwargs: Kwargs) -> Result<Value, Error> {
+    let indent = match indent {
+        Some(indent) => Some(indent),
+        None => kwargs.get::<Option<usize>>("indent")?,
+    };
+    let mut out = Vec::new();
+    let result = match indent {
+        Some(indent) => {
+            let indent = " ".repeat(indent);
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+            value.serialize(&mut serde_json::Serializer::with_formatter(
+                &mut out, formatter,
+            ))
+        }
+        None => value.serialize(&mut serde_json::Serializer::with_formatter(
+            &mut out,
+            PythonFormatter,
+        )),
+    };
+    result.map_err(|e| Error::new(ErrorKind::BadSerialization, e.to_string()))?;
+    Ok(Value::from_safe_string(
+        String::from_utf8_lossy(&out).into_owned(),
+    ))
+}
+
+struct PythonFormatter;
+
+impl serde_json::ser::Formatter for PythonFormatter {
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b": ")
+    }
+}
+
+fn call_python_method(
+    state: &State,
+    value: &Value,
+    method: &str,
+    args: &[Value],
+) -> Result<Value, Error> {
+    match value.kind() {
+        ValueKind::String => call_str_method(value.as_str().unwrap_or_default(), method, args),
+        ValueKind::Seq => call_list_method(value, method, args),
+        ValueKind::Map => call_dict_method(state, value, method, args),
+        _ => Err(Error::from(ErrorKind::UnknownMethod)),
+    }
+}
+
+fn arg_str(args: &[Value], idx: usize) -> Option<&str> {
+    args.get(idx).and_then(|arg| arg.as_str())
+}
+
+/// `str.startswith` and `str.endswith` accept a string or a tuple of strings.
+fn any_affix(arg: Option<&Value>, matches: impl Fn(&str) -> bool) -> Result<bool, Error> {
+    let Some(arg) = arg else {
+        return Err(Error::new(ErrorKind::MissingArgument, "missing affix"));
+    };
+    if let Some(affix) = arg.as_str() {
+        return Ok(matches(affix));
+    }
+    Ok(arg
+        .try_iter()?
+        .any(|affix| affix.as_str().is_some_and(&matches)))
+}
+
+fn strip_chars(args: &[Value]) -> Option<Vec<char>> {
+    arg_str(args, 0).map(|chars| chars.chars().collect())
+}
+
+fn call_str_method(s: &str, method: &str, args: &[Value]) -> Result<Value, Error> {
+    let value = match method {
+        "startswith" => Value::from(any_affix(args.first(), |p| s.starts_with(p))?),
+        "endswith" => Value::from(any_affix(args.first(), |p| s.ends_with(p))?),
+        "strip" => Value::from(match strip_chars(args) {
+            Some(chars) => s.trim_matches(chars.as_slice()),
+            None => s.trim(),
+        }),
+        "lstrip" => Value::from(match strip_chars(args) {
+            Some(chars) => s.trim_start_matches(chars.as_slice()),
+            None => s.trim_start(),
+        }),
+        "rstrip" => Value::from(match strip_chars(args) {
+            Some(chars) => s.trim_end_matches(chars.as_slice()),
+            None => s.trim_end(),
+        }),
+        "split" => {
+            let maxsplit = args
+                .get(1)
+                .and_then(|arg| arg.as_i64())
+                .filter(|maxsplit| *maxsplit >= 0)
+                .map(|maxsplit| maxsplit as usize + 1);
+            let parts: Vec<Value> = match (arg_str(args, 0), maxsplit) {
+                (Some(sep), Some(n)) => s.splitn(n, sep).map(Value::from).collect(),
+                (Some(sep), None) => s.split(sep).map(Value::from).collect(),
+                (None, _) => s.split_whitespace().map(Value::from).collect(),
+            };
+            Value::from(parts)
+        }
+        "upper" => Value::from(s.to_uppercase()),
+        "lower" => Value::from(s.to_lowercase()),
+        "capitalize" => {
+            let mut chars = s.chars();
+            Value::from(match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            })
+        }
+        "title" => {
+            let mut title = String::with_capacity(s.len());
+            let mut start = true;
+            for c in s.chars() {
+                if start {
+                    title.extend(c.to_uppercase());
+                } else {
+                    title.extend(c.to_lowercase());
+                }
+                start = !c.is_alphanumeric();
+            }
+            Value::from(title)
+        }
+        "replace" => {
+            let (Some(from), Some(to)) = (arg_str(args, 0), arg_str(args, 1)) else {
+                return Err(Error::new(ErrorKind::MissingArgument, "replace(old, new)"));
+            };
+            Value::from(s.replace(from, to))
+        }
+        "find" => {
+            let sub = arg_str(args, 0).unwrap_or_default();
+            Value::from(match s.find(sub) {
+                Some(idx) => s[..idx].chars().count() as i64,
+                None => -1,
+            })
+        }
+        "count" => Value::from(s.matches(arg_str(args, 0).unwrap_or_default()).count()),
+        "join" => {
+            let Some(items) = args.first() else {
+                return Err(Error::new(ErrorKind::MissingArgument, "join(iterable)"));
+            };
+            let items: Vec<String> = items.try_iter()?.map(|item| item.to_string()).collect();
+            Value::from(items.join(s))
+        }
+        "isdigit" => Value::from(!s.is_empty() && s.chars().all(|c| c.is_ascii_digit())),
+        "isspace" => Value::from(!s.is_empty() && s.chars().all(char::is_whitespace)),
+        _ => return Err(Error::from(ErrorKind::UnknownMethod)),
+    };
+    Ok(value)
+}
+
+fn call_list_method(value: &Value, method: &str, args: &[Value]) -> Result<Value, Error> {
+    let needle = args.first().cloned().unwrap_or_default();
+    match method {
+        "index" => value
+            .try_iter()?
+            .position(|item| item == needle)
+            .map(Value::from)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidOperation, "value is not in list")),
+        "count" => Ok(Value::from(
+            value.try_iter()?.filter(|item| *item == needle).count(),
+        )),
+        _ => Err(Error::from(ErrorKind::UnknownMethod)),
+    }
+}
+
+fn call_dict_method(
+    state: &State,
+    value: &Value,
+    method: &str,
+    args: &[Value],
+) -> Result<Value, Error> {
+    match method {
+        "items" => state.apply_filter("items", std::slice::from_ref(value)),
+        "keys" => Ok(Value::from(value.try_iter()?.collect::<Vec<_>>())),
+        "values" => {
+            let values = value
+                .try_iter()?
+                .map(|key| value.get_item(&key))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::from(values))
+        }
+        "get" => {
+            let key = args.first().cloned().unwrap_or_default();
+            let item = value.get_item(&key)?;
+            if item.is_undefined() {
+                Ok(args.get(1).cloned().unwrap_or_default())
+            } else {
+                Ok(item)
+            }
+        }
+        _ => Err(Error::from(ErrorKind::UnknownMethod)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str) -> String {
+        create_environment().render_str(source, ()).unwrap()
+    }
+
+    #[test]
+    fn test_python_methods() {
+        assert_eq!(render("{{ 'abc'.startswith('ab') }}"), "true");
+        assert_eq!(render("{{ 'abc'.endswith(('x', 'bc')) }}"), "true");
+        assert_eq!(
+            render("{{ '\\n a \\n'.strip() }}|{{ 'xxa'.lstrip('x') }}"),
+            "a|a"
+        );
+        assert_eq!(
+            render("{{ 'a</think>\\n\\nb'.split('</think>')[-1].lstrip('\\n') }}"),
+            "b"
+        );
+        assert_eq!(render("{{ ' a  b '.split() }}"), "[\"a\", \"b\"]");
+        assert_eq!(render("{{ 'hello world'.title() }}"), "Hello World");
+        assert_eq!(render("{{ ', '.join(['a', 'b']) }}"), "a, b");
+        assert_eq!(render("{{ {'a': 1}.get('b', 2) }}"), "2");
+        assert_eq!(
+            render("{% for k, v in {'a': 1}.items() %}{{ k }}={{ v }}{% endfor %}"),
+            "a=1"
+        );
+        assert_eq!(
+            render("{{ {'a': [1, 'b']} | tojson }}"),
+            "{\"a\": [1, \"b\"]}"
+        );
+        assert_eq!(
+            render("{{ {'b': 1, 'a': 2} | tojson }}"),
+            "{\"b\": 1, \"a\": 2}"
+        );
+        assert_eq!(
+            render("{{ {'a': 1} | tojson(indent=2) }}"),
+            "{\n  \"a\": 1\n}"
+        );
+        assert!(
+            create_environment()
+                .render_str("{{ raise_exception('nope') }}", ())
+                .is_err()
+        );
+    }
+}
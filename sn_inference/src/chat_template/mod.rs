@@ -1 +1,3 @@
 pub(crate) mod chat_template;
+pub(crate) mod environment;
+pub(crate) mod tool_call_parser;
@@ -0,0 +1,221 @@
+use crate::token::token_reasoning_parser::partial_tag_len;
+use serde_json::Value;
+use sn_core::types::tool::ToolCall;
+
+static QWEN_TOOL_CALL_START: &str = "<tool_call>";
+static QWEN_TOOL_CALL_END: &str = "</tool_call>";
+static LLAMA_PYTHON_TAG: &str = "<|python_tag|>";
+
+/// Split generated text into its content and the tool calls it holds, written either
+/// Qwen's way, `<tool_call>{"name": .., "arguments": ..}</tool_call>`, or Llama 3's,
+/// `<|python_tag|>{"name": .., "parameters": ..}` or the bare JSON object when the tag
+/// was skipped while decoding. Text that does not parse is kept as content.
+pub fn parse_tool_calls(text: &str) -> (String, Vec<ToolCall>) {
+    if text.contains(QWEN_TOOL_CALL_START) {
+        return parse_qwen_tool_calls(text);
+    }
+    parse_llama_tool_calls(text)
+}
+
+/// Holds the tool calls of a generation back from the stream, so clients do not get
+/// their markup before the parsed calls. Everything from the first text that may start
+/// one, `<tool_call>`, `<|python_tag|>` or a JSON object opening the answer, is held
+/// until the end of the generation, then only what is not a tool call is let through.
+#[derive(Debug, Default)]
+pub struct ToolCallStreamFilter {
+    /// Some text other than line breaks or spaces was let through.
+    started: bool,
+    holding: bool,
+    pending: String,
+}
+
+impl ToolCallStreamFilter {
+    /// The part of the answer that can be streamed now.
+    pub fn push(&mut self, text: &str) -> String {
+        self.pending.push_str(text);
+        if self.holding {
+            return String::new();
+        }
+        if !self.started {
+            let answer = self.pending.trim_start();
+            if answer.is_empty() {
+                return String::new();
+            }
+            self.holding = answer.starts_with('{');
+            self.started = !self.holding;
+            if self.holding {
+                return String::new();
+            }
+        }
+        let tags = [QWEN_TOOL_CALL_START, LLAMA_PYTHON_TAG];
+        match tags.iter().filter_map(|tag| self.pending.find(tag)).min() {
+            Some(idx) => {
+                self.holding = true;
+                self.pending.drain(..idx).collect()
+            }
+            None => {
+                let keep = tags
+                    .iter()
+                    .map(|tag| partial_tag_len(&self.pending, tag))
+                    .max()
+                    .unwrap_or(0);
+                self.pending.drain(..self.pending.len() - keep).collect()
+            }
+        }
+    }
+
+    /// The text held back, without its tool calls, once the generation ended.
+    pub fn finish(&mut self) -> String {
+        let text = std::mem::take(&mut self.pending);
+        if !self.holding {
+            return text;
+        }
+        let (content, tool_calls) = parse_tool_calls(&text);
+        if tool_calls.is_empty() { text } else { content }
+    }
+}
+
+fn parse_qwen_tool_calls(text: &str) -> (String, Vec<ToolCall>) {
+    let mut content = String::new();
+    let mut tool_calls = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(QWEN_TOOL_CALL_START) {
+        let body = &rest[start + QWEN_TOOL_CALL_START.len()..];
+        // The generation may stop before the closing tag.
+        let (json, next) = match body.find(QWEN_TOOL_CALL_END) {
+            Some(end) => (&body[..end], &body[end + QWEN_TOOL_CALL_END.len()..]),
+            None => (body, ""),
+        };
+        match tool_call_from_json(json) {
+            Some(tool_call) => {
+                content.push_str(&rest[..start]);
+                tool_calls.push(tool_call);
+            }
+            None => content.push_str(&rest[..rest.len() - next.len()]),
+        }
+        rest = next;
+    }
+    content.push_str(rest);
+    (content.trim().to_string(), tool_calls)
+}
+
+fn parse_llama_tool_calls(text: &str) -> (String, Vec<ToolCall>) {
+    let (content, calls) = match text.find(LLAMA_PYTHON_TAG) {
+        Some(idx) => (&text[..idx], &text[idx + LLAMA_PYTHON_TAG.len()..]),
+        None => ("", text),
+    };
+    if !calls.trim_start().starts_with('{') {
+        return (text.to_string(), Vec::new());
+    }
+    // Several calls are separated by `;`.
+    let tool_calls: Option<Vec<ToolCall>> = match tool_call_from_json(calls) {
+        Some(tool_call) => Some(vec![tool_call]),
+        None => calls
+            .split(';')
+            .filter(|call| !call.trim().is_empty())
+            .map(tool_call_from_json)
+            .collect(),
+    };
+    match tool_calls {
+        Some(tool_calls) if !tool_calls.is_empty() => (content.trim().to_string(), tool_calls),
+        _ => (text.to_string(), Vec::new()),
+    }
+}
+
+fn tool_call_from_json(json: &str) -> Option<ToolCall> {
+    let value: Value = serde_json::from_str(json.trim()).ok()?;
+    let name = value.get("name")?.as_str()?;
+    let arguments = value
+        .get("arguments")
+        .or_else(|| value.get("parameters"))
+        .cloned()
+        .unwrap_or(Value::Object(Default::default()));
+    // Some models write the arguments as a JSON string.
+    let arguments = match arguments {
+        Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+        arguments => arguments,
+    };
+    Some(ToolCall::new(name, arguments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_tool_calls() {
+        let (content, tool_calls) = parse_tool_calls(
+            "<think>\nNeed the weather.\n</think>\n\n<tool_call>\n{\"name\": \"get_weather\", \"arguments\": {\"city\": \"Paris\"}}\n</tool_call>\n<tool_call>\n{\"name\": \"get_time\", \"arguments\": \"{\\\"tz\\\": \\\"CET\\\"}\"}\n</tool_call>",
+        );
+        assert_eq!(content, "<think>\nNeed the weather.\n</think>");
+        assert_eq!(
+            tool_calls,
+            vec![
+                ToolCall::new("get_weather", json!({"city": "Paris"})),
+                ToolCall::new("get_time", json!({"tz": "CET"})),
+            ]
+        );
+
+        let (content, tool_calls) = parse_tool_calls(
+            "<|python_tag|>{\"name\": \"get_weather\", \"parameters\": {\"city\": \"Paris\"}}",
+        );
+        assert_eq!(content, "");
+        assert_eq!(
+            tool_calls,
+            vec![ToolCall::new("get_weather", json!({"city": "Paris"}))]
+        );
+
+        let (_, tool_calls) =
+            parse_tool_calls("{\"name\": \"get_weather\", \"parameters\": {\"city\": \"Paris\"}}");
+        assert_eq!(tool_calls.len(), 1);
+
+        let (content, tool_calls) = parse_tool_calls("<tool_call>\n{\"name\": \"get_");
+        assert_eq!(content, "<tool_call>\n{\"name\": \"get_");
+        assert!(tool_calls.is_empty());
+
+        let (content, tool_calls) = parse_tool_calls("{\"answer\": 42}");
+        assert_eq!(content, "{\"answer\": 42}");
+        assert!(tool_calls.is_empty());
+    }
+
+    fn stream(filter: &mut ToolCallStreamFilter, texts: &[&str]) -> Vec<String> {
+        let mut streamed: Vec<String> = texts.iter().map(|text| filter.push(text)).collect();
+        streamed.push(filter.finish());
+        streamed.retain(|text| !text.is_empty());
+        streamed
+    }
+
+    #[test]
+    fn test_hold_tool_calls_back_from_stream() {
+        let mut filter = ToolCallStreamFilter::default();
+        let streamed = stream(
+            &mut filter,
+            &[
+                "Let me check.",
+                "\n<tool",
+                "_call>\n{\"name\": \"get_weather\", ",
+                "\"arguments\": {}}\n</tool_call>",
+            ],
+        );
+        assert_eq!(streamed, vec!["Let me check.", "\n"]);
+
+        let mut filter = ToolCallStreamFilter::default();
+        let streamed = stream(
+            &mut filter,
+            &[
+                "<|python_tag|>",
+                "{\"name\": \"get_weather\", \"parameters\": {}}",
+            ],
+        );
+        assert!(streamed.is_empty());
+
+        let mut filter = ToolCallStreamFilter::default();
+        let streamed = stream(&mut filter, &["\n", "{\"answer\":", " 42}"]);
+        assert_eq!(streamed, vec!["\n{\"answer\": 42}"]);
+
+        let mut filter = ToolCallStreamFilter::default();
+        let streamed = stream(&mut filter, &["Use <", "b>bold</b> {here}"]);
+        assert_eq!(streamed, vec!["Use ", "<b>bold</b> {here}"]);
+    }
+}
@@ -1,6 +1,10 @@
 use crate::config::config_model::ConfigModel;
 use crate::config::config_tokenizer_custom::ConfigTokenizerCustom;
 use crate::error::Result;
+use crate::model::gguf::find_gguf_file;
+use crate::model::gguf::metadata::{config_model, config_tokenizer_custom};
+use crate::model::gguf::reader::open_gguf;
+use crate::model::gguf::tensor::quantization_bits;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -18,6 +22,8 @@ pub struct Config {
     pub tokenizer_path: String,
     pub tokenizer_vocab_path: String,
     pub special_tokens_path: String,
+    /// Set when the model is a single GGUF file instead of a transformers checkpoint.
+    pub gguf_path: Option<String>,
 }
 
 fn get_config_tokenizer_custom(
@@ -62,9 +68,19 @@ impl Config {
             .display()
             .to_string();
 
-        let config_model = Config::from_file::<ConfigModel>(&config_path)?;
-        let config_tokenizer_custom =
-            get_config_tokenizer_custom(&tokenizer_custom_path, &root_path)?;
+        let gguf_path = if Path::new(&config_path).is_file() {
+            None
+        } else {
+            find_gguf_file(&root_path)
+        };
+
+        let (config_model, config_tokenizer_custom) = match &gguf_path {
+            Some(gguf_path) => Config::from_gguf(gguf_path)?,
+            None => (
+                Config::from_file::<ConfigModel>(&config_path)?,
+                get_config_tokenizer_custom(&tokenizer_custom_path, &root_path)?,
+            ),
+        };
         Ok(Config {
             model: Rc::new(config_model),
             tokenizer_custom: config_tokenizer_custom,
@@ -73,9 +89,20 @@ impl Config {
             tokenizer_path,
             tokenizer_vocab_path,
             special_tokens_path,
+            gguf_path,
         })
     }
 
+    fn from_gguf(gguf_path: &str) -> Result<(ConfigModel, ConfigTokenizerCustom)> {
+        debug!("Reading config from GGUF metadata in {}", gguf_path);
+        let (mmap, header) = open_gguf(gguf_path)?;
+        let bits = quantization_bits(&header)?;
+        Ok((
+            config_model(&header, &mmap, bits)?,
+            config_tokenizer_custom(&header)?,
+        ))
+    }
+
     fn from_file<T: DeserializeOwned>(path: &str) -> Result<T> {
         let data = fs::read_to_string(&path)?;
         let config: T = serde_json::from_str(&data)?;
@@ -1,18 +1,72 @@
+use crate::config::config_models::default::DefaultConfig;
 use crate::config::config_models::llama::LLaMAConfig;
+use crate::config::config_models::qwen2::Qwen2Config;
 use crate::config::config_models::qwen3::Qwen3Config;
+use crate::config::config_models::qwen3_moe::Qwen3MoeConfig;
 use serde::de;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use sn_core::types::model_quantization::ModelQuantization;
 use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub enum ConfigModel {
     LLaMA(Rc<LLaMAConfig>),
+    Qwen2(Rc<Qwen2Config>),
     Qwen3(Rc<Qwen3Config>),
+    Qwen3Moe(Rc<Qwen3MoeConfig>),
+    Default(Rc<DefaultConfig>),
 }
 
 pub trait ConfigModelCommon {
-    fn get_name(&self) -> String;
+    /// Architecture name from `config.json`, e.g. `Qwen3ForCausalLM`.
+    fn architecture(&self) -> String;
+    /// Maximum number of positions the model was trained for.
+    fn context_length(&self) -> Option<i32>;
+}
+
+impl ConfigModel {
+    fn common(&self) -> &dyn ConfigModelCommon {
+        match self {
+            ConfigModel::LLaMA(config) => config.as_ref(),
+            ConfigModel::Qwen2(config) => config.as_ref(),
+            ConfigModel::Qwen3(config) => config.as_ref(),
+            ConfigModel::Qwen3Moe(config) => config.as_ref(),
+            ConfigModel::Default(config) => config.as_ref(),
+        }
+    }
+
+    pub fn architecture(&self) -> String {
+        self.common().architecture()
+    }
+
+    pub fn context_length(&self) -> Option<i32> {
+        self.common().context_length()
+    }
+
+    /// Quantization the checkpoint was saved with, read from the `quantization` section.
+    pub fn quantization(&self) -> Option<ModelQuantization> {
+        let (group_size, bits) = match self {
+            ConfigModel::LLaMA(config) => {
+                config.quantization.as_ref().map(|q| (q.group_size, q.bits))
+            }
+            ConfigModel::Qwen2(config) => {
+                config.quantization.as_ref().map(|q| (q.group_size, q.bits))
+            }
+            ConfigModel::Qwen3(config) => {
+                config.quantization.as_ref().map(|q| (q.group_size, q.bits))
+            }
+            ConfigModel::Qwen3Moe(config) => config
+                .base
+                .quantization
+                .as_ref()
+                .map(|q| (q.group_size, q.bits)),
+            ConfigModel::Default(config) => {
+                config.quantization.as_ref().map(|q| (q.group_size, q.bits))
+            }
+        }?;
+        Some(ModelQuantization { bits, group_size })
+    }
 }
 
 impl<'de> Deserialize<'de> for ConfigModel {
@@ -34,13 +88,39 @@ impl<'de> Deserialize<'de> for ConfigModel {
                     serde_json::from_value(llama_value).map_err(de::Error::custom)?;
                 Ok(ConfigModel::LLaMA(Rc::new(llama_config)))
             }
+            "qwen2" => {
+                let qwen2_value = Value::Object(value);
+                let qwen2_config: Qwen2Config =
+                    serde_json::from_value(qwen2_value).map_err(de::Error::custom)?;
+                Ok(ConfigModel::Qwen2(Rc::new(qwen2_config)))
+            }
             "qwen3" => {
                 let qwen3_value = Value::Object(value);
                 let qwen3_config: Qwen3Config =
                     serde_json::from_value(qwen3_value).map_err(de::Error::custom)?;
                 Ok(ConfigModel::Qwen3(Rc::new(qwen3_config)))
             }
-            other => Err(de::Error::unknown_variant(other, &["qwen3"])),
+            "qwen3_moe" => {
+                let qwen3_moe_value = Value::Object(value);
+                let qwen3_moe_config: Qwen3MoeConfig =
+                    serde_json::from_value(qwen3_moe_value).map_err(de::Error::custom)?;
+                Ok(ConfigModel::Qwen3Moe(Rc::new(qwen3_moe_config)))
+            }
+            other if DefaultConfig::fits(&value) => {
+                let other = other.to_string();
+                let default_config: DefaultConfig = serde_json::from_value(Value::Object(value))
+                    .map_err(|e| {
+                        de::Error::custom(format!(
+                            "model_type `{}` is not llama-like enough for the default decoder: {}",
+                            other, e
+                        ))
+                    })?;
+                Ok(ConfigModel::Default(Rc::new(default_config)))
+            }
+            other => Err(de::Error::unknown_variant(
+                other,
+                &["llama", "qwen2", "qwen3", "qwen3_moe", "mistral", "yi"],
+            )),
         }
     }
 }
@@ -52,7 +132,100 @@ impl Serialize for ConfigModel {
     {
         match self {
             ConfigModel::LLaMA(config) => config.serialize(serializer),
+            ConfigModel::Qwen2(config) => config.serialize(serializer),
             ConfigModel::Qwen3(config) => config.serialize(serializer),
+            ConfigModel::Qwen3Moe(config) => config.serialize(serializer),
+            ConfigModel::Default(config) => config.serialize(serializer),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn llama_like(model_type: &str) -> Value {
+        json!({
+            "model_type": model_type,
+            "hidden_size": 4096,
+            "intermediate_size": 14336,
+            "num_attention_heads": 32,
+            "num_hidden_layers": 32,
+            "num_key_value_heads": 8,
+            "vocab_size": 32768,
+            "rms_norm_eps": 1e-5,
+            "eos_token_id": 2
+        })
+    }
+
+    #[test]
+    fn test_unknown_llama_like_falls_back_to_default() {
+        let config: ConfigModel = serde_json::from_value(llama_like("mistral")).unwrap();
+        match config {
+            ConfigModel::Default(config) => {
+                assert_eq!(config.model_type, "mistral");
+                assert_eq!(config.hidden_act, "silu");
+                assert_eq!(config.head_dim(), 128);
+                assert_eq!(config.eos_token_ids(), vec![2]);
+            }
+            other => panic!("unexpected config: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_moe_is_rejected() {
+        let mut value = llama_like("mixtral");
+        value["num_local_experts"] = json!(8);
+        let error = serde_json::from_value::<ConfigModel>(value).unwrap_err();
+        assert!(error.to_string().contains("qwen3_moe"));
+        assert!(error.to_string().contains("mistral"));
+    }
+
+    #[test]
+    fn test_qwen_architectures_keep_their_attention() {
+        let mut qwen3 = llama_like("custom");
+        qwen3["architectures"] = json!(["Qwen3ForCausalLM"]);
+        let Ok(ConfigModel::Default(config)) = serde_json::from_value::<ConfigModel>(qwen3) else {
+            panic!("expected a default config");
+        };
+        assert!(config.use_qk_norm());
+        assert!(!config.attention_bias());
+
+        let mut qwen2 = llama_like("custom");
+        qwen2["architectures"] = json!(["Qwen2ForCausalLM"]);
+        let Ok(ConfigModel::Default(config)) = serde_json::from_value::<ConfigModel>(qwen2) else {
+            panic!("expected a default config");
+        };
+        assert!(!config.use_qk_norm());
+        assert!(config.attention_bias());
+
+        let mut explicit = llama_like("custom");
+        explicit["architectures"] = json!(["Qwen2ForCausalLM"]);
+        explicit["attention_bias"] = json!(false);
+        let Ok(ConfigModel::Default(config)) = serde_json::from_value::<ConfigModel>(explicit)
+        else {
+            panic!("expected a default config");
+        };
+        assert!(!config.attention_bias());
+    }
+
+    #[test]
+    fn test_gemma_and_phi3_are_rejected() {
+        let mut gemma = llama_like("gemma");
+        gemma["architectures"] = json!(["GemmaForCausalLM"]);
+        gemma["hidden_act"] = json!("gelu_pytorch_tanh");
+        assert!(serde_json::from_value::<ConfigModel>(gemma).is_err());
+
+        let mut phi3 = llama_like("phi3");
+        phi3["architectures"] = json!(["Phi3ForCausalLM"]);
+        assert!(serde_json::from_value::<ConfigModel>(phi3).is_err());
+
+        let mut custom = llama_like("custom");
+        custom["architectures"] = json!(["LlamaForCausalLM"]);
+        assert!(matches!(
+            serde_json::from_value::<ConfigModel>(custom),
+            Ok(ConfigModel::Default(_))
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::config::config_model::ConfigModelCommon;
+use crate::config::config_models::quantization_config::QuantizationConfig;
+use crate::config::config_models::rope_scaling_config::RopeScalingConfig;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultNormType {
+    RmsNorm,
+    LayerNorm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultRopeVariant {
+    /// Rotates the two halves of the head dimension (GPT-NeoX, Llama, Qwen...).
+    Default,
+    /// Rotates interleaved pairs of the head dimension (GPT-J, original Llama).
+    Traditional,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DefaultTokenIds {
+    Single(i32),
+    Multiple(Vec<i32>),
+}
+
+/// Config of the decoder-only transformers following the Llama layout. Fields missing
+/// from `config.json` fall back to the Llama defaults, the flags below can be set in
+/// `config.json` to describe the variations between architectures.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DefaultConfig {
+    pub model_type: String,
+    pub architectures: Option<Vec<String>>,
+
+    pub hidden_size: i32,
+    pub intermediate_size: i32,
+    pub num_attention_heads: i32,
+    pub num_hidden_layers: i32,
+    pub num_key_value_heads: Option<i32>,
+    pub head_dim: Option<i32>,
+    pub vocab_size: i32,
+    pub max_position_embeddings: Option<i32>,
+
+    pub bos_token_id: Option<DefaultTokenIds>,
+    pub eos_token_id: Option<DefaultTokenIds>,
+
+    #[serde(default = "default_hidden_act")]
+    pub hidden_act: String,
+    pub rms_norm_eps: Option<f32>,
+    pub layer_norm_eps: Option<f32>,
+
+    // Flags
+    pub norm_type: Option<DefaultNormType>,
+    pub use_qk_norm: Option<bool>,
+    pub attention_bias: Option<bool>,
+    #[serde(default)]
+    pub mlp_bias: bool,
+    #[serde(default)]
+    pub tie_word_embeddings: bool,
+    pub rope_variant: Option<DefaultRopeVariant>,
+
+    #[serde(default = "default_rope_theta")]
+    pub rope_theta: f32,
+    pub rope_scaling: Option<RopeScalingConfig>,
+
+    pub quantization: Option<QuantizationConfig>,
+    pub quantization_config: Option<QuantizationConfig>,
+}
+
+fn default_hidden_act() -> String {
+    "silu".to_string()
+}
+
+fn default_rope_theta() -> f32 {
+    10_000.0
+}
+
+impl DefaultConfig {
+    /// `model_type` values known to follow the Llama layout and weight names. Others,
+    /// like Gemma's scaled embeddings or Phi-3's fused projections, load into garbage.
+    /// Llama and Qwen have their own configs, they only reach the default decoder under
+    /// another `model_type`, by their architecture.
+    const LLAMA_LIKE_MODEL_TYPES: [&'static str; 2] = ["mistral", "yi"];
+
+    const LLAMA_LIKE_ARCHITECTURES: [&'static str; 4] = [
+        "LlamaForCausalLM",
+        "MistralForCausalLM",
+        "Qwen2ForCausalLM",
+        "Qwen3ForCausalLM",
+    ];
+
+    /// Keys only found in architectures the generic decoder can not represent.
+    const UNSUPPORTED_KEYS: [&'static str; 4] = [
+        "num_local_experts",
+        "num_experts",
+        "sliding_window_pattern",
+        "layer_types",
+    ];
+
+    /// Whether a raw `config.json` describes a Llama-like decoder, by its `model_type`
+    /// or one of its `architectures`.
+    pub fn fits(value: &serde_json::Map<String, serde_json::Value>) -> bool {
+        let known_model_type = value
+            .get("model_type")
+            .and_then(|v| v.as_str())
+            .is_some_and(|model_type| Self::LLAMA_LIKE_MODEL_TYPES.contains(&model_type));
+        let known_architecture = value
+            .get("architectures")
+            .and_then(|v| v.as_array())
+            .is_some_and(|architectures| {
+                architectures.iter().any(|architecture| {
+                    architecture
+                        .as_str()
+                        .is_some_and(|a| Self::LLAMA_LIKE_ARCHITECTURES.contains(&a))
+                })
+            });
+        (known_model_type || known_architecture)
+            && !Self::UNSUPPORTED_KEYS
+                .iter()
+                .any(|key| value.get(*key).is_some_and(|v| !v.is_null()))
+    }
+
+    pub fn norm_type(&self) -> DefaultNormType {
+        match (self.norm_type, self.rms_norm_eps, self.layer_norm_eps) {
+            (Some(norm_type), _, _) => norm_type,
+            (None, None, Some(_)) => DefaultNormType::LayerNorm,
+            _ => DefaultNormType::RmsNorm,
+        }
+    }
+
+    pub fn norm_eps(&self) -> f32 {
+        let eps = match self.norm_type() {
+            DefaultNormType::RmsNorm => self.rms_norm_eps.or(self.layer_norm_eps),
+            DefaultNormType::LayerNorm => self.layer_norm_eps.or(self.rms_norm_eps),
+        };
+        eps.unwrap_or(1e-5)
+    }
+
+    pub fn num_key_value_heads(&self) -> i32 {
+        self.num_key_value_heads.unwrap_or(self.num_attention_heads)
+    }
+
+    pub fn head_dim(&self) -> i32 {
+        self.head_dim
+            .unwrap_or(self.hidden_size / self.num_attention_heads)
+    }
+
+    fn has_architecture(&self, name: &str) -> bool {
+        self.architectures
+            .as_ref()
+            .is_some_and(|architectures| architectures.iter().any(|a| a == name))
+    }
+
+    /// Qwen3 normalizes queries and keys per head, `config.json` does not say so.
+    pub fn use_qk_norm(&self) -> bool {
+        self.use_qk_norm
+            .unwrap_or_else(|| self.has_architecture("Qwen3ForCausalLM"))
+    }
+
+    /// Qwen2 has biases on the query, key and value projections, `config.json` does not
+    /// say so.
+    pub fn attention_bias(&self) -> bool {
+        self.attention_bias
+            .unwrap_or_else(|| self.has_architecture("Qwen2ForCausalLM"))
+    }
+
+    pub fn rope_traditional(&self) -> bool {
+        self.rope_variant == Some(DefaultRopeVariant::Traditional)
+    }
+
+    pub fn eos_token_ids(&self) -> Vec<i32> {
+        match &self.eos_token_id {
+            Some(DefaultTokenIds::Single(id)) => vec![*id],
+            Some(DefaultTokenIds::Multiple(ids)) => ids.clone(),
+            None => vec![],
+        }
+    }
+}
+
+impl ConfigModelCommon for DefaultConfig {
+    fn architecture(&self) -> String {
+        self.architectures
+            .as_ref()
+            .and_then(|architectures| architectures.first().cloned())
+            .unwrap_or_else(|| self.model_type.clone())
+    }
+
+    fn context_length(&self) -> Option<i32> {
+        self.max_position_embeddings
+    }
+}
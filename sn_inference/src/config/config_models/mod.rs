@@ -1,3 +1,7 @@
+pub(crate) mod default;
 pub(crate) mod llama;
 mod quantization_config;
+pub(crate) mod qwen2;
 pub(crate) mod qwen3;
+pub(crate) mod qwen3_moe;
+pub(crate) mod rope_scaling_config;
@@ -0,0 +1,47 @@
+use crate::config::config_model::ConfigModelCommon;
+use crate::config::config_models::quantization_config::QuantizationConfig;
+use crate::config::config_models::rope_scaling_config::RopeScalingConfig;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Qwen2Config {
+    pub architectures: Vec<String>,
+    pub attention_dropout: f64,
+    pub bos_token_id: Option<i32>,
+    pub eos_token_id: i32,
+    pub hidden_act: String,
+    pub hidden_size: i32,
+    pub initializer_range: f64,
+    pub intermediate_size: i32,
+    pub max_position_embeddings: i32,
+    pub max_window_layers: i32,
+    pub model_type: String,
+    pub num_attention_heads: i32,
+    pub num_hidden_layers: i32,
+    pub num_key_value_heads: i32,
+    pub quantization: Option<QuantizationConfig>,
+    pub quantization_config: Option<QuantizationConfig>,
+    pub rms_norm_eps: f32,
+    pub rope_scaling: Option<RopeScalingConfig>,
+    pub rope_theta: f32,
+    pub sliding_window: Option<serde_json::Value>,
+    pub tie_word_embeddings: bool,
+    pub torch_dtype: String,
+    pub transformers_version: String,
+    pub use_cache: bool,
+    pub use_sliding_window: bool,
+    pub vocab_size: i32,
+}
+
+impl ConfigModelCommon for Qwen2Config {
+    fn architecture(&self) -> String {
+        self.architectures
+            .first()
+            .cloned()
+            .unwrap_or_else(|| self.model_type.clone())
+    }
+
+    fn context_length(&self) -> Option<i32> {
+        Some(self.max_position_embeddings)
+    }
+}
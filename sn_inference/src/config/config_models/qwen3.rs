@@ -1,5 +1,6 @@
 use crate::config::config_model::ConfigModelCommon;
 use crate::config::config_models::quantization_config::QuantizationConfig;
+use crate::config::config_models::rope_scaling_config::RopeScalingConfig;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,7 +33,7 @@ pub struct Qwen3Config {
     pub use_qk_norm: Option<bool>,
 
     pub rms_norm_eps: f32,
-    pub rope_scaling: Option<Qwen3RopeScalingConfig>,
+    pub rope_scaling: Option<RopeScalingConfig>,
     pub rope_theta: f32,
     pub sliding_window: Option<serde_json::Value>,
     pub tie_word_embeddings: bool,
@@ -43,32 +44,15 @@ pub struct Qwen3Config {
     pub vocab_size: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Qwen3RopeScalingConfig {}
-
 impl ConfigModelCommon for Qwen3Config {
-    fn get_name(&self) -> String {
-        let model_type = "Qwen3";
-
-        let size = match (self.hidden_size, self.num_hidden_layers) {
-            (4096, 32) => "8B",
-            (5120, 40) => "13B",
-            (6656, 60) => "70B",
-            _ => "unknown-size",
-        };
-
-        let quant = if self.quantization.is_some() || self.quantization_config.is_some() {
-            "4bit"
-        } else {
-            "fp16"
-        };
+    fn architecture(&self) -> String {
+        self.architectures
+            .first()
+            .cloned()
+            .unwrap_or_else(|| self.model_type.clone())
+    }
 
-        format!(
-            "models-{}-{}-{}{}",
-            model_type,
-            size,
-            "Instruct",
-            format!("-{}", quant)
-        )
+    fn context_length(&self) -> Option<i32> {
+        Some(self.max_position_embeddings)
     }
 }
@@ -0,0 +1,43 @@
+use crate::config::config_model::ConfigModelCommon;
+use crate::config::config_models::qwen3::Qwen3Config;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Qwen3MoeConfig {
+    // Attention, norms and embeddings are shared with the dense Qwen3 layout
+    #[serde(flatten)]
+    pub base: Rc<Qwen3Config>,
+
+    pub num_experts: i32,
+    pub num_experts_per_tok: i32,
+    pub norm_topk_prob: bool,
+    pub moe_intermediate_size: i32,
+    #[serde(default = "default_decoder_sparse_step")]
+    pub decoder_sparse_step: i32,
+    #[serde(default)]
+    pub mlp_only_layers: Vec<i32>,
+}
+
+fn default_decoder_sparse_step() -> i32 {
+    1
+}
+
+impl Qwen3MoeConfig {
+    /// Whether the layer at `layer_idx` uses the sparse MoE block instead of the dense MLP.
+    pub fn is_sparse_layer(&self, layer_idx: i32) -> bool {
+        !self.mlp_only_layers.contains(&layer_idx)
+            && self.num_experts > 0
+            && (layer_idx + 1) % self.decoder_sparse_step.max(1) == 0
+    }
+}
+
+impl ConfigModelCommon for Qwen3MoeConfig {
+    fn architecture(&self) -> String {
+        self.base.architecture()
+    }
+
+    fn context_length(&self) -> Option<i32> {
+        self.base.context_length()
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// `rope_scaling` section of `config.json`, shared by every architecture. Only the
+/// fields used by the selected `rope_type` are expected to be present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RopeScalingConfig {
+    pub rope_type: Option<String>,
+    // Older checkpoints (Qwen2.5, Llama 2) name it `type`
+    #[serde(rename = "type")]
+    pub legacy_type: Option<String>,
+    pub factor: Option<f32>,
+    pub original_max_position_embeddings: Option<i32>,
+
+    // llama3
+    pub low_freq_factor: Option<f32>,
+    pub high_freq_factor: Option<f32>,
+    /// Per-dimension divisors of the rope frequencies computed from the fields above,
+    /// llama.cpp stores them in the `rope_freqs.weight` tensor of GGUF files instead.
+    pub rope_freqs: Option<Vec<f32>>,
+
+    // yarn
+    pub beta_fast: Option<f32>,
+    pub beta_slow: Option<f32>,
+    pub mscale: Option<f32>,
+    pub mscale_all_dim: Option<f32>,
+}
+
+impl RopeScalingConfig {
+    pub fn rope_type(&self) -> &str {
+        self.rope_type
+            .as_deref()
+            .or(self.legacy_type.as_deref())
+            .unwrap_or("default")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rope_type_from_legacy_type() {
+        let config: RopeScalingConfig = serde_json::from_str(
+            r#"{"type": "yarn", "factor": 4.0, "original_max_position_embeddings": 32768}"#,
+        )
+        .unwrap();
+        assert_eq!(config.rope_type(), "yarn");
+        assert_eq!(config.original_max_position_embeddings, Some(32768));
+    }
+
+    #[test]
+    fn test_rope_type_prefers_rope_type() {
+        let config: RopeScalingConfig =
+            serde_json::from_str(r#"{"rope_type": "llama3", "type": "default", "factor": 8.0}"#)
+                .unwrap();
+        assert_eq!(config.rope_type(), "llama3");
+    }
+}
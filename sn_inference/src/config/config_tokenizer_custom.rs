@@ -1,9 +1,31 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 const DEFAULT_CHAT_TEMPLATE: &str = "";
 #[derive(Deserialize, Debug, Clone, Default, Serialize)]
 pub struct ConfigTokenizerCustom {
     pub chat_template: Option<String>,
     pub pad_token: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_special_token")]
+    pub bos_token: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_special_token")]
+    pub eos_token: Option<String>,
+}
+
+/// Special tokens are either a string or an `AddedToken` object holding it in `content`.
+fn deserialize_special_token<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SpecialToken {
+        Content(String),
+        AddedToken { content: String },
+    }
+    Ok(
+        Option::<SpecialToken>::deserialize(deserializer)?.map(|token| match token {
+            SpecialToken::Content(content) | SpecialToken::AddedToken { content } => content,
+        }),
+    )
 }
 
 impl ConfigTokenizerCustom {
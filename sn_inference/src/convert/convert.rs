@@ -0,0 +1,212 @@
+use crate::config::config::Config;
+use crate::convert::safetensors_writer::{SafetensorsEntry, ShardedSafetensorsWriter};
+use crate::error::{Error, Result};
+use crate::factory::model::create_model_instance;
+use crate::model::gguf::metadata::config_model_value;
+use crate::model::gguf::reader::open_gguf;
+use crate::model::gguf::tokenizer::tokenizer_json;
+use crate::model::model::Model;
+use crate::model::weight::Weight;
+use crate::utils::d_type::DTypeExt;
+use half::{bf16, f16};
+use mlx_rs::error::Exception;
+use mlx_rs::{Array, ArrayElement, Dtype};
+use serde_json::{Value, json};
+use sn_core::types::model_quantization::ModelQuantization;
+use sn_core::utils::rw_lock::RwLockExt;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use tracing::{debug, info};
+
+/// Same shard size as the checkpoints published by `mlx-lm`.
+static MAX_SHARD_BYTES: usize = 5 << 30;
+
+/// Files copied as-is from the source directory when present.
+static TOKENIZER_FILES: [&str; 8] = [
+    "tokenizer.json",
+    "tokenizer_config.json",
+    "special_tokens_map.json",
+    "vocab.json",
+    "merges.txt",
+    "tokenizer.model",
+    "chat_template.jinja",
+    "generation_config.json",
+];
+
+fn slice_bytes<T: ArrayElement>(array: &Array) -> Result<Vec<u8>> {
+    let slice = array
+        .try_as_slice::<T>()
+        .map_err(|e| Exception::custom(e.to_string()))?;
+    let bytes = unsafe {
+        std::slice::from_raw_parts(slice.as_ptr() as *const u8, std::mem::size_of_val(slice))
+    };
+    Ok(bytes.to_vec())
+}
+
+fn array_bytes(array: &Array) -> Result<Vec<u8>> {
+    match array.dtype() {
+        Dtype::Bool => slice_bytes::<bool>(array),
+        Dtype::Uint8 => slice_bytes::<u8>(array),
+        Dtype::Uint16 => slice_bytes::<u16>(array),
+        Dtype::Uint32 => slice_bytes::<u32>(array),
+        Dtype::Uint64 => slice_bytes::<u64>(array),
+        Dtype::Int8 => slice_bytes::<i8>(array),
+        Dtype::Int16 => slice_bytes::<i16>(array),
+        Dtype::Int32 => slice_bytes::<i32>(array),
+        Dtype::Int64 => slice_bytes::<i64>(array),
+        Dtype::Float16 => slice_bytes::<f16>(array),
+        Dtype::Bfloat16 => slice_bytes::<bf16>(array),
+        Dtype::Float32 => slice_bytes::<f32>(array),
+        Dtype::Float64 => slice_bytes::<f64>(array),
+        other => Err(Error::UnsupportedSafetensorsDtype(format!("{:?}", other))),
+    }
+}
+
+fn entry(name: String, array: &Array) -> Result<SafetensorsEntry> {
+    let dtype = array
+        .dtype()
+        .to_safetensors_str()
+        .ok_or_else(|| Error::UnsupportedSafetensorsDtype(format!("{:?}", array.dtype())))?;
+    Ok(SafetensorsEntry {
+        name,
+        dtype,
+        shape: array.shape().to_vec(),
+        data: array_bytes(array)?,
+    })
+}
+
+/// Weight names are loaded without their `model.` prefix, put it back so the
+/// checkpoint keeps the transformers layout.
+fn checkpoint_name(name: &str) -> String {
+    if name.starts_with("lm_head") {
+        name.to_string()
+    } else {
+        format!("model.{}", name)
+    }
+}
+
+/// Only the matrices of linear and embedding layers are quantized, norms and biases
+/// are kept in full precision. The experts of a mixture of experts layer are stacked
+/// into one `switch_mlp` tensor per projection, quantized like the loader expects.
+fn should_quantize(name: &str, array: &Array) -> bool {
+    name.ends_with(".weight")
+        && (array.ndim() == 2 || (array.ndim() == 3 && name.contains(".switch_mlp.")))
+}
+
+fn write_config(config: &Config, dst: &Path, quantization: ModelQuantization) -> Result<()> {
+    let mut value = match &config.gguf_path {
+        Some(gguf_path) => {
+            let (mmap, header) = open_gguf(gguf_path)?;
+            config_model_value(&header, &mmap, None)?
+        }
+        None => serde_json::from_str(&fs::read_to_string(
+            Path::new(&config.root_path).join("config.json"),
+        )?)?,
+    };
+    let section = json!({
+        "group_size": quantization.group_size,
+        "bits": quantization.bits,
+    });
+    if let Value::Object(object) = &mut value {
+        object.insert("quantization".to_string(), section.clone());
+        object.insert("quantization_config".to_string(), section);
+    }
+    fs::write(
+        dst.join("config.json"),
+        serde_json::to_string_pretty(&value)?,
+    )?;
+    Ok(())
+}
+
+fn write_tokenizer(config: &Config, dst: &Path) -> Result<()> {
+    if let Some(gguf_path) = &config.gguf_path {
+        let (_, header) = open_gguf(gguf_path)?;
+        fs::write(
+            dst.join("tokenizer.json"),
+            serde_json::to_string(&tokenizer_json(&header)?)?,
+        )?;
+        fs::write(
+            dst.join("tokenizer_config.json"),
+            serde_json::to_string_pretty(&config.tokenizer_custom)?,
+        )?;
+        return Ok(());
+    }
+
+    for file in TOKENIZER_FILES {
+        let src = Path::new(&config.root_path).join(file);
+        if src.is_file() {
+            debug!("Copying {}", src.display());
+            fs::copy(&src, dst.join(file))?;
+        }
+    }
+    Ok(())
+}
+
+/// Quantize the full precision model in `src_path` and write it to `dst_path` as a
+/// sharded safetensors checkpoint, with its config and tokenizer files.
+/// Returns the number of shards written.
+pub fn convert_model(
+    src_path: &str,
+    dst_path: &str,
+    quantization: ModelQuantization,
+) -> Result<usize> {
+    let config = Rc::new(Config::new(src_path)?);
+    if config.model.quantization().is_some() {
+        return Err(Error::ModelAlreadyQuantized(src_path.to_string()));
+    }
+    let mut weight = Weight::new(&config, None)?;
+    // Same layout as at load time: experts stacked, tied heads dropped.
+    create_model_instance(config.clone())?
+        .write_lock("convert_model:sanitize")?
+        .sanitize(&mut weight);
+
+    let dst = Path::new(dst_path);
+    fs::create_dir_all(dst)?;
+
+    let ModelQuantization { bits, group_size } = quantization;
+    let mut names: Vec<&String> = weight.tensors.keys().collect();
+    names.sort();
+
+    let mut writer = ShardedSafetensorsWriter::new(dst, MAX_SHARD_BYTES);
+    for name in names {
+        let array = weight.tensors[name].data.as_ref();
+        let checkpoint_name = checkpoint_name(name);
+        if !should_quantize(name, array) {
+            writer.add(entry(checkpoint_name, array)?)?;
+            continue;
+        }
+
+        let last_dim = array.shape().last().copied().unwrap_or_default();
+        if last_dim % group_size != 0 {
+            return Err(Error::UnsupportedQuantization(format!(
+                "{}: last dimension {} is not divisible by group size {}",
+                name, last_dim, group_size
+            )));
+        }
+        debug!("Quantizing {} with {}", name, quantization);
+        let (w, scales, biases) = mlx_rs::ops::quantize(array, group_size, bits)?;
+        let prefix = checkpoint_name.trim_end_matches(".weight");
+        writer.add(entry(checkpoint_name.clone(), &w)?)?;
+        writer.add(entry(format!("{}.scales", prefix), &scales)?)?;
+        writer.add(entry(format!("{}.biases", prefix), &biases)?)?;
+    }
+    let num_shards = writer.finish()?;
+
+    write_config(&config, dst, quantization)?;
+    write_tokenizer(&config, dst)?;
+    // The loader finds the model root through its README.
+    fs::write(
+        dst.join("README.md"),
+        format!(
+            "Converted from {} with {} quantization.\n",
+            src_path, quantization
+        ),
+    )?;
+
+    info!(
+        "Model {} converted to {} in {} shard(s)",
+        src_path, dst_path, num_shards
+    );
+    Ok(num_shards)
+}
@@ -0,0 +1,196 @@
+use crate::error::Result;
+use crate::model::weight::INDEX_FILE_SAFETENSORS;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+static HEADER_ALIGNMENT_SAFETENSORS: usize = 8;
+
+#[derive(Debug)]
+pub struct SafetensorsEntry {
+    pub name: String,
+    pub dtype: &'static str,
+    pub shape: Vec<i32>,
+    pub data: Vec<u8>,
+}
+
+/// Write tensors to a single safetensors file: the 8-byte little-endian header size,
+/// the JSON header padded with spaces to 8 bytes, then the raw data.
+pub fn write_safetensors(path: &Path, entries: &[SafetensorsEntry]) -> Result<()> {
+    let mut header = Map::new();
+    header.insert("__metadata__".to_string(), json!({ "format": "mlx" }));
+
+    let mut offset = 0usize;
+    for entry in entries {
+        header.insert(
+            entry.name.clone(),
+            json!({
+                "dtype": entry.dtype,
+                "shape": entry.shape,
+                "data_offsets": [offset, offset + entry.data.len()],
+            }),
+        );
+        offset += entry.data.len();
+    }
+
+    let mut header = serde_json::to_vec(&Value::Object(header))?;
+    let padded_len =
+        header.len().div_ceil(HEADER_ALIGNMENT_SAFETENSORS) * HEADER_ALIGNMENT_SAFETENSORS;
+    header.resize(padded_len, b' ');
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(&(header.len() as u64).to_le_bytes())?;
+    writer.write_all(&header)?;
+    for entry in entries {
+        writer.write_all(&entry.data)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Accumulates tensors and flushes them to numbered shards once `max_shard_bytes` is
+/// reached. Shards are renamed to `model-0000i-of-0000N.safetensors` by `finish`.
+pub struct ShardedSafetensorsWriter<'a> {
+    dir: &'a Path,
+    max_shard_bytes: usize,
+    pending: Vec<SafetensorsEntry>,
+    pending_bytes: usize,
+    shards: Vec<Vec<String>>,
+    total_size: usize,
+}
+
+impl<'a> ShardedSafetensorsWriter<'a> {
+    pub fn new(dir: &'a Path, max_shard_bytes: usize) -> Self {
+        ShardedSafetensorsWriter {
+            dir,
+            max_shard_bytes,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            shards: Vec::new(),
+            total_size: 0,
+        }
+    }
+
+    fn temp_shard_path(&self, idx: usize) -> std::path::PathBuf {
+        self.dir
+            .join(format!("model-{:05}.safetensors.tmp", idx + 1))
+    }
+
+    pub fn add(&mut self, entry: SafetensorsEntry) -> Result<()> {
+        if !self.pending.is_empty() && self.pending_bytes + entry.data.len() > self.max_shard_bytes
+        {
+            self.flush()?;
+        }
+        self.pending_bytes += entry.data.len();
+        self.pending.push(entry);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let path = self.temp_shard_path(self.shards.len());
+        write_safetensors(&path, &self.pending)?;
+        self.total_size += self.pending_bytes;
+        self.shards
+            .push(self.pending.drain(..).map(|entry| entry.name).collect());
+        self.pending_bytes = 0;
+        Ok(())
+    }
+
+    /// Write the last shard, give every shard its final name and write the index when
+    /// there is more than one. Returns the number of shards.
+    pub fn finish(mut self) -> Result<usize> {
+        self.flush()?;
+        let num_shards = self.shards.len();
+
+        let mut weight_map = BTreeMap::new();
+        for (idx, names) in self.shards.iter().enumerate() {
+            let shard_name = if num_shards == 1 {
+                "model.safetensors".to_string()
+            } else {
+                format!("model-{:05}-of-{:05}.safetensors", idx + 1, num_shards)
+            };
+            fs::rename(self.temp_shard_path(idx), self.dir.join(&shard_name))?;
+            for name in names {
+                weight_map.insert(name.clone(), shard_name.clone());
+            }
+        }
+
+        if num_shards > 1 {
+            let index = json!({
+                "metadata": { "total_size": self.total_size },
+                "weight_map": weight_map,
+            });
+            fs::write(
+                self.dir.join(INDEX_FILE_SAFETENSORS),
+                serde_json::to_string_pretty(&index)?,
+            )?;
+        }
+        Ok(num_shards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::weight::{ModelIndexJSON, read_safetensors_header};
+
+    fn entry(name: &str, len: usize) -> SafetensorsEntry {
+        SafetensorsEntry {
+            name: name.to_string(),
+            dtype: "U8",
+            shape: vec![len as i32],
+            data: vec![7u8; len],
+        }
+    }
+
+    /// Three tensors of 10, 5 and 10 bytes written in shards of at most 16 bytes.
+    fn write_shards(dir: &Path) -> ModelIndexJSON {
+        let mut writer = ShardedSafetensorsWriter::new(dir, 16);
+        writer.add(entry("model.a.weight", 10)).unwrap();
+        writer.add(entry("model.b.weight", 5)).unwrap();
+        writer.add(entry("model.c.weight", 10)).unwrap();
+        assert_eq!(writer.finish().unwrap(), 2);
+        serde_json::from_str(&fs::read_to_string(dir.join(INDEX_FILE_SAFETENSORS)).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_writer_names_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = write_shards(dir.path());
+        assert_eq!(
+            index.shard_names(),
+            vec![
+                "model-00001-of-00002.safetensors",
+                "model-00002-of-00002.safetensors"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shards_match_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = write_shards(dir.path());
+        for shard in index.shard_names() {
+            let bytes = fs::read(dir.path().join(&shard)).unwrap();
+            let (header, size) = read_safetensors_header(&bytes).unwrap();
+            assert_eq!(size % HEADER_ALIGNMENT_SAFETENSORS, 0);
+            index.validate_shard(&shard, &header).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_shard_packs_tensors_back_to_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_shards(dir.path());
+        let bytes = fs::read(dir.path().join("model-00001-of-00002.safetensors")).unwrap();
+        let (header, size) = read_safetensors_header(&bytes).unwrap();
+        assert_eq!(header.tensors["model.b.weight"].data_offsets, [10, 15]);
+        assert_eq!(bytes.len(), 8 + size + 15);
+    }
+}
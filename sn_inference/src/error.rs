@@ -81,18 +81,33 @@ pub enum Error {
     #[error("Failed to read safetensors header")]
     SafetensorsHeaderReadError,
 
+    #[error("Safetensors index does not match shard '{shard}': {reason}")]
+    SafetensorsIndexMismatch { shard: String, reason: String },
+
+    #[error("Failed to read GGUF file: {0}")]
+    GgufReadError(String),
+
+    #[error("Unsupported GGUF content: {0}")]
+    GgufUnsupported(String),
+
     #[error("Invalid glob pattern")]
     GlobPatternError(#[from] glob::PatternError),
 
-    #[error("Missing rope configuration in config file")]
-    RopeConfigMissing,
-
     #[error("Failed to initialize token generation process")]
     TokenGenerationStartFailure,
 
     #[error("Unable to retrieve peak memory usage")]
     MemoryPeakQueryFailure,
 
+    #[error("Unable to reset peak memory usage")]
+    MemoryPeakResetFailure,
+
+    #[error("Unable to clear the memory cache")]
+    MemoryCacheClearFailure,
+
+    #[error("MLX gather matmul failed: {0}")]
+    GatherMatmulFailure(String),
+
     #[error("Failed to load MLX library function: {0}")]
     MlxFunctionLoadFailure(String),
 
@@ -119,6 +134,9 @@ pub enum Error {
     #[error("Empty prompt generated")]
     EmptyPrompt,
 
+    #[error("Model {0} has no fill-in-the-middle tokens")]
+    FimNotSupported(String),
+
     #[error("Model not found when generating text")]
     MissingModel,
 
@@ -140,6 +158,54 @@ pub enum Error {
     #[error("Failed to find padding token for encoding")]
     MissingPadToken,
 
+    #[error("Unsupported quantization: {0}")]
+    UnsupportedQuantization(String),
+
+    #[error("Model {0} is already running with quantization {1}")]
+    ModelRunningWithOtherQuantization(String, String),
+
+    #[error("Model {0} is already quantized")]
+    ModelAlreadyQuantized(String),
+
+    #[error("Model already exists at {0}")]
+    ModelAlreadyExists(String),
+
+    #[error("Unsupported dtype for safetensors: {0}")]
+    UnsupportedSafetensorsDtype(String),
+
+    #[error("Model {0} not found in the Hugging Face cache")]
+    HfCacheModelNotFound(String),
+
+    #[error("Revision {1} of {0} not found in the Hugging Face cache")]
+    HfCacheRevisionNotFound(String, String),
+
+    #[error("Blob missing for {0}, the download may be incomplete")]
+    HfCacheBlobMissing(String),
+
+    #[error("Invalid model name: {0}")]
+    InvalidModelName(String),
+
+    #[error("Model {0} is not installed")]
+    ModelNotInstalled(String),
+
+    #[error("No driver model loaded, embeddings are unavailable")]
+    DriverNotLoaded,
+
+    #[error("Cannot truncate embeddings to {0} dimensions, the model produces {1}")]
+    InvalidEmbeddingDimensions(usize, usize),
+
+    #[error("Model {0} needs {1} bytes, only {2} bytes of the memory budget can be freed")]
+    MemoryBudgetExceeded(String, u64, u64),
+
+    #[error("Unsupported LoRA adapter: {0}")]
+    UnsupportedLoraAdapter(String),
+
+    #[error("Adapter {0} not found")]
+    AdapterNotFound(String),
+
+    #[error("Adapter {0} is already loaded")]
+    AdapterAlreadyLoaded(String),
+
     #[error("Unexpected mask shape: {0}")]
     UnexpectedMaskShape(String),
 
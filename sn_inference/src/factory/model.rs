@@ -2,8 +2,11 @@ use crate::config::config::Config;
 use crate::config::config_model::ConfigModel;
 use crate::error::Result;
 use crate::model::model_kind::ModelKind;
+use crate::model::models::default::model::ModelDefault;
 use crate::model::models::llama::llama::ModelLLama;
+use crate::model::models::qwen2::qwen2::ModelQwen2;
 use crate::model::models::qwen3::qwen3::ModelQwen3;
+use crate::model::models::qwen3_moe::qwen3_moe::ModelQwen3Moe;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 //use crate::models::model_mistral::ModelMistral;
@@ -15,9 +18,22 @@ pub fn create_model_instance(config: Rc<Config>) -> Result<Arc<RwLock<ModelKind>
             let instance = ModelLLama::new(llama_config.clone())?;
             Ok(Arc::new(RwLock::new(ModelKind::LLaMA(instance))))
         }
+        ConfigModel::Qwen2(qwen2_config) => {
+            let instance = ModelQwen2::new(qwen2_config.clone())?;
+            Ok(Arc::new(RwLock::new(ModelKind::Qwen2(instance))))
+        }
         ConfigModel::Qwen3(qwen_config) => {
             let instance = ModelQwen3::new(qwen_config.clone())?;
             Ok(Arc::new(RwLock::new(ModelKind::Qwen3(instance))))
         }
+        ConfigModel::Qwen3Moe(qwen_moe_config) => {
+            let instance = ModelQwen3Moe::new(qwen_moe_config.clone())?;
+            Ok(Arc::new(RwLock::new(ModelKind::Qwen3Moe(instance))))
+        }
+        // Fallback for llama-like architectures without a dedicated module
+        ConfigModel::Default(default_config) => {
+            let instance = ModelDefault::new(default_config.clone())?;
+            Ok(Arc::new(RwLock::new(ModelKind::Default(instance))))
+        }
     }
 }
@@ -1,9 +1,12 @@
 mod cache;
 mod chat_template;
 mod config;
+mod convert;
 pub mod error;
 mod factory;
+mod lora;
 mod mask;
+mod memory;
 pub mod model;
 mod module;
 mod quantized;
@@ -0,0 +1,263 @@
+use crate::error::{Error, Result};
+use crate::lora::lora_config::LoraConfig;
+use crate::model::weight::load_weights;
+use mlx_rs::Array;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::debug;
+
+pub static ADAPTER_CONFIG_FILE: &str = "adapter_config.json";
+/// `adapters.safetensors` is written by mlx-lm, `adapter_model.safetensors` by PEFT.
+static ADAPTER_WEIGHTS_FILES: [&str; 2] = ["adapters.safetensors", "adapter_model.safetensors"];
+
+/// Projections a low-rank delta can be applied to, relative to a transformer block.
+static LORA_TARGETS: [&str; 7] = [
+    "self_attn.q_proj",
+    "self_attn.k_proj",
+    "self_attn.v_proj",
+    "self_attn.o_proj",
+    "mlp.gate_proj",
+    "mlp.up_proj",
+    "mlp.down_proj",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LoraMatrix {
+    /// mlx-lm `lora_a`, stored as `[input_dims, rank]`.
+    A,
+    /// mlx-lm `lora_b`, stored as `[rank, output_dims]`.
+    B,
+    /// PEFT `lora_A.weight`, stored as `[rank, input_dims]`.
+    PeftA,
+    /// PEFT `lora_B.weight`, stored as `[output_dims, rank]`.
+    PeftB,
+}
+
+/// Split an adapter tensor name into its layer index, the projection it targets and
+/// which of the two matrices it holds, e.g.
+/// `model.layers.3.self_attn.q_proj.lora_a` -> `(3, "self_attn.q_proj", A)`.
+fn parse_lora_name(name: &str) -> Option<(usize, &str, LoraMatrix)> {
+    let name = &name[name.find("layers.")? + "layers.".len()..];
+    let (idx, target) = name.split_once('.')?;
+    let (target, matrix) = if let Some(target) = target.strip_suffix(".lora_a") {
+        (target, LoraMatrix::A)
+    } else if let Some(target) = target.strip_suffix(".lora_b") {
+        (target, LoraMatrix::B)
+    } else if let Some(target) = target.strip_suffix(".lora_A.weight") {
+        (target, LoraMatrix::PeftA)
+    } else if let Some(target) = target.strip_suffix(".lora_B.weight") {
+        (target, LoraMatrix::PeftB)
+    } else {
+        return None;
+    };
+    Some((idx.parse().ok()?, target, matrix))
+}
+
+#[derive(Debug, Clone)]
+pub struct LoraDelta {
+    /// `[input_dims, rank]`
+    pub a: Array,
+    /// `[rank, output_dims]`
+    pub b: Array,
+    pub scale: Array,
+}
+
+impl LoraDelta {
+    pub fn forward(&self, x: &Array) -> Result<Array> {
+        Ok(x.matmul(&self.a)?.matmul(&self.b)?.multiply(&self.scale)?)
+    }
+}
+
+/// Deltas of one transformer block, keyed by projection path relative to the module
+/// they are handed to (`self_attn.q_proj` for the block, `q_proj` for the attention).
+#[derive(Debug, Default)]
+pub struct LoraLayer {
+    deltas: HashMap<String, LoraDelta>,
+}
+
+impl LoraLayer {
+    pub fn get(&self, name: &str) -> Option<&LoraDelta> {
+        self.deltas.get(name)
+    }
+
+    /// Deltas under `prefix`, with the prefix removed. `None` when there are none.
+    pub fn scoped(&self, prefix: &str) -> Option<Arc<LoraLayer>> {
+        let prefix = format!("{}.", prefix);
+        let deltas: HashMap<String, LoraDelta> = self
+            .deltas
+            .iter()
+            .filter_map(|(name, delta)| {
+                name.strip_prefix(&prefix)
+                    .map(|name| (name.to_string(), delta.clone()))
+            })
+            .collect();
+        (!deltas.is_empty()).then(|| Arc::new(LoraLayer { deltas }))
+    }
+}
+
+/// A LoRA adapter loaded on top of a running model. Deltas are applied at forward time
+/// and never merged into the base weights.
+#[derive(Debug)]
+pub struct LoraAdapter {
+    pub name: String,
+    pub path: String,
+    pub rank: i32,
+    pub scale: f32,
+    layers: HashMap<usize, Arc<LoraLayer>>,
+}
+
+impl LoraAdapter {
+    /// `sparse_mlp_layers` are mixture of experts layers, their `mlp.*` deltas would be
+    /// ignored and are refused.
+    pub fn load(
+        name: &str,
+        path: &str,
+        num_layers: usize,
+        sparse_mlp_layers: &[usize],
+    ) -> Result<LoraAdapter> {
+        let root = Path::new(path);
+        let config_path = root.join(ADAPTER_CONFIG_FILE);
+        let config: LoraConfig = serde_json::from_str(
+            &fs::read_to_string(&config_path)
+                .map_err(|_| Error::FileOpenError(config_path.display().to_string()))?,
+        )?;
+        config.validate()?;
+
+        let weights_path = ADAPTER_WEIGHTS_FILES
+            .iter()
+            .map(|file| root.join(file))
+            .find(|path| path.is_file())
+            .ok_or_else(|| Error::ModelWeightPathNotFound(path.to_string()))?;
+        let weight = load_weights(&vec![weights_path.display().to_string()], None, None)?;
+
+        let scale = Array::from_f32(config.scale());
+        let mut matrices: HashMap<(usize, String), (Option<Array>, Option<Array>)> = HashMap::new();
+        for (name, tensor) in &weight.tensors {
+            let (idx, target, matrix) = parse_lora_name(name)
+                .ok_or_else(|| Error::UnsupportedLoraAdapter(format!("tensor '{}'", name)))?;
+            check_target(idx, target, num_layers, sparse_mlp_layers)?;
+            let entry = matrices.entry((idx, target.to_string())).or_default();
+            let array = tensor.data.as_ref();
+            match matrix {
+                LoraMatrix::A => entry.0 = Some(array.clone()),
+                LoraMatrix::B => entry.1 = Some(array.clone()),
+                LoraMatrix::PeftA => entry.0 = Some(array.t()),
+                LoraMatrix::PeftB => entry.1 = Some(array.t()),
+            }
+        }
+
+        let mut layers: HashMap<usize, LoraLayer> = HashMap::new();
+        for ((idx, target), pair) in matrices {
+            let (Some(a), Some(b)) = pair else {
+                return Err(Error::UnsupportedLoraAdapter(format!(
+                    "layer {} {} is missing one of its matrices",
+                    idx, target
+                )));
+            };
+            if a.shape()[1] != b.shape()[0] {
+                return Err(Error::UnsupportedLoraAdapter(format!(
+                    "layer {} {} has mismatched ranks {:?} and {:?}",
+                    idx,
+                    target,
+                    a.shape(),
+                    b.shape()
+                )));
+            }
+            layers.entry(idx).or_default().deltas.insert(
+                target,
+                LoraDelta {
+                    a,
+                    b,
+                    scale: scale.clone(),
+                },
+            );
+        }
+        debug!("Loaded adapter {} on {} layers", name, layers.len());
+
+        Ok(LoraAdapter {
+            name: name.to_string(),
+            path: path.to_string(),
+            rank: config.rank(),
+            scale: config.scale(),
+            layers: layers
+                .into_iter()
+                .map(|(idx, layer)| (idx, Arc::new(layer)))
+                .collect(),
+        })
+    }
+
+    pub fn layer(&self, idx: usize) -> Option<Arc<LoraLayer>> {
+        self.layers.get(&idx).cloned()
+    }
+
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+}
+
+fn check_target(
+    idx: usize,
+    target: &str,
+    num_layers: usize,
+    sparse_mlp_layers: &[usize],
+) -> Result<()> {
+    if idx >= num_layers || !LORA_TARGETS.contains(&target) {
+        return Err(Error::UnsupportedLoraAdapter(format!(
+            "target '{}' of layer {}",
+            target, idx
+        )));
+    }
+    if target.starts_with("mlp.") && sparse_mlp_layers.contains(&idx) {
+        return Err(Error::UnsupportedLoraAdapter(format!(
+            "target '{}' of layer {}, a mixture of experts layer",
+            target, idx
+        )));
+    }
+    Ok(())
+}
+
+/// Whether two adapter selections point to the same loaded adapter.
+pub fn is_same_adapter(a: &Option<Arc<LoraAdapter>>, b: &Option<Arc<LoraAdapter>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lora_name() {
+        assert_eq!(
+            parse_lora_name("model.layers.3.self_attn.q_proj.lora_a"),
+            Some((3, "self_attn.q_proj", LoraMatrix::A))
+        );
+        assert_eq!(
+            parse_lora_name("base_model.model.model.layers.12.mlp.down_proj.lora_B.weight"),
+            Some((12, "mlp.down_proj", LoraMatrix::PeftB))
+        );
+        assert_eq!(
+            parse_lora_name("model.layers.3.self_attn.q_proj.weight"),
+            None
+        );
+        assert_eq!(parse_lora_name("lm_head.lora_a"), None);
+    }
+
+    #[test]
+    fn test_check_target() {
+        assert!(check_target(3, "self_attn.q_proj", 4, &[]).is_ok());
+        assert!(check_target(3, "mlp.down_proj", 4, &[1]).is_ok());
+        assert!(check_target(3, "self_attn.q_proj", 4, &[3]).is_ok());
+        assert!(matches!(
+            check_target(3, "mlp.down_proj", 4, &[1, 3]),
+            Err(Error::UnsupportedLoraAdapter(_))
+        ));
+        assert!(check_target(4, "self_attn.q_proj", 4, &[]).is_err());
+        assert!(check_target(0, "mlp.experts.0.down_proj", 4, &[]).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::error::{Error, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoraParameters {
+    pub rank: i32,
+    pub scale: f32,
+}
+
+/// `adapter_config.json`, either as written by `mlx_lm.lora` or by PEFT.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LoraConfig {
+    Mlx {
+        #[serde(default)]
+        fine_tune_type: Option<String>,
+        lora_parameters: LoraParameters,
+    },
+    Peft {
+        r: i32,
+        lora_alpha: f32,
+        #[serde(default)]
+        use_rslora: bool,
+    },
+}
+
+impl LoraConfig {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            LoraConfig::Mlx {
+                fine_tune_type: Some(fine_tune_type),
+                ..
+            } if fine_tune_type != "lora" => Err(Error::UnsupportedLoraAdapter(format!(
+                "fine tune type '{}'",
+                fine_tune_type
+            ))),
+            _ if self.rank() <= 0 => Err(Error::UnsupportedLoraAdapter(format!(
+                "rank {}",
+                self.rank()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn rank(&self) -> i32 {
+        match self {
+            LoraConfig::Mlx {
+                lora_parameters, ..
+            } => lora_parameters.rank,
+            LoraConfig::Peft { r, .. } => *r,
+        }
+    }
+
+    /// Factor applied to the low-rank delta. PEFT stores `lora_alpha` and derives the
+    /// scale from the rank, mlx-lm stores the scale directly.
+    pub fn scale(&self) -> f32 {
+        match self {
+            LoraConfig::Mlx {
+                lora_parameters, ..
+            } => lora_parameters.scale,
+            LoraConfig::Peft {
+                r,
+                lora_alpha,
+                use_rslora,
+            } => {
+                if *use_rslora {
+                    lora_alpha / (*r as f32).sqrt()
+                } else {
+                    lora_alpha / *r as f32
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lora_config_formats() {
+        let mlx: LoraConfig = serde_json::from_str(
+            r#"{"fine_tune_type":"lora","num_layers":16,"lora_parameters":{"rank":8,"scale":20.0,"dropout":0.0}}"#,
+        )
+        .unwrap();
+        assert!(mlx.validate().is_ok());
+        assert_eq!((mlx.rank(), mlx.scale()), (8, 20.0));
+
+        let peft: LoraConfig = serde_json::from_str(
+            r#"{"peft_type":"LORA","r":16,"lora_alpha":32,"target_modules":["q_proj","v_proj"]}"#,
+        )
+        .unwrap();
+        assert_eq!((peft.rank(), peft.scale()), (16, 2.0));
+
+        let dora: LoraConfig = serde_json::from_str(
+            r#"{"fine_tune_type":"dora","lora_parameters":{"rank":8,"scale":20.0}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            dora.validate(),
+            Err(Error::UnsupportedLoraAdapter(_))
+        ));
+    }
+}
@@ -0,0 +1,22 @@
+use crate::error::Result;
+use crate::lora::lora_adapter::LoraLayer;
+use mlx_rs::Array;
+use mlx_rs::module::Module as MLXModule;
+use mlx_rs::nn::Linear;
+use mlx_rs::quantization::MaybeQuantized;
+
+/// Linear projection with an optional low-rank delta added to its output. The base
+/// weights are left untouched so several adapters can share the same model.
+pub trait LoraLinear {
+    fn forward_lora(&mut self, x: &Array, lora: Option<&LoraLayer>, name: &str) -> Result<Array>;
+}
+
+impl LoraLinear for MaybeQuantized<Linear> {
+    fn forward_lora(&mut self, x: &Array, lora: Option<&LoraLayer>, name: &str) -> Result<Array> {
+        let y = self.forward(x)?;
+        match lora.and_then(|lora| lora.get(name)) {
+            Some(delta) => Ok(&y + delta.forward(x)?.as_dtype(y.dtype())?),
+            None => Ok(y),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub(crate) mod lora_adapter;
+pub(crate) mod lora_config;
+pub(crate) mod lora_linear;
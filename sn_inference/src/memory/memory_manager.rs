@@ -0,0 +1,271 @@
+use crate::error::{Error, Result};
+use sn_core::types::model_quantization::ModelQuantization;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Memory the loaded models may use together, e.g. `24G` or `512M`. Unlimited when unset.
+const ENV_MEMORY_BUDGET: &str = "SANAGA_MEMORY_BUDGET";
+/// Seconds after which a model nobody used is unloaded. Disabled when unset.
+const ENV_MODEL_IDLE_TTL: &str = "SANAGA_MODEL_IDLE_TTL";
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryConfig {
+    pub budget: Option<u64>,
+    pub idle_ttl: Option<Duration>,
+}
+
+impl MemoryConfig {
+    pub fn from_env() -> MemoryConfig {
+        MemoryConfig {
+            budget: std::env::var(ENV_MEMORY_BUDGET)
+                .ok()
+                .and_then(|value| parse_bytes(&value)),
+            idle_ttl: std::env::var(ENV_MODEL_IDLE_TTL)
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs),
+        }
+    }
+}
+
+/// `1073741824`, `1024M`, `1G` or `1GB` -> `1073741824`
+fn parse_bytes(value: &str) -> Option<u64> {
+    let value = value.trim().to_uppercase();
+    let value = value.strip_suffix('B').unwrap_or(&value);
+    let (number, unit) = match value.char_indices().last()? {
+        (idx, 'K') => (&value[..idx], 1u64 << 10),
+        (idx, 'M') => (&value[..idx], 1u64 << 20),
+        (idx, 'G') => (&value[..idx], 1u64 << 30),
+        (idx, 'T') => (&value[..idx], 1u64 << 40),
+        _ => (value, 1),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    Some((number * unit as f64) as u64)
+}
+
+/// Size of the weights of the model installed in `root_path`, read from the files on
+/// disk before the model is loaded.
+pub fn estimate_model_bytes(root_path: &str, quantization: Option<ModelQuantization>) -> u64 {
+    let bytes: u64 = WalkDir::new(root_path)
+        .into_iter()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "safetensors" || ext == "gguf")
+        })
+        .filter_map(|entry| std::fs::metadata(entry.path()).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    // Quantizing on load shrinks half precision weights.
+    match quantization {
+        Some(q) => bytes * q.bits as u64 / 16,
+        None => bytes,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ModelUsage {
+    model_bytes: u64,
+    kv_bytes: u64,
+    last_used: Instant,
+    /// Order of use, `Instant`s can be equal for models used in a row.
+    last_used_order: u64,
+    quantization: Option<ModelQuantization>,
+}
+
+impl ModelUsage {
+    fn bytes(&self) -> u64 {
+        self.model_bytes + self.kv_bytes
+    }
+}
+
+/// Tracks the memory used by each loaded model and picks the ones to unload, least
+/// recently used first, when a new model would not fit in the budget.
+#[derive(Debug, Default)]
+pub struct MemoryManager {
+    config: MemoryConfig,
+    usage: HashMap<String, ModelUsage>,
+    uses: u64,
+    /// Weights of the driver, always loaded and never unloaded.
+    driver_bytes: u64,
+    /// Quantization of the unloaded models, reused when they are loaded again lazily.
+    unloaded: HashMap<String, Option<ModelQuantization>>,
+}
+
+impl MemoryManager {
+    pub fn new(config: MemoryConfig) -> MemoryManager {
+        MemoryManager {
+            config,
+            ..MemoryManager::default()
+        }
+    }
+
+    pub fn register(
+        &mut self,
+        model_id: &str,
+        model_bytes: u64,
+        quantization: Option<ModelQuantization>,
+    ) {
+        self.unloaded.remove(model_id);
+        self.uses += 1;
+        self.usage.insert(
+            model_id.to_string(),
+            ModelUsage {
+                model_bytes,
+                kv_bytes: 0,
+                last_used: Instant::now(),
+                last_used_order: self.uses,
+                quantization,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, model_id: &str) {
+        if let Some(usage) = self.usage.remove(model_id) {
+            self.unloaded
+                .insert(model_id.to_string(), usage.quantization);
+        }
+    }
+
+    pub fn touch(&mut self, model_id: &str) {
+        self.uses += 1;
+        if let Some(usage) = self.usage.get_mut(model_id) {
+            usage.last_used = Instant::now();
+            usage.last_used_order = self.uses;
+        }
+    }
+
+    pub fn set_driver_bytes(&mut self, driver_bytes: u64) {
+        self.driver_bytes = driver_bytes;
+    }
+
+    /// Size of the KV caches `model_id` keeps in memory.
+    pub fn record_kv_bytes(&mut self, model_id: &str, kv_bytes: u64) {
+        if let Some(usage) = self.usage.get_mut(model_id) {
+            usage.kv_bytes = kv_bytes;
+        }
+    }
+
+    pub fn unloaded_quantization(&self, model_id: &str) -> Option<Option<ModelQuantization>> {
+        self.unloaded.get(model_id).copied()
+    }
+
+    pub fn used_bytes(&self) -> u64 {
+        self.driver_bytes + self.usage.values().map(ModelUsage::bytes).sum::<u64>()
+    }
+
+    /// Models to unload, least recently used first, so that `incoming` bytes fit in the
+    /// budget. Models in `pinned` are being used and are never picked.
+    pub fn plan_eviction(
+        &self,
+        name: &str,
+        incoming: u64,
+        pinned: &[String],
+    ) -> Result<Vec<String>> {
+        let Some(budget) = self.config.budget else {
+            return Ok(Vec::new());
+        };
+
+        let mut candidates: Vec<(&String, &ModelUsage)> = self
+            .usage
+            .iter()
+            .filter(|(model_id, _)| !pinned.contains(model_id))
+            .collect();
+        candidates.sort_by_key(|(_, usage)| usage.last_used_order);
+
+        let mut used = self.used_bytes();
+        let mut evicted = Vec::new();
+        for (model_id, usage) in candidates {
+            if used + incoming <= budget {
+                break;
+            }
+            used -= usage.bytes();
+            evicted.push(model_id.clone());
+        }
+        if used + incoming > budget {
+            return Err(Error::MemoryBudgetExceeded(
+                name.to_string(),
+                incoming,
+                budget.saturating_sub(used),
+            ));
+        }
+        Ok(evicted)
+    }
+
+    /// Models not used for longer than the idle TTL.
+    pub fn idle_models(&self) -> Vec<String> {
+        let Some(idle_ttl) = self.config.idle_ttl else {
+            return Vec::new();
+        };
+        self.usage
+            .iter()
+            .filter(|(_, usage)| usage.last_used.elapsed() >= idle_ttl)
+            .map(|(model_id, _)| model_id.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("1024"), Some(1024));
+        assert_eq!(parse_bytes("512M"), Some(512 << 20));
+        assert_eq!(parse_bytes("1.5gb"), Some(3 << 29));
+        assert_eq!(parse_bytes("lots"), None);
+    }
+
+    #[test]
+    fn test_plan_eviction_least_recently_used_first() {
+        let mut manager = MemoryManager::new(MemoryConfig {
+            budget: Some(100),
+            idle_ttl: None,
+        });
+        manager.register("a", 40, None);
+        manager.register("b", 30, None);
+        manager.register("c", 20, None);
+        manager.touch("a");
+
+        assert!(manager.plan_eviction("d", 10, &[]).unwrap().is_empty());
+        assert_eq!(manager.plan_eviction("d", 30, &[]).unwrap(), vec!["b"]);
+        assert_eq!(
+            manager.plan_eviction("d", 50, &["b".to_string()]).unwrap(),
+            vec!["c", "a"]
+        );
+        assert!(matches!(
+            manager.plan_eviction("d", 50, &["a".to_string(), "b".to_string()]),
+            Err(Error::MemoryBudgetExceeded(_, 50, 30))
+        ));
+
+        manager.record_kv_bytes("c", 20);
+        assert_eq!(manager.used_bytes(), 110);
+
+        manager.remove("b");
+        assert_eq!(manager.unloaded_quantization("b"), Some(None));
+        assert_eq!(manager.unloaded_quantization("a"), None);
+    }
+
+    #[test]
+    fn test_plan_eviction_counts_driver() {
+        let mut manager = MemoryManager::new(MemoryConfig {
+            budget: Some(100),
+            idle_ttl: None,
+        });
+        manager.set_driver_bytes(30);
+        manager.register("a", 40, None);
+        assert_eq!(manager.used_bytes(), 70);
+
+        assert!(manager.plan_eviction("b", 30, &[]).unwrap().is_empty());
+        assert_eq!(manager.plan_eviction("b", 40, &[]).unwrap(), vec!["a"]);
+        // The driver is never unloaded, 70 bytes at most fit next to it.
+        assert!(matches!(
+            manager.plan_eviction("b", 80, &[]),
+            Err(Error::MemoryBudgetExceeded(_, 80, 70))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::error::Result;
+use crate::model::model_runtime::ModelRuntime;
+use serde::{Deserialize, Serialize};
+use sn_core::types::embedding_options::EmbeddingOptions;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+pub static DRIVER_ID: &str = "driver";
+pub static DRIVER_CONFIG_FILE: &str = "driver.json";
+pub static DEFAULT_DRIVER_MODEL_NAME: &str = "models--Qwen--Qwen3-1.7B-MLX-4bit";
+/// Driver model to load on start, takes precedence over the one saved in `driver.json`.
+pub static ENV_DRIVER_MODEL: &str = "SANAGA_DRIVER_MODEL";
+static EMBEDDING_DIM_PROBE: &str = "embedding dimension";
+
+/// Driver selected through the API, kept across restarts with the embedding dimension
+/// it produced so a change of dimension can be detected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriverConfig {
+    pub name: Option<String>,
+    pub embedding_dim: Option<usize>,
+}
+
+impl DriverConfig {
+    pub fn load(path: &Path) -> Result<DriverConfig> {
+        if !path.is_file() {
+            return Ok(DriverConfig::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// The model used for embeddings and similarity, next to the chat models.
+#[derive(Debug)]
+pub struct Driver {
+    pub name: String,
+    pub path: String,
+    pub embedding_dim: usize,
+    pub model_runtime: Arc<RwLock<ModelRuntime>>,
+}
+
+impl Driver {
+    pub fn load(name: &str, path: &str) -> Result<Driver> {
+        let mut model_runtime =
+            ModelRuntime::load_with_path(path, &DRIVER_ID.to_string(), name, None)?;
+        model_runtime.routine_model(None)?;
+        let embedding_dim = model_runtime
+            .generate_embeddings(
+                &vec![EMBEDDING_DIM_PROBE.to_string()],
+                &EmbeddingOptions::default(),
+            )?
+            .shape()
+            .last()
+            .copied()
+            .unwrap_or_default() as usize;
+        info!(
+            "Driver {} loaded with embedding dimension {}",
+            name, embedding_dim
+        );
+        Ok(Driver {
+            name: name.to_string(),
+            path: path.to_string(),
+            embedding_dim,
+            model_runtime: Arc::new(RwLock::new(model_runtime)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_driver_config_defaults_when_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DriverConfig::load(&dir.path().join(DRIVER_CONFIG_FILE)).unwrap();
+        assert!(config.name.is_none());
+        assert!(config.embedding_dim.is_none());
+    }
+
+    #[test]
+    fn test_driver_config_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DRIVER_CONFIG_FILE);
+
+        DriverConfig {
+            name: Some("Qwen3-Embedding-0.6B".to_string()),
+            embedding_dim: Some(1024),
+        }
+        .save(&path)
+        .unwrap();
+        let config = DriverConfig::load(&path).unwrap();
+        assert_eq!(config.name.as_deref(), Some("Qwen3-Embedding-0.6B"));
+        assert_eq!(config.embedding_dim, Some(1024));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::config::config_model::ConfigModel;
+use crate::config::config_tokenizer_custom::ConfigTokenizerCustom;
+use crate::error::{Error, Result};
+use crate::model::gguf::reader::{GgmlType, GgufHeader, GgufValue};
+use crate::model::gguf::tensor::GGUF_GROUP_SIZE;
+use serde_json::{Map, Value, json};
+
+impl GgufHeader {
+    pub fn architecture(&self) -> Result<&str> {
+        self.get("general.architecture")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::GgufReadError("missing general.architecture".to_string()))
+    }
+
+    fn arch_value(&self, key: &str) -> Option<&GgufValue> {
+        let arch = self.architecture().ok()?;
+        self.get(&format!("{}.{}", arch, key))
+    }
+
+    fn arch_i64(&self, key: &str) -> Result<i64> {
+        self.arch_value(key)
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| Error::GgufReadError(format!("missing metadata {}", key)))
+    }
+
+    pub fn num_attention_heads(&self) -> Result<i64> {
+        self.arch_i64("attention.head_count")
+    }
+
+    pub fn num_key_value_heads(&self) -> Result<i64> {
+        self.arch_i64("attention.head_count_kv")
+            .or_else(|_| self.num_attention_heads())
+    }
+
+    pub fn tokens(&self) -> Result<Vec<&str>> {
+        self.get("tokenizer.ggml.tokens")
+            .and_then(|v| v.as_array())
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .map(|t| t.as_str().unwrap_or_default())
+                    .collect()
+            })
+            .ok_or_else(|| Error::GgufReadError("missing tokenizer.ggml.tokens".to_string()))
+    }
+
+    pub fn token_id(&self, key: &str) -> Option<i64> {
+        self.get(&format!("tokenizer.ggml.{}_token_id", key))
+            .and_then(|v| v.as_i64())
+    }
+
+    /// Rope frequency factors written by llama.cpp for Llama 3.1+ in place of the
+    /// `llama3` rope scaling parameters.
+    fn rope_freqs(&self, buffer: &[u8]) -> Result<Option<Vec<f32>>> {
+        let Some(info) = self
+            .tensor("rope_freqs.weight")
+            .filter(|info| info.ggml_type == GgmlType::F32)
+        else {
+            return Ok(None);
+        };
+        let data = self.tensor_data(buffer, info)?;
+        Ok(Some(
+            data.chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+        ))
+    }
+}
+
+/// `model_type` of the transformers config matching a GGUF architecture.
+fn model_type(arch: &str) -> &str {
+    match arch {
+        "qwen3moe" => "qwen3_moe",
+        other => other,
+    }
+}
+
+/// Build a transformers style `config.json` from the GGUF metadata, so the model goes
+/// through the same `ConfigModel` deserializer as the safetensors checkpoints. `buffer`
+/// is the mapped file, the rope frequencies of Llama 3.1+ are stored as a tensor.
+pub fn config_model_value(
+    header: &GgufHeader,
+    buffer: &[u8],
+    quantization_bits: Option<i32>,
+) -> Result<Value> {
+    let arch = header.architecture()?;
+    let num_hidden_layers = header.arch_i64("block_count")?;
+    let hidden_size = header.arch_i64("embedding_length")?;
+    let intermediate_size = header
+        .arch_i64("feed_forward_length")
+        .or_else(|_| header.arch_i64("expert_feed_forward_length"))?;
+    let vocab_size = match header.arch_i64("vocab_size") {
+        Ok(vocab_size) => vocab_size,
+        Err(_) => header.tokens()?.len() as i64,
+    };
+    let eos_token_id = header.token_id("eos").unwrap_or_default();
+    let mut eos_token_ids = vec![eos_token_id];
+    if let Some(eot) = header.token_id("eot").filter(|eot| *eot != eos_token_id) {
+        eos_token_ids.push(eot);
+    }
+
+    let mut config = json!({
+        "model_type": model_type(arch),
+        "architectures": [arch],
+        "hidden_size": hidden_size,
+        "intermediate_size": intermediate_size,
+        "num_hidden_layers": num_hidden_layers,
+        "num_attention_heads": header.num_attention_heads()?,
+        "num_key_value_heads": header.num_key_value_heads()?,
+        "max_position_embeddings": header.arch_i64("context_length").unwrap_or(4096),
+        "vocab_size": vocab_size,
+        "rms_norm_eps": header
+            .arch_value("attention.layer_norm_rms_epsilon")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1e-6),
+        "rope_theta": header
+            .arch_value("rope.freq_base")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(10_000.0),
+        "bos_token_id": header.token_id("bos"),
+        "eos_token_id": if arch == "llama" { json!(eos_token_ids) } else { json!(eos_token_id) },
+        "pad_token_id": header.token_id("padding"),
+        "tie_word_embeddings": header.tensor("output.weight").is_none(),
+        "attention_bias": header.tensor("blk.0.attn_q.bias").is_some(),
+        "hidden_act": "silu",
+        "attention_dropout": 0.0,
+        "initializer_range": 0.02,
+        "mlp_bias": false,
+        "pretraining_tp": 1,
+        "max_window_layers": num_hidden_layers,
+        "use_sliding_window": false,
+        "use_cache": true,
+        "torch_dtype": "float16",
+        "transformers_version": "gguf",
+    });
+
+    let object = config.as_object_mut().unwrap();
+    if let Ok(head_dim) = header.arch_i64("attention.key_length") {
+        object.insert("head_dim".to_string(), json!(head_dim));
+    }
+    if let Some(bits) = quantization_bits {
+        object.insert(
+            "quantization".to_string(),
+            json!({ "group_size": GGUF_GROUP_SIZE, "bits": bits }),
+        );
+    }
+    if let Some(rope_type) = header
+        .arch_value("rope.scaling.type")
+        .and_then(|v| v.as_str())
+        .filter(|t| *t != "none")
+    {
+        let mut rope_scaling = Map::new();
+        rope_scaling.insert("rope_type".to_string(), json!(rope_type));
+        if let Some(factor) = header
+            .arch_value("rope.scaling.factor")
+            .and_then(|v| v.as_f64())
+        {
+            rope_scaling.insert("factor".to_string(), json!(factor));
+        }
+        if let Ok(original) = header.arch_i64("rope.scaling.original_context_length") {
+            rope_scaling.insert(
+                "original_max_position_embeddings".to_string(),
+                json!(original),
+            );
+        }
+        object.insert("rope_scaling".to_string(), Value::Object(rope_scaling));
+    } else if let Some(rope_freqs) = header.rope_freqs(buffer)? {
+        object.insert(
+            "rope_scaling".to_string(),
+            json!({ "rope_type": "llama3", "rope_freqs": rope_freqs }),
+        );
+    }
+    if let Ok(num_experts) = header.arch_i64("expert_count") {
+        object.insert("num_experts".to_string(), json!(num_experts));
+        object.insert(
+            "num_experts_per_tok".to_string(),
+            json!(header.arch_i64("expert_used_count")?),
+        );
+        object.insert(
+            "moe_intermediate_size".to_string(),
+            json!(header.arch_i64("expert_feed_forward_length")?),
+        );
+        object.insert("norm_topk_prob".to_string(), json!(true));
+    }
+
+    Ok(config)
+}
+
+pub fn config_model(
+    header: &GgufHeader,
+    buffer: &[u8],
+    quantization_bits: Option<i32>,
+) -> Result<ConfigModel> {
+    let value = config_model_value(header, buffer, quantization_bits)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+pub fn config_tokenizer_custom(header: &GgufHeader) -> Result<ConfigTokenizerCustom> {
+    let token = |key: &str| -> Result<Option<String>> {
+        Ok(match header.token_id(key) {
+            Some(id) => header.tokens()?.get(id as usize).map(|t| t.to_string()),
+            None => None,
+        })
+    };
+    Ok(ConfigTokenizerCustom {
+        chat_template: header
+            .get("tokenizer.chat_template")
+            .and_then(|v| v.as_str())
+            .map(|t| t.to_string()),
+        pad_token: token("padding")?,
+        bos_token: token("bos")?,
+        eos_token: token("eos")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::gguf::reader::read_gguf_header;
+    use crate::model::gguf::reader::tests::GgufBuilder;
+
+    fn qwen3_header() -> Vec<u8> {
+        GgufBuilder::default()
+            .string("general.architecture", "qwen3")
+            .u32("qwen3.block_count", 28)
+            .u32("qwen3.embedding_length", 1024)
+            .u32("qwen3.feed_forward_length", 3072)
+            .u32("qwen3.attention.head_count", 16)
+            .u32("qwen3.attention.head_count_kv", 8)
+            .u32("qwen3.attention.key_length", 128)
+            .u32("qwen3.context_length", 40960)
+            .f32("qwen3.rope.freq_base", 1_000_000.0)
+            .f32("qwen3.attention.layer_norm_rms_epsilon", 1e-6)
+            .strings("tokenizer.ggml.tokens", &["a", "b", "<|endoftext|>"])
+            .u32("tokenizer.ggml.eos_token_id", 2)
+            .u32("tokenizer.ggml.padding_token_id", 2)
+            .string("tokenizer.chat_template", "{{ messages }}")
+            .build()
+    }
+
+    #[test]
+    fn test_config_model_from_gguf() {
+        let buffer = qwen3_header();
+        let header = read_gguf_header(&buffer).unwrap();
+        match config_model(&header, &buffer, Some(4)).unwrap() {
+            ConfigModel::Qwen3(config) => {
+                assert_eq!(config.num_hidden_layers, 28);
+                assert_eq!(config.head_dim, Some(128));
+                assert_eq!(config.vocab_size, 3);
+                assert_eq!(config.eos_token_id, 2);
+                assert!(config.tie_word_embeddings);
+                assert_eq!(config.quantization.as_ref().map(|q| q.bits), Some(4));
+            }
+            other => panic!("expected a qwen3 config, got {:?}", other),
+        }
+
+        let tokenizer_custom = config_tokenizer_custom(&header).unwrap();
+        assert_eq!(tokenizer_custom.pad_token.as_deref(), Some("<|endoftext|>"));
+        assert_eq!(tokenizer_custom.get_chat_template(), "{{ messages }}");
+    }
+
+    #[test]
+    fn test_llama_without_bos_from_gguf() {
+        let buffer = GgufBuilder::default()
+            .string("general.architecture", "llama")
+            .u32("llama.block_count", 16)
+            .u32("llama.embedding_length", 2048)
+            .u32("llama.feed_forward_length", 8192)
+            .u32("llama.attention.head_count", 32)
+            .u32("llama.attention.head_count_kv", 8)
+            .strings("tokenizer.ggml.tokens", &["a", "</s>"])
+            .u32("tokenizer.ggml.eos_token_id", 1)
+            .build();
+        let header = read_gguf_header(&buffer).unwrap();
+        match config_model(&header, &buffer, None).unwrap() {
+            ConfigModel::LLaMA(config) => {
+                assert_eq!(config.bos_token_id, None);
+                assert_eq!(config.eos_token_id, vec![1]);
+            }
+            other => panic!("expected a llama config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_llama3_rope_freqs_from_gguf() {
+        let rope_freqs = [1.0f32, 1.5, 8.0, 8.0];
+        let buffer = GgufBuilder::default()
+            .string("general.architecture", "llama")
+            .u32("llama.block_count", 16)
+            .u32("llama.embedding_length", 2048)
+            .u32("llama.feed_forward_length", 8192)
+            .u32("llama.attention.head_count", 32)
+            .u32("llama.attention.head_count_kv", 8)
+            .u32("llama.context_length", 131072)
+            .f32("llama.rope.freq_base", 500_000.0)
+            .strings(
+                "tokenizer.ggml.tokens",
+                &["<|begin_of_text|>", "a", "<|eot_id|>"],
+            )
+            .u32("tokenizer.ggml.bos_token_id", 0)
+            .u32("tokenizer.ggml.eos_token_id", 2)
+            .tensor(
+                "rope_freqs.weight",
+                &[4],
+                0,
+                rope_freqs.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            )
+            .build();
+        let header = read_gguf_header(&buffer).unwrap();
+        match config_model(&header, &buffer, None).unwrap() {
+            ConfigModel::LLaMA(config) => {
+                let rope_scaling = config.rope_scaling.as_ref().unwrap();
+                assert_eq!(rope_scaling.rope_type(), "llama3");
+                assert_eq!(rope_scaling.rope_freqs.as_deref(), Some(&rope_freqs[..]));
+            }
+            other => panic!("expected a llama config, got {:?}", other),
+        }
+    }
+}
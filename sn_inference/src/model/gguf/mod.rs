@@ -0,0 +1,17 @@
+pub(crate) mod metadata;
+pub(crate) mod reader;
+pub(crate) mod tensor;
+pub(crate) mod tokenizer;
+
+/// First `*.gguf` file of a model directory, used when it has no `config.json`.
+pub fn find_gguf_file(model_path: &str) -> Option<String> {
+    let mut files: Vec<String> = std::fs::read_dir(model_path)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gguf"))
+        .map(|path| path.display().to_string())
+        .collect();
+    files.sort();
+    files.into_iter().next()
+}
@@ -0,0 +1,516 @@
+use crate::error::{Error, Result};
+use memmap2::{Mmap, MmapOptions};
+use std::collections::HashMap;
+use std::fs::File;
+
+static GGUF_MAGIC: &[u8; 4] = b"GGUF";
+static GGUF_DEFAULT_ALIGNMENT: u64 = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GgufValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    F32(f32),
+    Bool(bool),
+    String(String),
+    Array(Vec<GgufValue>),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+}
+
+impl GgufValue {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            GgufValue::U8(v) => Some(*v as i64),
+            GgufValue::I8(v) => Some(*v as i64),
+            GgufValue::U16(v) => Some(*v as i64),
+            GgufValue::I16(v) => Some(*v as i64),
+            GgufValue::U32(v) => Some(*v as i64),
+            GgufValue::I32(v) => Some(*v as i64),
+            GgufValue::U64(v) => i64::try_from(*v).ok(),
+            GgufValue::I64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            GgufValue::F32(v) => Some(*v as f64),
+            GgufValue::F64(v) => Some(*v),
+            other => other.as_i64().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            GgufValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GgufValue::String(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[GgufValue]> {
+        match self {
+            GgufValue::Array(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+/// Storage type of a tensor, only the ones we know how to load are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GgmlType {
+    F32,
+    F16,
+    BF16,
+    Q4_0,
+    Q8_0,
+    /// K-quants group 256 values in super-blocks, `Q4_K` and `Q6_K` in llama.cpp.
+    Q4K,
+    Q6K,
+    Other(u32),
+}
+
+impl GgmlType {
+    fn from_u32(value: u32) -> GgmlType {
+        match value {
+            0 => GgmlType::F32,
+            1 => GgmlType::F16,
+            2 => GgmlType::Q4_0,
+            8 => GgmlType::Q8_0,
+            12 => GgmlType::Q4K,
+            14 => GgmlType::Q6K,
+            30 => GgmlType::BF16,
+            other => GgmlType::Other(other),
+        }
+    }
+
+    /// Number of elements stored in one block and the size of that block in bytes.
+    pub fn block_layout(&self) -> Result<(usize, usize)> {
+        match self {
+            GgmlType::F32 => Ok((1, 4)),
+            GgmlType::F16 | GgmlType::BF16 => Ok((1, 2)),
+            GgmlType::Q4_0 => Ok((32, 18)),
+            GgmlType::Q8_0 => Ok((32, 34)),
+            GgmlType::Q4K => Ok((256, 144)),
+            GgmlType::Q6K => Ok((256, 210)),
+            GgmlType::Other(t) => Err(Error::GgufUnsupported(format!("ggml tensor type {}", t))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GgufTensorInfo {
+    pub name: String,
+    /// Dimensions in GGUF order, the first one is the contiguous one.
+    pub dims: Vec<u64>,
+    pub ggml_type: GgmlType,
+    pub offset: u64,
+}
+
+impl GgufTensorInfo {
+    /// Row-major shape as used by MLX.
+    pub fn shape(&self) -> Vec<i32> {
+        self.dims.iter().rev().map(|d| *d as i32).collect()
+    }
+
+    pub fn row_size(&self) -> usize {
+        self.dims.first().copied().unwrap_or(1) as usize
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.dims.iter().skip(1).product::<u64>() as usize
+    }
+
+    pub fn byte_size(&self) -> Result<usize> {
+        let (block_size, block_bytes) = self.ggml_type.block_layout()?;
+        if !self.row_size().is_multiple_of(block_size) {
+            return Err(Error::GgufReadError(format!(
+                "tensor '{}' row of {} elements is not a multiple of the block size {}",
+                self.name,
+                self.row_size(),
+                block_size
+            )));
+        }
+        Ok(self.num_rows() * self.row_size() / block_size * block_bytes)
+    }
+}
+
+#[derive(Debug)]
+pub struct GgufHeader {
+    pub metadata: HashMap<String, GgufValue>,
+    pub tensors: Vec<GgufTensorInfo>,
+    /// Offset of the tensor data section from the start of the file.
+    pub data_offset: usize,
+}
+
+impl GgufHeader {
+    pub fn get(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.get(key)
+    }
+
+    pub fn tensor(&self, name: &str) -> Option<&GgufTensorInfo> {
+        self.tensors.iter().find(|t| t.name == name)
+    }
+
+    /// Bytes of a tensor inside the mapped file.
+    pub fn tensor_data<'a>(&self, buffer: &'a [u8], info: &GgufTensorInfo) -> Result<&'a [u8]> {
+        let byte_size = info.byte_size()?;
+        // The offset comes from the file, a crafted one must not wrap around.
+        let (start, end) = usize::try_from(info.offset)
+            .ok()
+            .and_then(|offset| self.data_offset.checked_add(offset))
+            .and_then(|start| Some((start, start.checked_add(byte_size)?)))
+            .ok_or_else(|| {
+                Error::GgufReadError(format!("offset of tensor {} overflows", info.name))
+            })?;
+        if end > buffer.len() {
+            return Err(Error::SafetensorsOutOfBounds {
+                tensor: info.name.clone(),
+                start,
+                end,
+                file_size: buffer.len(),
+            });
+        }
+        Ok(&buffer[start..end])
+    }
+}
+
+struct Cursor<'a> {
+    buffer: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.buffer.len())
+            .ok_or_else(|| {
+                Error::GgufReadError(format!("unexpected end of file at {}", self.pos))
+            })?;
+        let bytes = &self.buffer[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_u64()?;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.buffer.len() - self.pos)
+            .ok_or_else(|| Error::GgufReadError(format!("invalid length {} at {}", len, self.pos)))
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let len = self.read_len()?;
+        Ok(String::from_utf8_lossy(self.read_bytes(len)?).to_string())
+    }
+
+    fn read_value(&mut self, value_type: u32) -> Result<GgufValue> {
+        let value = match value_type {
+            0 => GgufValue::U8(self.read_array::<1>()?[0]),
+            1 => GgufValue::I8(self.read_array::<1>()?[0] as i8),
+            2 => GgufValue::U16(u16::from_le_bytes(self.read_array()?)),
+            3 => GgufValue::I16(i16::from_le_bytes(self.read_array()?)),
+            4 => GgufValue::U32(self.read_u32()?),
+            5 => GgufValue::I32(i32::from_le_bytes(self.read_array()?)),
+            6 => GgufValue::F32(f32::from_le_bytes(self.read_array()?)),
+            7 => GgufValue::Bool(self.read_array::<1>()?[0] != 0),
+            8 => GgufValue::String(self.read_string()?),
+            9 => {
+                let item_type = self.read_u32()?;
+                let len = self.read_len()?;
+                let items = (0..len)
+                    .map(|_| self.read_value(item_type))
+                    .collect::<Result<Vec<_>>>()?;
+                GgufValue::Array(items)
+            }
+            10 => GgufValue::U64(self.read_u64()?),
+            11 => GgufValue::I64(i64::from_le_bytes(self.read_array()?)),
+            12 => GgufValue::F64(f64::from_le_bytes(self.read_array()?)),
+            other => {
+                return Err(Error::GgufReadError(format!(
+                    "unknown metadata value type {}",
+                    other
+                )));
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Parse the GGUF header: metadata key/values and tensor infos.
+pub fn read_gguf_header(buffer: &[u8]) -> Result<GgufHeader> {
+    let mut cursor = Cursor { buffer, pos: 0 };
+
+    if cursor.read_bytes(4)? != GGUF_MAGIC {
+        return Err(Error::GgufReadError("invalid magic".to_string()));
+    }
+    let version = cursor.read_u32()?;
+    if version < 2 {
+        return Err(Error::GgufUnsupported(format!("GGUF version {}", version)));
+    }
+
+    let tensor_count = cursor.read_u64()?;
+    let metadata_count = cursor.read_u64()?;
+
+    let mut metadata = HashMap::new();
+    for _ in 0..metadata_count {
+        let key = cursor.read_string()?;
+        let value_type = cursor.read_u32()?;
+        let value = cursor.read_value(value_type)?;
+        metadata.insert(key, value);
+    }
+
+    let mut tensors = Vec::new();
+    for _ in 0..tensor_count {
+        let name = cursor.read_string()?;
+        let n_dims = cursor.read_u32()?;
+        let dims = (0..n_dims)
+            .map(|_| cursor.read_u64())
+            .collect::<Result<Vec<_>>>()?;
+        let ggml_type = GgmlType::from_u32(cursor.read_u32()?);
+        let offset = cursor.read_u64()?;
+        tensors.push(GgufTensorInfo {
+            name,
+            dims,
+            ggml_type,
+            offset,
+        });
+    }
+
+    let alignment = metadata
+        .get("general.alignment")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as u64)
+        .filter(|v| *v > 0)
+        .unwrap_or(GGUF_DEFAULT_ALIGNMENT);
+    let data_offset = (cursor.pos as u64).div_ceil(alignment) * alignment;
+
+    Ok(GgufHeader {
+        metadata,
+        tensors,
+        data_offset: data_offset as usize,
+    })
+}
+
+pub fn open_gguf(path: &str) -> Result<(Mmap, GgufHeader)> {
+    let file = File::open(path).map_err(|_| Error::FileOpenError(path.to_owned()))?;
+    let mmap = unsafe { MmapOptions::new().map(&file)? };
+    let header = read_gguf_header(&mmap)?;
+    Ok((mmap, header))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Minimal GGUF writer used to build fixtures in tests.
+    #[derive(Default)]
+    pub(crate) struct GgufBuilder {
+        metadata: Vec<u8>,
+        metadata_count: u64,
+        tensors: Vec<(String, Vec<u64>, u32, Vec<u8>)>,
+    }
+
+    fn write_string(buffer: &mut Vec<u8>, value: &str) {
+        buffer.extend((value.len() as u64).to_le_bytes());
+        buffer.extend(value.as_bytes());
+    }
+
+    impl GgufBuilder {
+        pub(crate) fn u32(mut self, key: &str, value: u32) -> Self {
+            write_string(&mut self.metadata, key);
+            self.metadata.extend(4u32.to_le_bytes());
+            self.metadata.extend(value.to_le_bytes());
+            self.metadata_count += 1;
+            self
+        }
+
+        pub(crate) fn f32(mut self, key: &str, value: f32) -> Self {
+            write_string(&mut self.metadata, key);
+            self.metadata.extend(6u32.to_le_bytes());
+            self.metadata.extend(value.to_le_bytes());
+            self.metadata_count += 1;
+            self
+        }
+
+        pub(crate) fn bool(mut self, key: &str, value: bool) -> Self {
+            write_string(&mut self.metadata, key);
+            self.metadata.extend(7u32.to_le_bytes());
+            self.metadata.push(value as u8);
+            self.metadata_count += 1;
+            self
+        }
+
+        pub(crate) fn string(mut self, key: &str, value: &str) -> Self {
+            write_string(&mut self.metadata, key);
+            self.metadata.extend(8u32.to_le_bytes());
+            write_string(&mut self.metadata, value);
+            self.metadata_count += 1;
+            self
+        }
+
+        pub(crate) fn strings(mut self, key: &str, values: &[&str]) -> Self {
+            write_string(&mut self.metadata, key);
+            self.metadata.extend(9u32.to_le_bytes());
+            self.metadata.extend(8u32.to_le_bytes());
+            self.metadata.extend((values.len() as u64).to_le_bytes());
+            for value in values {
+                write_string(&mut self.metadata, value);
+            }
+            self.metadata_count += 1;
+            self
+        }
+
+        pub(crate) fn i32s(mut self, key: &str, values: &[i32]) -> Self {
+            write_string(&mut self.metadata, key);
+            self.metadata.extend(9u32.to_le_bytes());
+            self.metadata.extend(5u32.to_le_bytes());
+            self.metadata.extend((values.len() as u64).to_le_bytes());
+            for value in values {
+                self.metadata.extend(value.to_le_bytes());
+            }
+            self.metadata_count += 1;
+            self
+        }
+
+        pub(crate) fn tensor(
+            mut self,
+            name: &str,
+            dims: &[u64],
+            ggml_type: u32,
+            data: Vec<u8>,
+        ) -> Self {
+            self.tensors
+                .push((name.to_string(), dims.to_vec(), ggml_type, data));
+            self
+        }
+
+        pub(crate) fn build(self) -> Vec<u8> {
+            let mut buffer = GGUF_MAGIC.to_vec();
+            buffer.extend(3u32.to_le_bytes());
+            buffer.extend((self.tensors.len() as u64).to_le_bytes());
+            buffer.extend(self.metadata_count.to_le_bytes());
+            buffer.extend(self.metadata);
+
+            let mut offset = 0u64;
+            for (name, dims, ggml_type, data) in &self.tensors {
+                write_string(&mut buffer, name);
+                buffer.extend((dims.len() as u32).to_le_bytes());
+                for dim in dims {
+                    buffer.extend(dim.to_le_bytes());
+                }
+                buffer.extend(ggml_type.to_le_bytes());
+                buffer.extend(offset.to_le_bytes());
+                offset +=
+                    (data.len() as u64).div_ceil(GGUF_DEFAULT_ALIGNMENT) * GGUF_DEFAULT_ALIGNMENT;
+            }
+
+            for (_, _, _, data) in self.tensors {
+                buffer.resize(buffer.len().div_ceil(32) * 32, 0);
+                buffer.extend(data);
+            }
+            buffer
+        }
+    }
+
+    #[test]
+    fn test_read_gguf_header() {
+        let data: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let buffer = GgufBuilder::default()
+            .string("general.architecture", "llama")
+            .u32("llama.block_count", 2)
+            .strings("tokenizer.ggml.tokens", &["a", "b"])
+            .tensor("output_norm.weight", &[4], 0, data.clone())
+            .tensor("token_embd.weight", &[2, 2], 0, data.clone())
+            .build();
+
+        let header = read_gguf_header(&buffer).unwrap();
+        assert_eq!(
+            header.get("general.architecture").and_then(|v| v.as_str()),
+            Some("llama")
+        );
+        assert_eq!(
+            header.get("llama.block_count").and_then(|v| v.as_i64()),
+            Some(2)
+        );
+        assert_eq!(
+            header
+                .get("tokenizer.ggml.tokens")
+                .and_then(|v| v.as_array())
+                .map(|v| v.len()),
+            Some(2)
+        );
+
+        let embd = header.tensor("token_embd.weight").unwrap();
+        assert_eq!(embd.shape(), vec![2, 2]);
+        assert_eq!(embd.ggml_type, GgmlType::F32);
+        assert_eq!(header.tensor_data(&buffer, embd).unwrap(), data.as_slice());
+    }
+
+    #[test]
+    fn test_tensor_data_rejects_offset_outside_file() {
+        let buffer = GgufBuilder::default()
+            .tensor("output_norm.weight", &[4], 0, vec![0; 16])
+            .build();
+        let mut header = read_gguf_header(&buffer).unwrap();
+
+        header.tensors[0].offset = 16;
+        assert!(matches!(
+            header.tensor_data(&buffer, &header.tensors[0]),
+            Err(Error::SafetensorsOutOfBounds { .. })
+        ));
+
+        header.tensors[0].offset = u64::MAX;
+        assert!(matches!(
+            header.tensor_data(&buffer, &header.tensors[0]),
+            Err(Error::GgufReadError(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_gguf_header_rejects_truncated_file() {
+        let buffer = GgufBuilder::default()
+            .strings("tokenizer.ggml.tokens", &["a", "b"])
+            .build();
+        assert!(matches!(
+            read_gguf_header(&buffer[..buffer.len() - 1]),
+            Err(Error::GgufReadError(_))
+        ));
+        assert!(matches!(
+            read_gguf_header(b"GGML"),
+            Err(Error::GgufReadError(_))
+        ));
+    }
+}
@@ -0,0 +1,502 @@
+use crate::error::{Error, Result};
+use crate::model::gguf::reader::{GgmlType, GgufHeader, GgufTensorInfo, open_gguf};
+use crate::model::weight::{Metadata, Tensor, Weight};
+use crate::token::token_stream_manager::PromptStreamCallback;
+use half::f16;
+use mlx_rs::ops::{dequantize, quantize};
+use mlx_rs::{Array, Dtype};
+use sn_core::server::payload::backend::run_model_response::RunModelResponseSSE;
+use sn_core::types::stream_data::StreamData;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::Arc;
+use tracing::debug;
+
+/// GGUF blocks always hold 32 values, the repacked arrays keep the same grouping.
+pub static GGUF_GROUP_SIZE: i32 = 32;
+
+/// Map a GGUF tensor name onto the names expected by `set_weight`.
+pub fn weight_name(gguf_name: &str) -> Option<String> {
+    let (base, suffix) = gguf_name.rsplit_once('.')?;
+    let mapped = match base {
+        "token_embd" => "embed_tokens".to_string(),
+        "output_norm" => "norm".to_string(),
+        "output" => "lm_head".to_string(),
+        _ => {
+            let rest = base.strip_prefix("blk.")?;
+            let (idx, layer_name) = rest.split_once('.')?;
+            let sub_name = match layer_name {
+                "attn_norm" => "input_layernorm",
+                "ffn_norm" => "post_attention_layernorm",
+                "attn_q" => "self_attn.q_proj",
+                "attn_k" => "self_attn.k_proj",
+                "attn_v" => "self_attn.v_proj",
+                "attn_output" => "self_attn.o_proj",
+                "attn_q_norm" => "self_attn.q_norm",
+                "attn_k_norm" => "self_attn.k_norm",
+                "ffn_gate" => "mlp.gate_proj",
+                "ffn_up" => "mlp.up_proj",
+                "ffn_down" => "mlp.down_proj",
+                "ffn_gate_inp" => "mlp.gate",
+                "ffn_gate_exps" => "mlp.switch_mlp.gate_proj",
+                "ffn_up_exps" => "mlp.switch_mlp.up_proj",
+                "ffn_down_exps" => "mlp.switch_mlp.down_proj",
+                _ => return None,
+            };
+            format!("layers.{}.{}", idx, sub_name)
+        }
+    };
+    Some(format!("{}.{}", mapped, suffix))
+}
+
+/// Bits of the MLX quantization used for the model, `None` when every tensor is stored
+/// as floats. 4 bits wins over 8 when a file mixes both, llama.cpp Q4_0 files often
+/// keep the output or the embeddings in Q6_K.
+pub fn quantization_bits(header: &GgufHeader) -> Result<Option<i32>> {
+    let mut bits = None;
+    for info in &header.tensors {
+        match info.ggml_type {
+            GgmlType::Q4_0 | GgmlType::Q4K => bits = Some(4),
+            GgmlType::Q8_0 | GgmlType::Q6K => bits = bits.or(Some(8)),
+            GgmlType::F32 | GgmlType::F16 | GgmlType::BF16 => {}
+            GgmlType::Other(t) => {
+                return Err(Error::GgufUnsupported(format!(
+                    "ggml tensor type {} for '{}', only F32, F16, BF16, Q4_0, Q8_0, Q4_K and Q6_K can be loaded",
+                    t, info.name
+                )));
+            }
+        }
+    }
+    Ok(bits)
+}
+
+/// llama.cpp stores the Q/K projections of llama models with the rows of each head
+/// interleaved for its rope kernel, put them back in the transformers order.
+pub fn unpermute_rows(data: &[u8], num_rows: usize, num_heads: usize) -> Vec<u8> {
+    let row_bytes = data.len() / num_rows;
+    let head_dim = num_rows / num_heads;
+    let mut out = vec![0u8; data.len()];
+    for h in 0..num_heads {
+        for i in 0..head_dim / 2 {
+            for j in 0..2 {
+                let src = h * head_dim + 2 * i + j;
+                let dst = h * head_dim + j * (head_dim / 2) + i;
+                out[dst * row_bytes..(dst + 1) * row_bytes]
+                    .copy_from_slice(&data[src * row_bytes..(src + 1) * row_bytes]);
+            }
+        }
+    }
+    out
+}
+
+/// MLX affine quantized data: packed `uint32` values, scales and biases per group.
+pub struct RepackedBlocks {
+    pub weight: Vec<u32>,
+    pub scales: Vec<f16>,
+    pub biases: Vec<f16>,
+}
+
+/// Repack Q4_0 blocks (`d: f16`, 16 bytes of nibbles, `w = d * (q - 8)`) into 4 bits
+/// MLX groups of 32 with `scale = d` and `bias = -8 * d`.
+pub fn repack_q4_0(data: &[u8]) -> RepackedBlocks {
+    let num_blocks = data.len() / 18;
+    let mut weight = Vec::with_capacity(num_blocks * 4);
+    let mut scales = Vec::with_capacity(num_blocks);
+    let mut biases = Vec::with_capacity(num_blocks);
+
+    for block in data.chunks_exact(18) {
+        let d = f16::from_le_bytes([block[0], block[1]]);
+        let qs = &block[2..];
+        // Q4_0 stores values j and j + 16 in the same byte, MLX packs them in order.
+        let value = |j: usize| -> u32 {
+            if j < 16 {
+                (qs[j] & 0x0F) as u32
+            } else {
+                (qs[j - 16] >> 4) as u32
+            }
+        };
+        for word in 0..4 {
+            weight.push((0..8).fold(0u32, |acc, k| acc | (value(word * 8 + k) << (4 * k))));
+        }
+        scales.push(d);
+        biases.push(f16::from_f32(-8.0 * d.to_f32()));
+    }
+
+    RepackedBlocks {
+        weight,
+        scales,
+        biases,
+    }
+}
+
+/// Repack Q8_0 blocks (`d: f16`, 32 signed bytes, `w = d * q`) into 8 bits MLX groups
+/// of 32 with `scale = d` and `bias = -128 * d`.
+pub fn repack_q8_0(data: &[u8]) -> RepackedBlocks {
+    let num_blocks = data.len() / 34;
+    let mut weight = Vec::with_capacity(num_blocks * 8);
+    let mut scales = Vec::with_capacity(num_blocks);
+    let mut biases = Vec::with_capacity(num_blocks);
+
+    for block in data.chunks_exact(34) {
+        let d = f16::from_le_bytes([block[0], block[1]]);
+        for bytes in block[2..].chunks_exact(4) {
+            weight.push(bytes.iter().enumerate().fold(0u32, |acc, (k, q)| {
+                acc | (((*q as i8 as i32 + 128) as u32) << (8 * k))
+            }));
+        }
+        scales.push(d);
+        biases.push(f16::from_f32(-128.0 * d.to_f32()));
+    }
+
+    RepackedBlocks {
+        weight,
+        scales,
+        biases,
+    }
+}
+
+/// Scale and min of the sub-block `j` of a Q4_K super-block, packed on 6 bits.
+fn q4_k_scale_min(j: usize, scales: &[u8]) -> (u8, u8) {
+    if j < 4 {
+        (scales[j] & 63, scales[j + 4] & 63)
+    } else {
+        (
+            (scales[j + 4] & 0x0F) | ((scales[j - 4] >> 6) << 4),
+            (scales[j + 4] >> 4) | ((scales[j] >> 6) << 4),
+        )
+    }
+}
+
+/// Dequantize Q4_K super-blocks (`d: f16`, `dmin: f16`, 12 bytes of scales and mins, 128
+/// bytes of nibbles) holding 8 sub-blocks of 32 values, `w = d * sc * q - dmin * m`.
+pub fn dequantize_q4_k(data: &[u8]) -> Vec<f32> {
+    let mut values = Vec::with_capacity(data.len() / 144 * 256);
+    for block in data.chunks_exact(144) {
+        let d = f16::from_le_bytes([block[0], block[1]]).to_f32();
+        let dmin = f16::from_le_bytes([block[2], block[3]]).to_f32();
+        let scales = &block[4..16];
+        // Each 32 bytes of nibbles hold two sub-blocks, the low ones first.
+        for (chunk, qs) in block[16..].chunks_exact(32).enumerate() {
+            for (j, shift) in [(2 * chunk, 0), (2 * chunk + 1, 4)] {
+                let (sc, m) = q4_k_scale_min(j, scales);
+                let (scale, min) = (d * sc as f32, dmin * m as f32);
+                values.extend(
+                    qs.iter()
+                        .map(|q| scale * ((q >> shift) & 0x0F) as f32 - min),
+                );
+            }
+        }
+    }
+    values
+}
+
+/// Dequantize Q6_K super-blocks (128 bytes of low nibbles, 64 bytes of high bits, 16
+/// signed scales, `d: f16`) holding 16 sub-blocks of 16 values, `w = d * sc * (q - 32)`.
+pub fn dequantize_q6_k(data: &[u8]) -> Vec<f32> {
+    let mut values = Vec::with_capacity(data.len() / 210 * 256);
+    for block in data.chunks_exact(210) {
+        let d = f16::from_le_bytes([block[208], block[209]]).to_f32();
+        // Two halves of 128 values, each with 64 bytes of `ql`, 32 of `qh` and 8 scales.
+        for half in 0..2 {
+            let ql = &block[64 * half..64 * (half + 1)];
+            let qh = &block[128 + 32 * half..128 + 32 * (half + 1)];
+            let scales = &block[192 + 8 * half..192 + 8 * (half + 1)];
+            let mut out = [0f32; 128];
+            for l in 0..32 {
+                let q = [
+                    (ql[l] & 0x0F) | ((qh[l] & 3) << 4),
+                    (ql[l + 32] & 0x0F) | (((qh[l] >> 2) & 3) << 4),
+                    (ql[l] >> 4) | (((qh[l] >> 4) & 3) << 4),
+                    (ql[l + 32] >> 4) | (((qh[l] >> 6) & 3) << 4),
+                ];
+                for (k, q) in q.into_iter().enumerate() {
+                    let scale = scales[l / 16 + 2 * k] as i8 as f32;
+                    out[l + 32 * k] = d * scale * (q as i32 - 32) as f32;
+                }
+            }
+            values.extend(out);
+        }
+    }
+    values
+}
+
+fn to_tensor(data: Array) -> Tensor {
+    Tensor {
+        size: data.nbytes() as u64,
+        dtype: data.dtype(),
+        shape: data.shape().to_vec(),
+        data: Arc::new(data),
+    }
+}
+
+fn insert_quantized(
+    tensors: &mut HashMap<String, Tensor>,
+    name: &str,
+    (weight, scales, biases): (Array, Array, Array),
+) {
+    let prefix = name.strip_suffix(".weight").unwrap_or(name);
+    tensors.insert(format!("{}.weight", prefix), to_tensor(weight));
+    tensors.insert(format!("{}.scales", prefix), to_tensor(scales));
+    tensors.insert(format!("{}.biases", prefix), to_tensor(biases));
+}
+
+fn read_tensor(
+    header: &GgufHeader,
+    buffer: &[u8],
+    info: &GgufTensorInfo,
+    target_bits: Option<i32>,
+    tensors: &mut HashMap<String, Tensor>,
+    name: &str,
+) -> Result<()> {
+    let mut data = header.tensor_data(buffer, info)?.to_vec();
+
+    if header.architecture()? == "llama" {
+        let num_heads = match name.rsplit_once(".self_attn.") {
+            Some((_, "q_proj.weight" | "q_proj.bias")) => Some(header.num_attention_heads()?),
+            Some((_, "k_proj.weight" | "k_proj.bias")) => Some(header.num_key_value_heads()?),
+            _ => None,
+        };
+        if let Some(num_heads) = num_heads {
+            let num_rows = if info.dims.len() == 1 {
+                info.row_size()
+            } else {
+                info.num_rows()
+            };
+            data = unpermute_rows(&data, num_rows, num_heads as usize);
+        }
+    }
+
+    let shape = info.shape();
+    // Only matrices are quantized, norms and biases stay as floats.
+    let target_bits = target_bits.filter(|_| shape.len() >= 2);
+
+    let (repacked, bits) = match info.ggml_type {
+        GgmlType::Q4_0 => (repack_q4_0(&data), 4),
+        GgmlType::Q8_0 => (repack_q8_0(&data), 8),
+        other_type => {
+            // K-quants use per sub-block scales MLX can not represent, they go through
+            // floats like the unquantized tensors.
+            let array = match other_type {
+                GgmlType::Q4K => Array::from_slice(&dequantize_q4_k(&data), &shape),
+                GgmlType::Q6K => Array::from_slice(&dequantize_q6_k(&data), &shape),
+                float_type => {
+                    let dtype = match float_type {
+                        GgmlType::F32 => Dtype::Float32,
+                        GgmlType::BF16 => Dtype::Bfloat16,
+                        _ => Dtype::Float16,
+                    };
+                    unsafe { Array::from_raw_data(data.as_ptr() as *const c_void, &shape, dtype) }
+                }
+            }
+            .as_dtype(Dtype::Float16)?;
+            match target_bits {
+                Some(bits) => {
+                    insert_quantized(tensors, name, quantize(&array, GGUF_GROUP_SIZE, bits)?)
+                }
+                None => {
+                    tensors.insert(name.to_string(), to_tensor(array));
+                }
+            }
+            return Ok(());
+        }
+    };
+
+    let mut packed_shape = shape.clone();
+    let mut groups_shape = shape;
+    if let (Some(packed), Some(groups)) = (packed_shape.last_mut(), groups_shape.last_mut()) {
+        *packed = *packed * bits / 32;
+        *groups /= GGUF_GROUP_SIZE;
+    }
+    let quantized = (
+        Array::from_slice(&repacked.weight, &packed_shape),
+        Array::from_slice(&repacked.scales, &groups_shape),
+        Array::from_slice(&repacked.biases, &groups_shape),
+    );
+
+    match target_bits {
+        Some(target) if target == bits => insert_quantized(tensors, name, quantized),
+        _ => {
+            let (weight, scales, biases) = quantized;
+            let array = dequantize(&weight, &scales, &biases, GGUF_GROUP_SIZE, bits)?;
+            match target_bits {
+                Some(target) => {
+                    insert_quantized(tensors, name, quantize(&array, GGUF_GROUP_SIZE, target)?)
+                }
+                None => {
+                    tensors.insert(name.to_string(), to_tensor(array));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Load every tensor of a GGUF file under the names used by the safetensors checkpoints.
+/// Q4_0/Q8_0 blocks are repacked into MLX quantized arrays, tensors stored in another
+/// format, K-quants included, are converted so the whole model shares the same
+/// quantization.
+pub fn load_gguf_weights(path: &str, callback: Option<PromptStreamCallback>) -> Result<Weight> {
+    let (mmap, header) = open_gguf(path)?;
+    let target_bits = quantization_bits(&header)?;
+
+    let mut tensors = HashMap::new();
+    for (idx, info) in header.tensors.iter().enumerate() {
+        let Some(name) = weight_name(&info.name) else {
+            debug!("Skipping GGUF tensor '{}'", info.name);
+            continue;
+        };
+
+        if let Some(cb) = callback.clone() {
+            let _ = cb.send(StreamData::for_run_model_sse_response(
+                RunModelResponseSSE {
+                    load_type: "loading_tensor".to_string(),
+                    tensor_name: name.clone(),
+                    tensor_index: idx + 1,
+                    total_tensors: header.tensors.len(),
+                },
+            ));
+        }
+
+        debug!(
+            "Loading GGUF tensor '{}' as '{}': type={:?} shape={:?}",
+            info.name,
+            name,
+            info.ggml_type,
+            info.shape()
+        );
+        read_tensor(&header, &mmap, info, target_bits, &mut tensors, &name)?;
+    }
+
+    if tensors.is_empty() {
+        return Err(Error::NoTensorInModelFile);
+    }
+
+    Ok(Weight {
+        metadata: Metadata {
+            format: Some("gguf".to_string()),
+        },
+        tensors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weight_name() {
+        assert_eq!(
+            weight_name("token_embd.weight").as_deref(),
+            Some("embed_tokens.weight")
+        );
+        assert_eq!(
+            weight_name("blk.3.attn_q.bias").as_deref(),
+            Some("layers.3.self_attn.q_proj.bias")
+        );
+        assert_eq!(
+            weight_name("blk.0.ffn_down_exps.weight").as_deref(),
+            Some("layers.0.mlp.switch_mlp.down_proj.weight")
+        );
+        // Read into the rope scaling of the config rather than loaded as a weight.
+        assert_eq!(weight_name("rope_freqs.weight"), None);
+    }
+
+    #[test]
+    fn test_unpermute_rows() {
+        // One head of 4 rows, llama.cpp order is [0, 2, 1, 3].
+        let data = [0u8, 0, 2, 2, 1, 1, 3, 3];
+        assert_eq!(unpermute_rows(&data, 4, 1), vec![0, 0, 1, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn test_repack_q4_0() {
+        let mut block = f16::from_f32(0.5).to_le_bytes().to_vec();
+        // Values 0..16 in the low nibbles and 15..=0 in the high ones.
+        block.extend((0..16u8).map(|j| j | ((15 - j) << 4)));
+
+        let repacked = repack_q4_0(&block);
+        assert_eq!(repacked.weight.len(), 4);
+        assert_eq!(repacked.weight[0], 0x7654_3210);
+        assert_eq!(repacked.weight[1], 0xFEDC_BA98);
+        assert_eq!(repacked.weight[2], 0x89AB_CDEF);
+        assert_eq!(repacked.weight[3], 0x0123_4567);
+        assert_eq!(repacked.scales, vec![f16::from_f32(0.5)]);
+        assert_eq!(repacked.biases, vec![f16::from_f32(-4.0)]);
+    }
+
+    #[test]
+    fn test_dequantize_q4_k() {
+        let mut block = f16::from_f32(1.0).to_le_bytes().to_vec();
+        block.extend(f16::from_f32(0.5).to_le_bytes());
+        // Sub-block 0 has scale 2 and min 1, sub-block 1 scale 3 and min 0.
+        let mut scales = [0u8; 12];
+        scales[0] = 2;
+        scales[1] = 3;
+        scales[4] = 1;
+        block.extend(scales);
+        let mut qs = [0u8; 128];
+        qs[0] = 0x53;
+        block.extend(qs);
+
+        let values = dequantize_q4_k(&block);
+        assert_eq!(values.len(), 256);
+        assert_eq!(values[0], 2.0 * 3.0 - 0.5);
+        assert_eq!(values[1], -0.5);
+        assert_eq!(values[32], 3.0 * 5.0);
+        assert_eq!(values[64], 0.0);
+    }
+
+    #[test]
+    fn test_dequantize_q6_k() {
+        let mut block = vec![0u8; 210];
+        block[0] = 0x21;
+        block[128] = 0b0000_0001;
+        block[192] = 2;
+        block[192 + 1..192 + 16].fill(1);
+        block[208..].copy_from_slice(&f16::from_f32(0.5).to_le_bytes());
+
+        let values = dequantize_q6_k(&block);
+        assert_eq!(values.len(), 256);
+        // q = 1 | 1 << 4 = 17 with the scale of sub-block 0.
+        assert_eq!(values[0], 0.5 * 2.0 * (17.0 - 32.0));
+        assert_eq!(values[1], 0.5 * 2.0 * -32.0);
+        // High nibble of the same byte, sub-block 4.
+        assert_eq!(values[64], 0.5 * (2.0 - 32.0));
+        assert_eq!(values[128], 0.5 * -32.0);
+    }
+
+    #[test]
+    fn test_quantization_bits_with_k_quants() {
+        use crate::model::gguf::reader::read_gguf_header;
+        use crate::model::gguf::reader::tests::GgufBuilder;
+
+        let header = |types: &[(&str, u32, usize)]| {
+            let builder = types
+                .iter()
+                .fold(GgufBuilder::default(), |builder, (name, t, bytes)| {
+                    builder.tensor(name, &[256], *t, vec![0; *bytes])
+                });
+            read_gguf_header(&builder.build()).unwrap()
+        };
+        let q4_0 = header(&[("blk.0.attn_q.weight", 2, 144), ("output.weight", 14, 210)]);
+        assert_eq!(quantization_bits(&q4_0).unwrap(), Some(4));
+        let q6_k = header(&[("output.weight", 14, 210)]);
+        assert_eq!(quantization_bits(&q6_k).unwrap(), Some(8));
+
+        let q5_k = header(&[("output.weight", 13, 176)]);
+        let error = quantization_bits(&q5_k).unwrap_err().to_string();
+        assert!(error.contains("Q4_K and Q6_K"), "{}", error);
+    }
+
+    #[test]
+    fn test_repack_q8_0() {
+        let mut block = f16::from_f32(0.25).to_le_bytes().to_vec();
+        block.extend((0..32i8).map(|j| (j - 16) as u8));
+
+        let repacked = repack_q8_0(&block);
+        assert_eq!(repacked.weight.len(), 8);
+        // -16 + 128 = 112 (0x70) for the first value.
+        assert_eq!(repacked.weight[0], 0x7372_7170);
+        assert_eq!(repacked.biases, vec![f16::from_f32(-32.0)]);
+    }
+}
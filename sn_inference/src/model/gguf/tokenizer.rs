@@ -0,0 +1,164 @@
+use crate::error::{Error, Result};
+use crate::model::gguf::reader::GgufHeader;
+use serde_json::{Map, Value, json};
+
+/// Token types stored in `tokenizer.ggml.token_type`.
+static TOKEN_TYPE_CONTROL: i64 = 3;
+static TOKEN_TYPE_USER_DEFINED: i64 = 4;
+
+static PRE_TOKENIZER_LLAMA3: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+";
+static PRE_TOKENIZER_QWEN2: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+";
+
+fn byte_level(use_regex: bool) -> Value {
+    json!({
+        "type": "ByteLevel",
+        "add_prefix_space": false,
+        "trim_offsets": true,
+        "use_regex": use_regex,
+    })
+}
+
+fn pre_tokenizer(pre: Option<&str>) -> Value {
+    let pattern = match pre {
+        Some("llama-bpe" | "llama3" | "smaug-bpe") => PRE_TOKENIZER_LLAMA3,
+        Some("qwen2") => PRE_TOKENIZER_QWEN2,
+        _ => return byte_level(true),
+    };
+    json!({
+        "type": "Sequence",
+        "pretokenizers": [
+            {
+                "type": "Split",
+                "pattern": { "Regex": pattern },
+                "behavior": "Isolated",
+                "invert": false,
+            },
+            byte_level(false),
+        ],
+    })
+}
+
+/// Build a `tokenizer.json` from the GGUF vocabulary. Only the byte-level BPE
+/// tokenizers (`tokenizer.ggml.model = gpt2`) used by Llama 3 and Qwen are supported.
+pub fn tokenizer_json(header: &GgufHeader) -> Result<Value> {
+    let model = header
+        .get("tokenizer.ggml.model")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if model != "gpt2" {
+        return Err(Error::GgufUnsupported(format!(
+            "tokenizer model '{}'",
+            model
+        )));
+    }
+
+    let tokens = header.tokens()?;
+    let token_types: Vec<i64> = header
+        .get("tokenizer.ggml.token_type")
+        .and_then(|v| v.as_array())
+        .map(|types| types.iter().map(|t| t.as_i64().unwrap_or(1)).collect())
+        .unwrap_or_default();
+    let merges: Vec<&str> = header
+        .get("tokenizer.ggml.merges")
+        .and_then(|v| v.as_array())
+        .map(|merges| merges.iter().filter_map(|m| m.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut vocab = Map::new();
+    let mut added_tokens = Vec::new();
+    for (id, token) in tokens.iter().enumerate() {
+        vocab.insert(token.to_string(), json!(id));
+        let token_type = token_types.get(id).copied().unwrap_or(1);
+        if token_type == TOKEN_TYPE_CONTROL || token_type == TOKEN_TYPE_USER_DEFINED {
+            added_tokens.push(json!({
+                "id": id,
+                "content": token,
+                "single_word": false,
+                "lstrip": false,
+                "rstrip": false,
+                "normalized": false,
+                "special": token_type == TOKEN_TYPE_CONTROL,
+            }));
+        }
+    }
+
+    let add_bos = header
+        .get("tokenizer.ggml.add_bos_token")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let post_processor = match header.token_id("bos").filter(|_| add_bos) {
+        Some(bos_id) => {
+            let bos = tokens.get(bos_id as usize).copied().unwrap_or_default();
+            json!({
+                "type": "TemplateProcessing",
+                "single": [
+                    { "SpecialToken": { "id": bos, "type_id": 0 } },
+                    { "Sequence": { "id": "A", "type_id": 0 } },
+                ],
+                "pair": [
+                    { "SpecialToken": { "id": bos, "type_id": 0 } },
+                    { "Sequence": { "id": "A", "type_id": 0 } },
+                    { "SpecialToken": { "id": bos, "type_id": 1 } },
+                    { "Sequence": { "id": "B", "type_id": 1 } },
+                ],
+                "special_tokens": {
+                    bos: { "id": bos, "ids": [bos_id], "tokens": [bos] },
+                },
+            })
+        }
+        None => byte_level(true),
+    };
+
+    let pre = header.get("tokenizer.ggml.pre").and_then(|v| v.as_str());
+    Ok(json!({
+        "version": "1.0",
+        "truncation": null,
+        "padding": null,
+        "added_tokens": added_tokens,
+        "normalizer": null,
+        "pre_tokenizer": pre_tokenizer(pre),
+        "post_processor": post_processor,
+        "decoder": byte_level(true),
+        "model": {
+            "type": "BPE",
+            "dropout": null,
+            "unk_token": null,
+            "continuing_subword_prefix": null,
+            "end_of_word_suffix": null,
+            "fuse_unk": false,
+            "byte_fallback": false,
+            "ignore_merges": matches!(pre, Some("llama-bpe" | "llama3" | "smaug-bpe")),
+            "vocab": vocab,
+            "merges": merges,
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::gguf::reader::read_gguf_header;
+    use crate::model::gguf::reader::tests::GgufBuilder;
+    use std::str::FromStr;
+    use tokenizers::tokenizer::Tokenizer as HugTokenizer;
+
+    #[test]
+    fn test_tokenizer_json_from_gguf() {
+        let buffer = GgufBuilder::default()
+            .string("tokenizer.ggml.model", "gpt2")
+            .string("tokenizer.ggml.pre", "qwen2")
+            .strings("tokenizer.ggml.tokens", &["h", "i", "hi", "Ġ", "<|bos|>"])
+            .i32s("tokenizer.ggml.token_type", &[1, 1, 1, 1, 3])
+            .strings("tokenizer.ggml.merges", &["h i"])
+            .u32("tokenizer.ggml.bos_token_id", 4)
+            .bool("tokenizer.ggml.add_bos_token", true)
+            .build();
+        let header = read_gguf_header(&buffer).unwrap();
+
+        let json = tokenizer_json(&header).unwrap().to_string();
+        let tokenizer = HugTokenizer::from_str(&json).unwrap();
+        let encoding = tokenizer.encode("hi hi", true).unwrap();
+        assert_eq!(encoding.get_ids(), &[4, 2, 3, 2]);
+        assert_eq!(tokenizer.decode(&[2, 3, 2], true).unwrap(), "hi hi");
+    }
+}
@@ -0,0 +1,240 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+use walkdir::WalkDir;
+
+const HF_HUB_CACHE_DEFAULT: &str = ".cache/huggingface/hub";
+const HF_REPO_PREFIX: &str = "models--";
+const HF_REPO_SEPARATOR: &str = "--";
+const HF_DEFAULT_REVISION: &str = "main";
+
+/// A model repository found in a Hugging Face hub cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HfCachedModel {
+    /// `org/name`
+    pub repo_id: String,
+    /// `<cache>/models--org--name`
+    pub path: PathBuf,
+}
+
+/// Location of the hub cache, following the `huggingface_hub` lookup order:
+/// `HF_HUB_CACHE`, then `HF_HOME/hub`, then `~/.cache/huggingface/hub`.
+pub fn hf_hub_cache_path() -> PathBuf {
+    if let Ok(path) = std::env::var("HF_HUB_CACHE") {
+        return PathBuf::from(path);
+    }
+    if let Ok(path) = std::env::var("HF_HOME") {
+        return Path::new(&path).join("hub");
+    }
+    let home = std::env::var("HOME").unwrap_or_default();
+    Path::new(&home).join(HF_HUB_CACHE_DEFAULT)
+}
+
+/// `models--Qwen--Qwen3-1.7B` -> `Qwen/Qwen3-1.7B`
+fn repo_id_from_dir_name(dir_name: &str) -> Option<String> {
+    let name = dir_name.strip_prefix(HF_REPO_PREFIX)?;
+    let (org, repo) = name.split_once(HF_REPO_SEPARATOR)?;
+    Some(format!("{}/{}", org, repo))
+}
+
+/// Alias used when none is given, the repository name without its organisation.
+pub fn default_alias(repo_id: &str) -> String {
+    repo_id.rsplit('/').next().unwrap_or(repo_id).to_string()
+}
+
+pub fn list_cached_models(cache_path: &Path) -> Result<Vec<HfCachedModel>> {
+    let mut models: Vec<HfCachedModel> = fs::read_dir(cache_path)?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let repo_id = repo_id_from_dir_name(&entry.file_name().to_string_lossy())?;
+            Some(HfCachedModel {
+                repo_id,
+                path: entry.path(),
+            })
+        })
+        .collect();
+    models.sort_by(|a, b| a.repo_id.cmp(&b.repo_id));
+    Ok(models)
+}
+
+/// Hub repository ids are case-insensitive, `qwen/qwen3-1.7b` finds `Qwen/Qwen3-1.7B`.
+pub fn find_cached_model(cache_path: &Path, repo_id: &str) -> Result<HfCachedModel> {
+    list_cached_models(cache_path)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|model| model.repo_id.eq_ignore_ascii_case(repo_id))
+        .ok_or_else(|| Error::HfCacheModelNotFound(repo_id.to_string()))
+}
+
+impl HfCachedModel {
+    /// Snapshot directory of `revision`, either a ref (`main`) resolved through
+    /// `refs/<ref>` or a commit hash. Without a revision, `main` is used, falling back
+    /// to the only snapshot when the ref was not recorded.
+    pub fn snapshot_path(&self, revision: Option<&str>) -> Result<PathBuf> {
+        let snapshots = self.path.join("snapshots");
+        let requested = revision.unwrap_or(HF_DEFAULT_REVISION);
+
+        let ref_path = self.path.join("refs").join(requested);
+        let commit = match fs::read_to_string(&ref_path) {
+            Ok(commit) => commit.trim().to_string(),
+            Err(_) => requested.to_string(),
+        };
+        let path = snapshots.join(&commit);
+        if path.is_dir() {
+            return Ok(path);
+        }
+
+        if revision.is_none() {
+            let mut candidates: Vec<PathBuf> = fs::read_dir(&snapshots)
+                .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+                .unwrap_or_default();
+            if candidates.len() == 1 {
+                return Ok(candidates.remove(0));
+            }
+        }
+        Err(Error::HfCacheRevisionNotFound(
+            self.repo_id.clone(),
+            requested.to_string(),
+        ))
+    }
+}
+
+/// Register a cached snapshot as an installed model under `dst_path`. The snapshot
+/// entries are symlinks to `../../blobs/<hash>`, each one is resolved and linked
+/// directly to its blob so no weight is copied and the model keeps working if the
+/// snapshot directory is pruned. Returns the number of files linked.
+pub fn import_snapshot(snapshot_path: &Path, dst_path: &Path) -> Result<usize> {
+    if dst_path.exists() {
+        return Err(Error::ModelAlreadyExists(dst_path.display().to_string()));
+    }
+
+    let mut files: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for entry in WalkDir::new(snapshot_path).into_iter().flatten() {
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(snapshot_path)
+            .map_err(|_| Error::FileOpenError(entry.path().display().to_string()))?
+            .to_path_buf();
+        // A dangling link means the download of this file never completed.
+        let blob = fs::canonicalize(entry.path())
+            .map_err(|_| Error::HfCacheBlobMissing(entry.path().display().to_string()))?;
+        files.push((relative, blob));
+    }
+    if files.is_empty() {
+        return Err(Error::RootModelPathNotFound(
+            snapshot_path.display().to_string(),
+        ));
+    }
+
+    fs::create_dir_all(dst_path)?;
+    for (relative, blob) in &files {
+        let link = dst_path.join(relative);
+        if let Some(parent) = link.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        debug!("Linking {} -> {}", link.display(), blob.display());
+        std::os::unix::fs::symlink(blob, &link)?;
+    }
+
+    // The loader finds the model root through its README, not every repository has one.
+    let has_readme = files.iter().any(|(relative, _)| {
+        relative.components().count() == 1
+            && relative
+                .to_string_lossy()
+                .to_lowercase()
+                .starts_with("readme")
+    });
+    if !has_readme {
+        fs::write(
+            dst_path.join("README.md"),
+            format!("Imported from {}.\n", snapshot_path.display()),
+        )?;
+    }
+
+    info!(
+        "Imported {} file(s) from {} to {}",
+        files.len(),
+        snapshot_path.display(),
+        dst_path.display()
+    );
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cache holding one repo with a `main` ref and its config as a blob, returns
+    /// the snapshot directory.
+    fn cached_repo(hub: &Path) -> PathBuf {
+        let repo = hub.join("models--Qwen--Qwen3-0.6B");
+        let snapshot = repo.join("snapshots").join("abc123");
+        fs::create_dir_all(repo.join("blobs")).unwrap();
+        fs::create_dir_all(repo.join("refs")).unwrap();
+        fs::create_dir_all(&snapshot).unwrap();
+        fs::write(repo.join("refs").join("main"), "abc123\n").unwrap();
+        fs::write(repo.join("blobs").join("1111"), "{}").unwrap();
+        std::os::unix::fs::symlink("../../blobs/1111", snapshot.join("config.json")).unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn test_list_cached_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let hub = dir.path().join("hub");
+        cached_repo(&hub);
+
+        let models = list_cached_models(&hub).unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].repo_id, "Qwen/Qwen3-0.6B");
+        assert_eq!(default_alias(&models[0].repo_id), "Qwen3-0.6B");
+        assert_eq!(
+            find_cached_model(&hub, "qwen/qwen3-0.6b").unwrap(),
+            models[0]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_path_follows_refs() {
+        let dir = tempfile::tempdir().unwrap();
+        let hub = dir.path().join("hub");
+        let snapshot = cached_repo(&hub);
+
+        let model = find_cached_model(&hub, "Qwen/Qwen3-0.6B").unwrap();
+        assert_eq!(model.snapshot_path(None).unwrap(), snapshot);
+        assert!(model.snapshot_path(Some("dev")).is_err());
+    }
+
+    #[test]
+    fn test_import_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let hub = dir.path().join("hub");
+        let snapshot = cached_repo(&hub);
+
+        let dst = dir.path().join("models").join("qwen3");
+        assert_eq!(import_snapshot(&snapshot, &dst).unwrap(), 1);
+        assert_eq!(
+            fs::read_link(dst.join("config.json")).unwrap(),
+            fs::canonicalize(snapshot.join("config.json")).unwrap()
+        );
+        assert!(dst.join("README.md").is_file());
+        assert!(import_snapshot(&snapshot, &dst).is_err());
+    }
+
+    #[test]
+    fn test_import_snapshot_with_missing_blob() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = cached_repo(&dir.path().join("hub"));
+        std::os::unix::fs::symlink("../../blobs/2222", snapshot.join("model.safetensors")).unwrap();
+
+        assert!(matches!(
+            import_snapshot(&snapshot, &dir.path().join("models").join("broken")),
+            Err(Error::HfCacheBlobMissing(_))
+        ));
+    }
+}
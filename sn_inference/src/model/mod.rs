@@ -1,5 +1,9 @@
+pub(crate) mod driver;
+pub(crate) mod gguf;
+pub(crate) mod hf_cache;
 pub(crate) mod model;
 pub(crate) mod model_kind;
+pub(crate) mod model_registry;
 pub mod model_runtime;
 pub(crate) mod models;
 pub(crate) mod weight;
@@ -1,9 +1,11 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheList;
 use crate::error::Result;
+use crate::lora::lora_adapter::LoraAdapter;
 use crate::mask::mask::AttentionMask;
 use crate::model::weight::Weight;
 use crate::module::Module;
 use mlx_rs::Array;
+use std::sync::Arc;
 
 #[derive(PartialEq)]
 pub enum ForwardType {
@@ -16,6 +18,10 @@ pub trait Model: Module {
     fn supports_quantization(&self) -> bool;
     fn load_weights(&mut self, weight: &Weight) -> Result<()>;
     fn get_num_layer(&self) -> usize;
+    /// Layers whose feed forward is a mixture of experts, which LoRA deltas do not adapt.
+    fn get_sparse_mlp_layers(&self) -> Vec<usize> {
+        Vec::new()
+    }
     fn forward_model(
         &mut self,
         x: &Array,
@@ -24,4 +30,6 @@ pub trait Model: Module {
         forward_type: &ForwardType,
     ) -> Result<Array>;
     fn get_model_bytes(&self) -> u64;
+    /// Apply `adapter` to the following forwards, `None` to go back to the base model.
+    fn set_adapter(&mut self, adapter: Option<Arc<LoraAdapter>>);
 }
@@ -1,20 +1,28 @@
 use crate::cache::k_v_cache::k_v_cache::{ArcCacheItem, ArcCacheList};
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraAdapter;
 use crate::mask::mask::AttentionMask;
 use crate::model::model::{ForwardType, Model};
+use crate::model::models::default::model::ModelDefault;
 use crate::model::models::llama::llama::ModelLLama;
+use crate::model::models::qwen2::qwen2::ModelQwen2;
 use crate::model::models::qwen3::qwen3::ModelQwen3;
+use crate::model::models::qwen3_moe::qwen3_moe::ModelQwen3Moe;
 use crate::model::weight::{Tensor, Weight};
 use crate::module::Module;
 use crate::quantized::Quantize;
 use mlx_rs::Array;
+use std::sync::Arc;
 
 macro_rules! delegate_to_variants {
     // For &self methods
     ($this:ident => $method:ident $(, $arg:expr)* ) => {
         match $this {
             ModelKind::LLaMA(m) => m.$method($($arg),*),
+            ModelKind::Qwen2(m) => m.$method($($arg),*),
             ModelKind::Qwen3(m) => m.$method($($arg),*),
+            ModelKind::Qwen3Moe(m) => m.$method($($arg),*),
+            ModelKind::Default(m) => m.$method($($arg),*),
         }
     };
 
@@ -22,14 +30,20 @@ macro_rules! delegate_to_variants {
     (mut $this:ident => $method:ident $(, $arg:expr)* ) => {
         match $this {
             ModelKind::LLaMA(m) => m.$method($($arg),*),
+            ModelKind::Qwen2(m) => m.$method($($arg),*),
             ModelKind::Qwen3(m) => m.$method($($arg),*),
+            ModelKind::Qwen3Moe(m) => m.$method($($arg),*),
+            ModelKind::Default(m) => m.$method($($arg),*),
         }
     };
 }
 #[derive(Debug)]
 pub enum ModelKind {
     LLaMA(ModelLLama),
+    Qwen2(ModelQwen2),
     Qwen3(ModelQwen3),
+    Qwen3Moe(ModelQwen3Moe),
+    Default(ModelDefault),
 }
 
 impl Module for ModelKind {
@@ -70,6 +84,10 @@ impl Model for ModelKind {
         delegate_to_variants!(self => get_num_layer)
     }
 
+    fn get_sparse_mlp_layers(&self) -> Vec<usize> {
+        delegate_to_variants!(self => get_sparse_mlp_layers)
+    }
+
     fn forward_model(
         &mut self,
         x: &Array,
@@ -83,4 +101,8 @@ impl Model for ModelKind {
     fn get_model_bytes(&self) -> u64 {
         delegate_to_variants!(self => get_model_bytes)
     }
+
+    fn set_adapter(&mut self, adapter: Option<Arc<LoraAdapter>>) {
+        delegate_to_variants!(mut self => set_adapter, adapter)
+    }
 }
@@ -0,0 +1,213 @@
+use crate::config::config::Config;
+use crate::error::{Error, Result};
+use crate::model::model_runtime::ModelRuntime;
+use crate::model::weight::count_parameters;
+use serde::{Deserialize, Serialize};
+use sn_core::types::model_info::ModelInfo;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+pub static REGISTRY_FILE: &str = "registry.json";
+static MODEL_ID_LEN: usize = 12;
+
+/// Installed models with their metadata, persisted next to the models directory so
+/// ids survive restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModelRegistry {
+    #[serde(skip)]
+    path: PathBuf,
+    pub models: Vec<ModelInfo>,
+}
+
+impl ModelRegistry {
+    /// Read the registry at `path`, starting empty when the file does not exist yet.
+    pub fn load(path: &Path) -> Result<ModelRegistry> {
+        let mut registry = if path.is_file() {
+            serde_json::from_str(&fs::read_to_string(path)?)?
+        } else {
+            ModelRegistry::default()
+        };
+        registry.path = path.to_path_buf();
+        Ok(registry)
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Bring the registry in line with the models directory: new directories are
+    /// inspected and registered, entries whose directory is gone are dropped.
+    pub fn sync(&mut self, models_path: &Path) -> Result<()> {
+        let aliases: Vec<String> = match fs::read_dir(models_path) {
+            Ok(entries) => entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        let count = self.models.len();
+        self.models.retain(|model| aliases.contains(&model.alias));
+        let mut changed = count != self.models.len();
+
+        for alias in aliases {
+            if self.models.iter().any(|model| model.alias == alias) {
+                continue;
+            }
+            let path = models_path.join(&alias).display().to_string();
+            let id = self.generate_id(&path);
+            match inspect_model(&id, &alias, &path) {
+                Ok(model) => {
+                    info!("Registered model {} with id {}", model.alias, model.id);
+                    self.models.push(model);
+                    changed = true;
+                }
+                Err(e) => warn!("Skipping {} from the model registry: {}", path, e),
+            }
+        }
+
+        if changed {
+            self.models.sort_by(|a, b| a.alias.cmp(&b.alias));
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Find a model by alias or id.
+    pub fn find(&self, name: &str) -> Option<&ModelInfo> {
+        self.models
+            .iter()
+            .find(|model| model.alias == name || model.id == name)
+    }
+
+    fn generate_id(&self, path: &str) -> String {
+        let mut salt = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        loop {
+            let mut hasher = DefaultHasher::new();
+            path.hash(&mut hasher);
+            salt.hash(&mut hasher);
+            let id = hex::encode(hasher.finish().to_be_bytes())[..MODEL_ID_LEN].to_string();
+            if self.find(&id).is_none() {
+                return id;
+            }
+            salt += 1;
+        }
+    }
+}
+
+/// Read the metadata of the model installed in `root_path` without loading its weights.
+fn inspect_model(id: &str, alias: &str, root_path: &str) -> Result<ModelInfo> {
+    let model_path = ModelRuntime::find_model_path_from_root(root_path)?;
+    let config = Config::new(&model_path)?;
+    let parameters = count_parameters(&config)?;
+    if parameters == 0 {
+        return Err(Error::NoTensorInModelFile);
+    }
+    Ok(ModelInfo {
+        id: id.to_string(),
+        alias: alias.to_string(),
+        path: root_path.to_string(),
+        architecture: config.model.architecture(),
+        parameters,
+        quantization: config.model.quantization(),
+        context_length: config.model.context_length(),
+        has_chat_template: config.tokenizer_custom.chat_template.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_info(id: &str, alias: &str) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            alias: alias.to_string(),
+            path: format!("/models/{}", alias),
+            architecture: "Qwen3ForCausalLM".to_string(),
+            parameters: 1_720_000_000,
+            quantization: None,
+            context_length: Some(40960),
+            has_chat_template: true,
+        }
+    }
+
+    #[test]
+    fn test_registry_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry_path = dir.path().join(REGISTRY_FILE);
+
+        let mut registry = ModelRegistry::load(&registry_path).unwrap();
+        assert!(registry.models.is_empty());
+        registry.models.push(model_info("0123456789ab", "qwen3"));
+        registry.save().unwrap();
+
+        let registry = ModelRegistry::load(&registry_path).unwrap();
+        assert_eq!(registry.models, vec![model_info("0123456789ab", "qwen3")]);
+    }
+
+    #[test]
+    fn test_sync_drops_removed_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_path = dir.path().join("models");
+        fs::create_dir_all(models_path.join("qwen3")).unwrap();
+        let registry_path = dir.path().join(REGISTRY_FILE);
+
+        let mut registry = ModelRegistry::load(&registry_path).unwrap();
+        registry.models.push(model_info("0123456789ab", "qwen3"));
+        registry.models.push(model_info("ba9876543210", "removed"));
+        // `qwen3` holds no model, it is kept because it is already registered.
+        registry.sync(&models_path).unwrap();
+        assert_eq!(registry.models, vec![model_info("0123456789ab", "qwen3")]);
+
+        let registry = ModelRegistry::load(&registry_path).unwrap();
+        assert_eq!(registry.models, vec![model_info("0123456789ab", "qwen3")]);
+    }
+
+    #[test]
+    fn test_find_by_id_or_alias() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = ModelRegistry::load(&dir.path().join(REGISTRY_FILE)).unwrap();
+        registry.models.push(model_info("0123456789ab", "qwen3"));
+
+        assert_eq!(
+            registry.find("0123456789ab"),
+            Some(&model_info("0123456789ab", "qwen3"))
+        );
+        assert_eq!(registry.find("0123456789ab"), registry.find("qwen3"));
+        assert!(registry.find("other").is_none());
+    }
+
+    #[test]
+    fn test_generate_id_is_unused() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = ModelRegistry::load(&dir.path().join(REGISTRY_FILE)).unwrap();
+        registry.models.push(model_info("0123456789ab", "qwen3"));
+
+        let id = registry.generate_id("/models/other");
+        assert_eq!(id.len(), MODEL_ID_LEN);
+        assert!(registry.find(&id).is_none());
+    }
+
+    #[test]
+    fn test_parameters_label() {
+        assert_eq!(
+            model_info("0123456789ab", "qwen3").parameters_label(),
+            "1.7B"
+        );
+    }
+}
@@ -1,31 +1,54 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheList;
 use crate::chat_template::chat_template::ChatTemplate;
+use crate::chat_template::tool_call_parser::parse_tool_calls;
 use crate::config::config::Config;
-use crate::config::config_model::{ConfigModel, ConfigModelCommon};
 use crate::error::{Error, Result};
 use crate::factory::model::create_model_instance;
+use crate::lora::lora_adapter::LoraAdapter;
 use crate::model::model::Model;
 use crate::model::model_kind::ModelKind;
 use crate::model::weight::Weight;
 use crate::quantized::Quantize;
+use crate::token::token_embedding_batcher::{
+    DEFAULT_EMBEDDING_BATCH_TOKENS, TokenEmbeddingBatcher, restore_order,
+};
 use crate::token::token_embedding_generator::TokenEmbeddingGenerator;
+use crate::token::token_reasoning_parser::TokenReasoningParser;
+use crate::token::token_scorer::TokenScorer;
 use crate::token::token_stream_manager::{PromptStreamCallback, TokenStreamManager};
 use crate::tokenizer::tokenizer::Tokenizer;
 use crate::utils::mlx::similarity::similarity_cos;
 use crate::utils::tokenizer::pad_encode_batch::pad_encode_batch;
-use mlx_rs::ops::indexing::IndexOp;
-use mlx_rs::ops::stack;
 use mlx_rs::Array;
+use mlx_rs::ops::indexing::IndexOp;
+use mlx_rs::ops::{concatenate, stack};
 use serde::{Deserialize, Serialize};
+use sn_core::types::completion_prompt::CompletionPrompt;
+use sn_core::types::continuation_score::{ContinuationScore, TokenLogprob};
 use sn_core::types::conversation::Conversation;
+use sn_core::types::embedding_options::{EmbeddingInputType, EmbeddingOptions};
+use sn_core::types::fim_prompt::FimPrompt;
+use sn_core::types::generate_text_options::GenerateTextOptions;
 use sn_core::types::message_stats::MessageStats;
+use sn_core::types::model_quantization::ModelQuantization;
+use sn_core::types::stream_data::StreamData;
+use sn_core::types::tool::{Tool, ToolCall};
 use sn_core::utils::rw_lock::RwLockExt;
+use std::collections::HashSet;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
+use tracing::warn;
 use walkdir::WalkDir;
 
-pub type GenerateTextResult = (String, Option<MessageStats>);
+#[derive(Debug, Clone, Default)]
+pub struct GenerateTextResult {
+    pub content: String,
+    /// What the model wrote while reasoning, apart from the answer.
+    pub reasoning: Option<String>,
+    pub stats: Option<MessageStats>,
+    pub tool_calls: Vec<ToolCall>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModelRuntime {
@@ -33,6 +56,7 @@ pub struct ModelRuntime {
     pub name: String,
     pub model_path: String,
     pub config: Rc<Config>,
+    pub quantization: Option<ModelQuantization>,
     #[serde(skip_serializing, skip_deserializing)]
     pub model: Option<Arc<RwLock<ModelKind>>>,
     #[serde(skip_serializing, skip_deserializing)]
@@ -41,15 +65,17 @@ pub struct ModelRuntime {
     pub weight: Option<Weight>,
     #[serde(skip_serializing, skip_deserializing)]
     pub chat_template: Option<Rc<ChatTemplate>>,
+    /// LoRA adapters loaded on top of the model, selected per request by name.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub adapters: Arc<RwLock<Vec<Arc<LoraAdapter>>>>,
 }
 
 //todo :// - Add support for multiple models in the same runtime
-const EMB_TASK_QUERY: &str =
-    "Instruct: Given a web search query, retrieve relevant passages that answer the query\nQuery: ";
 impl ModelRuntime {
     pub fn load_with_path(
         root_path: &str,
         id: &String,
+        name: &str,
         callback: Option<PromptStreamCallback>,
     ) -> Result<ModelRuntime> {
         let path = Path::new(&root_path);
@@ -59,7 +85,6 @@ impl ModelRuntime {
 
         let model_path = Self::find_model_path_from_root(&root_path)?;
         let config = Rc::new(Config::new(&model_path)?);
-        let name = Self::get_name(&config.model);
         let weight = Weight::new(&config, callback)?;
         let model = create_model_instance(config.clone())?;
         let tokenizer = Rc::new(Tokenizer::new(config.clone())?);
@@ -67,24 +92,19 @@ impl ModelRuntime {
 
         Ok(ModelRuntime {
             id: id.clone(),
-            name,
+            name: name.to_string(),
             model_path,
             config,
+            quantization: None,
             model: Some(model),
             tokenizer: Some(tokenizer),
             weight: Some(weight),
             chat_template: Some(chat_template),
+            adapters: Arc::new(RwLock::new(Vec::new())),
         })
     }
 
-    fn get_name(config_model: &ConfigModel) -> String {
-        match config_model {
-            ConfigModel::LLaMA(llama_config) => llama_config.get_name(),
-            ConfigModel::Qwen3(qwen3_config) => qwen3_config.get_name(),
-        }
-    }
-
-    fn find_model_path_from_root(root_path: &str) -> Result<String> {
+    pub(crate) fn find_model_path_from_root(root_path: &str) -> Result<String> {
         for entry in WalkDir::new(root_path).into_iter().flatten() {
             let path = entry.path();
             if path.is_file() {
@@ -102,7 +122,9 @@ impl ModelRuntime {
         Err(Error::RootModelPathNotFound(root_path.to_string()))
     }
 
-    pub fn routine_model(&mut self) -> Result<()> {
+    /// Prepare the model and load its weights. `quantization` is applied in memory once
+    /// the weights are loaded, unless the checkpoint is already quantized.
+    pub fn routine_model(&mut self, quantization: Option<ModelQuantization>) -> Result<()> {
         let model = self
             .model
             .as_ref()
@@ -113,17 +135,40 @@ impl ModelRuntime {
             .ok_or(Error::RoutineMissingWeight(self.name.clone()))?;
 
         model.write_lock("reading_model:sanitize")?.sanitize(weight);
-        if model
-            .read_lock("routine_model:supports_quantization")?
-            .supports_quantization()
-        {
-            // Passing 0,0 as no effect the model, because the model will automatically
-            // quantize compute from his config file.
-            model.write_lock("routine_model")?.quantize(0, 0)?;
+
+        // A quantized checkpoint needs quantized layers before its weights can be set.
+        let checkpoint_quantization = self.config.model.quantization().filter(|_| {
+            model
+                .read_lock("routine_model:supports_quantization")
+                .map(|m| m.supports_quantization())
+                .unwrap_or(false)
+        });
+        if let Some(q) = checkpoint_quantization {
+            model
+                .write_lock("routine_model")?
+                .quantize(q.group_size, q.bits)?;
         }
         model
             .write_lock("routine_model:load_weights")?
             .load_weights(weight)?;
+
+        match (checkpoint_quantization, quantization) {
+            (None, Some(q)) => {
+                model
+                    .write_lock("routine_model:quantize")?
+                    .quantize(q.group_size, q.bits)?;
+                // Release the full precision tensors, the model only keeps quantized ones.
+                self.weight = None;
+            }
+            (Some(stored), Some(q)) if stored != q => {
+                warn!(
+                    "Model {} is already quantized with {}, ignoring requested {}",
+                    self.name, stored, q
+                );
+            }
+            _ => {}
+        }
+        self.quantization = checkpoint_quantization.or(quantization);
         Ok(())
     }
 
@@ -131,25 +176,33 @@ impl ModelRuntime {
         &self,
         queries: &Vec<String>,
         documents: &Vec<String>,
+        options: &EmbeddingOptions,
     ) -> Result<Array> {
-        // Build inputs based on condition
-        let queries: Vec<_> = queries
-            .clone()
-            .iter()
-            .map(|query| format!("{}{}", EMB_TASK_QUERY, query))
-            .collect();
+        // Queries and documents get their own instruction
+        let queries = options.apply_instruction(queries, EmbeddingInputType::Query);
+        let documents = options.apply_instruction(documents, EmbeddingInputType::Document);
 
         let mut all_texts = Vec::new();
         all_texts.extend(queries.iter().cloned());
         all_texts.extend(documents.iter().cloned());
-        let embeddings = self.generate_embeddings(&all_texts)?;
+        let embeddings = self.embed(&all_texts, options)?;
         let query_embeddings = embeddings.index(0..queries.len() as i32);
         let doc_embeddings = embeddings.index(queries.len() as i32..);
         let scores = similarity_cos(&query_embeddings, &doc_embeddings)?;
         Ok(scores)
     }
 
-    pub fn generate_embeddings(&self, texts: &Vec<String>) -> Result<Array> {
+    /// Embeddings of `texts`, prefixed with the instruction of `options.input_type`.
+    pub fn generate_embeddings(
+        &self,
+        texts: &Vec<String>,
+        options: &EmbeddingOptions,
+    ) -> Result<Array> {
+        let texts = options.apply_instruction(texts, options.input_type);
+        self.embed(&texts, options)
+    }
+
+    fn embed(&self, texts: &Vec<String>, options: &EmbeddingOptions) -> Result<Array> {
         let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
         let model = self.model.as_ref().ok_or(Error::MissingModel)?;
 
@@ -158,23 +211,49 @@ impl ModelRuntime {
         // get Pad token
         let pad_token = tokenizer.get_pad_token_id().ok_or(Error::MissingPadToken)?;
 
-        let batch = tokenizer.encode_batch(texts.clone(), true)?;
-        let batch = pad_encode_batch(&batch, pad_token.pad_id)?;
-        let inputs: Vec<Array> = batch.iter().map(|i| to_array(i.get_ids())).collect();
-        let masks: Vec<Array> = batch
-            .iter()
-            .map(|i| to_array(i.get_attention_mask()))
-            .collect();
-        let inputs = stack(inputs.as_ref())?;
-        let masks = stack(masks.as_ref())?;
+        let context_length = self
+            .config
+            .model
+            .context_length()
+            .map(|length| length as usize);
+        let max_length = match (context_length, options.max_length) {
+            (Some(context_length), Some(max_length)) => Some(context_length.min(max_length)),
+            (context_length, max_length) => context_length.or(max_length),
+        };
+        let batcher = TokenEmbeddingBatcher::new(max_length, DEFAULT_EMBEDDING_BATCH_TOKENS);
+
+        let mut encodings = tokenizer.encode_batch(texts.clone(), true)?;
+        batcher.truncate(&mut encodings);
+        let lengths: Vec<usize> = encodings.iter().map(|encoding| encoding.len()).collect();
+        let batches = batcher.plan(&lengths);
+
         let token_embedding_gen = TokenEmbeddingGenerator::new(model.clone());
-        Ok(token_embedding_gen.generate(&inputs, &masks)?)
+        let mut embeddings = Vec::with_capacity(batches.len());
+        for indices in &batches {
+            let batch: Vec<_> = indices.iter().map(|&idx| encodings[idx].clone()).collect();
+            let batch = pad_encode_batch(&batch, pad_token.pad_id)?;
+            let inputs: Vec<Array> = batch.iter().map(|i| to_array(i.get_ids())).collect();
+            let masks: Vec<Array> = batch
+                .iter()
+                .map(|i| to_array(i.get_attention_mask()))
+                .collect();
+            let inputs = stack(inputs.as_ref())?;
+            let masks = stack(masks.as_ref())?;
+            embeddings.push(token_embedding_gen.generate(&inputs, &masks, options)?);
+        }
+
+        let embeddings = concatenate(&embeddings)?;
+        let positions = restore_order(&batches);
+        let positions = Array::from_slice(&positions, &[positions.len() as i32]);
+        Ok(embeddings.take_axis(&positions, 0)?)
     }
 
     pub fn generate_text(
         &self,
         conversation: &Conversation,
+        options: &GenerateTextOptions,
         cache: ArcCacheList,
+        adapter: Option<&str>,
         callback: Option<PromptStreamCallback>,
     ) -> Result<GenerateTextResult> {
         let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
@@ -185,18 +264,272 @@ impl ModelRuntime {
             .ok_or(Error::MissingChatTemplate)?;
 
         // Render prompt from conversation
-        let inputs = chat_template.apply_chat_template(conversation, None, None)?;
+        let tools: Option<Vec<Tool>> = options
+            .tools
+            .as_ref()
+            .map(|tools| tools.iter().cloned().map(Tool::Schema).collect());
+        let inputs = if options.continue_final_message {
+            chat_template.continue_final_message(
+                conversation,
+                tools.as_deref(),
+                None,
+                options.enable_thinking,
+            )?
+        } else {
+            chat_template.apply_chat_template(
+                conversation,
+                tools.as_deref(),
+                None,
+                options.enable_thinking,
+            )?
+        };
+        let in_reasoning = TokenReasoningParser::prompt_opens_reasoning(&inputs)
+            || (options.continue_final_message && ChatTemplate::continues_reasoning(conversation));
         let prompt_ids = tokenizer.encode_prompt(vec![inputs])?;
 
         if prompt_ids.is_empty() {
             return Err(Error::EmptyPrompt);
         }
 
-        let mut stream = TokenStreamManager::new(model.clone(), tokenizer.clone());
-        let generated_text = stream.generate_text(prompt_ids, cache, callback.clone())?;
+        let adapter = adapter.map(|name| self.get_adapter(name)).transpose()?;
+        let tools_offered = tools.as_ref().is_some_and(|tools| !tools.is_empty());
+        let mut stream = TokenStreamManager::new(model.clone(), adapter, tokenizer.clone())
+            .with_reasoning_started(in_reasoning)
+            .with_tool_calls(tools_offered)
+            .with_max_tokens(options.max_tokens);
+        let (content, reasoning) = stream.generate_text(prompt_ids, cache, callback.clone())?;
+
+        // Tool calls are only looked for when tools were offered, a plain JSON answer
+        // would pass for one otherwise.
+        let (content, tool_calls) = match tools_offered {
+            true => parse_tool_calls(&content),
+            false => (content, Vec::new()),
+        };
+        if let (Some(cb), false) = (&callback, tool_calls.is_empty()) {
+            let _ = cb.send(StreamData::for_tool_calls(tool_calls.clone()));
+        }
         let stats = stream.get_average_stats(conversation.id, callback)?;
 
-        Ok((generated_text, stats))
+        Ok(GenerateTextResult {
+            content,
+            reasoning,
+            stats,
+            tool_calls,
+        })
+    }
+
+    /// Generate from `prompt` as it is, without chat template, reasoning or tool calls.
+    /// With `ignore_eos` only `max_tokens` ends the generation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_completion(
+        &self,
+        prompt: &CompletionPrompt,
+        add_special_tokens: bool,
+        max_tokens: Option<usize>,
+        ignore_eos: bool,
+        cache: ArcCacheList,
+        adapter: Option<&str>,
+        callback: Option<PromptStreamCallback>,
+    ) -> Result<GenerateTextResult> {
+        let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
+        let prompt_ids = match prompt {
+            CompletionPrompt::Text(text) => tokenizer
+                .encode(text, add_special_tokens)?
+                .get_ids()
+                .to_vec(),
+            CompletionPrompt::Ids(ids) => ids.clone(),
+        };
+        if prompt_ids.is_empty() {
+            return Err(Error::EmptyPrompt);
+        }
+        self.generate_raw(
+            prompt_ids,
+            HashSet::new(),
+            max_tokens,
+            ignore_eos,
+            cache,
+            adapter,
+            callback,
+        )
+    }
+
+    /// Token ids of `text` with the tokenizer of the model.
+    pub fn tokenize(&self, text: &str, add_special_tokens: bool) -> Result<Vec<u32>> {
+        let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
+        Ok(tokenizer
+            .encode(text, add_special_tokens)?
+            .get_ids()
+            .to_vec())
+    }
+
+    /// Log-likelihood of each continuation given `context`, read from one forward pass
+    /// over all of them without sampling.
+    pub fn score(
+        &self,
+        context: &str,
+        continuations: &[String],
+        adapter: Option<&str>,
+    ) -> Result<Vec<ContinuationScore>> {
+        let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
+        let model = self.model.as_ref().ok_or(Error::MissingModel)?;
+
+        let context_ids = tokenizer.encode(context, true)?.get_ids().to_vec();
+        // Without context tokens, the first token of a continuation is only conditioned
+        // on, the model has nothing to predict it from.
+        let scored_from = context_ids.len().max(1);
+        let mut sequences = Vec::with_capacity(continuations.len());
+        for continuation in continuations {
+            let mut ids = context_ids.clone();
+            ids.extend_from_slice(tokenizer.encode(continuation, false)?.get_ids());
+            sequences.push(ids);
+        }
+
+        let pad_id = tokenizer
+            .get_pad_token_id()
+            .map(|pad_token| pad_token.pad_id)
+            .unwrap_or_default();
+        let adapter = adapter.map(|name| self.get_adapter(name)).transpose()?;
+        let scores = TokenScorer::new(model.clone(), adapter).score(&sequences, pad_id)?;
+
+        Ok(sequences
+            .iter()
+            .zip(scores)
+            .enumerate()
+            .map(|(index, (ids, scores))| {
+                // Scores start at the second token of the sequence.
+                let skip = scored_from.min(ids.len()).saturating_sub(1);
+                let tokens = ids
+                    .iter()
+                    .skip(skip + 1)
+                    .zip(scores.logprobs.iter().skip(skip))
+                    .map(|(&id, &logprob)| TokenLogprob {
+                        id,
+                        token: tokenizer.decode_response(&vec![id], false),
+                        logprob,
+                    })
+                    .collect();
+                let greedy = scores.greedy.iter().skip(skip).all(|greedy| *greedy);
+                ContinuationScore::new(index, tokens, greedy)
+            })
+            .collect())
+    }
+
+    /// Generate the code between `prefix` and `suffix` with the FIM tokens of the model.
+    pub fn generate_fim(
+        &self,
+        prompt: &FimPrompt,
+        cache: ArcCacheList,
+        adapter: Option<&str>,
+        callback: Option<PromptStreamCallback>,
+    ) -> Result<GenerateTextResult> {
+        let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
+        let fim = tokenizer
+            .fim_tokens()
+            .ok_or_else(|| Error::FimNotSupported(self.name.clone()))?;
+        let encode = |text: &str| -> Result<Vec<u32>> {
+            Ok(tokenizer.encode(text, false)?.get_ids().to_vec())
+        };
+        let prompt_ids = fim.prompt_ids(
+            &encode(&prompt.prefix)?,
+            &encode(&prompt.suffix)?,
+            &encode(prompt.middle.as_deref().unwrap_or_default())?,
+        );
+        self.generate_raw(
+            prompt_ids,
+            fim.stop_ids,
+            None,
+            false,
+            cache,
+            adapter,
+            callback,
+        )
+    }
+
+    /// Generate from prompt ids built without the chat template, the text is returned
+    /// as generated.
+    #[allow(clippy::too_many_arguments)]
+    fn generate_raw(
+        &self,
+        prompt_ids: Vec<u32>,
+        stop_ids: HashSet<u32>,
+        max_tokens: Option<usize>,
+        ignore_eos: bool,
+        cache: ArcCacheList,
+        adapter: Option<&str>,
+        callback: Option<PromptStreamCallback>,
+    ) -> Result<GenerateTextResult> {
+        let tokenizer = self.tokenizer.as_ref().ok_or(Error::MissingTokenizer)?;
+        let model = self.model.as_ref().ok_or(Error::MissingModel)?;
+        let adapter = adapter.map(|name| self.get_adapter(name)).transpose()?;
+        let mut stream = TokenStreamManager::new(model.clone(), adapter, tokenizer.clone())
+            .with_stop_ids(stop_ids)
+            .with_ignore_eos(ignore_eos)
+            .with_max_tokens(max_tokens);
+        let (content, _) = stream.generate_text(prompt_ids, cache, callback.clone())?;
+        let stats = stream.get_average_stats(None, callback)?;
+
+        Ok(GenerateTextResult {
+            content,
+            stats,
+            ..GenerateTextResult::default()
+        })
+    }
+
+    pub fn load_adapter(&self, name: &str, path: &str) -> Result<Arc<LoraAdapter>> {
+        if self.get_adapter(name).is_ok() {
+            return Err(Error::AdapterAlreadyLoaded(name.to_string()));
+        }
+        let adapter = Arc::new(LoraAdapter::load(
+            name,
+            path,
+            self.get_num_layer()?,
+            &self.get_sparse_mlp_layers()?,
+        )?);
+        self.adapters
+            .write_lock("load_adapter")?
+            .push(adapter.clone());
+        Ok(adapter)
+    }
+
+    /// Requests already generating with the adapter keep their reference to it.
+    pub fn unload_adapter(&self, name: &str) -> Result<()> {
+        let mut adapters = self.adapters.write_lock("unload_adapter")?;
+        let index = adapters
+            .iter()
+            .position(|adapter| adapter.name == name)
+            .ok_or_else(|| Error::AdapterNotFound(name.to_string()))?;
+        adapters.remove(index);
+        Ok(())
+    }
+
+    pub fn get_adapter(&self, name: &str) -> Result<Arc<LoraAdapter>> {
+        self.adapters
+            .read_lock("get_adapter")?
+            .iter()
+            .find(|adapter| adapter.name == name)
+            .cloned()
+            .ok_or_else(|| Error::AdapterNotFound(name.to_string()))
+    }
+
+    pub fn list_adapters(&self) -> Result<Vec<Arc<LoraAdapter>>> {
+        Ok(self.adapters.read_lock("list_adapters")?.clone())
+    }
+
+    pub fn get_model_bytes(&self) -> Result<u64> {
+        let model = self
+            .model
+            .as_ref()
+            .ok_or(Error::RoutineMissingModel(self.name.clone()))?;
+        Ok(model.read_lock("get_model_bytes")?.get_model_bytes())
+    }
+
+    pub fn get_sparse_mlp_layers(&self) -> Result<Vec<usize>> {
+        match &self.model {
+            Some(model) => Ok(model
+                .read_lock("get_sparse_mlp_layers")?
+                .get_sparse_mlp_layers()),
+            None => Ok(Vec::new()),
+        }
     }
 
     pub fn get_num_layer(&self) -> Result<usize> {
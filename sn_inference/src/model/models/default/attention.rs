@@ -0,0 +1,224 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::default::DefaultConfig;
+use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
+use crate::mask::mask::AttentionMask;
+use crate::model::models::default::norm::NormDefault;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use crate::safe_quantize;
+use crate::utils::maybe_quantized::{MaybeQuantizedLinear, QuantizableParam};
+use crate::utils::rope::Rope;
+use crate::utils::scaled_dot_product_attention::scaled_dot_product_attention;
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::nn::{Linear, LinearBuilder};
+use mlx_rs::quantization::MaybeQuantized;
+use sn_core::utils::rw_lock::RwLockExt;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub struct AttentionDefault {
+    n_heads: i32,
+    n_kv_heads: i32,
+    scale: f64,
+
+    q_proj: MaybeQuantized<Linear>,
+    k_proj: MaybeQuantized<Linear>,
+    v_proj: MaybeQuantized<Linear>,
+    o_proj: MaybeQuantized<Linear>,
+    rope: Rope,
+    q_norm: Option<NormDefault>,
+    k_norm: Option<NormDefault>,
+    lora: Option<Arc<LoraLayer>>,
+}
+
+impl Quantize for AttentionDefault {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        safe_quantize!(self, group_size, bits, q_proj, k_proj, v_proj, o_proj,);
+        Ok(())
+    }
+}
+
+impl Module for AttentionDefault {
+    fn forward(
+        &mut self,
+        x: &Array,
+        mask: Option<&AttentionMask>,
+        cache: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        let shape = x.shape();
+        let b = shape[0];
+        let l = shape[1];
+
+        let mut queries = self
+            .q_proj
+            .forward_lora(x, self.lora.as_deref(), "q_proj")?
+            .reshape(&[b, l, self.n_heads, -1])?;
+        let mut keys = self
+            .k_proj
+            .forward_lora(x, self.lora.as_deref(), "k_proj")?
+            .reshape(&[b, l, self.n_kv_heads, -1])?;
+        let values = self
+            .v_proj
+            .forward_lora(x, self.lora.as_deref(), "v_proj")?
+            .reshape(&[b, l, self.n_kv_heads, -1])?;
+
+        if let Some(q_norm) = self.q_norm.as_mut() {
+            queries = q_norm.forward(&queries)?;
+        }
+        if let Some(k_norm) = self.k_norm.as_mut() {
+            keys = k_norm.forward(&keys)?;
+        }
+
+        let mut queries = queries.transpose_axes(&[0, 2, 1, 3])?;
+        let mut keys = keys.transpose_axes(&[0, 2, 1, 3])?;
+        let mut values = values.transpose_axes(&[0, 2, 1, 3])?;
+
+        if let Some(cache_ref) = cache.as_ref() {
+            let context = "reading cache for offset";
+            let offset = cache_ref.read_lock(context)?.offset;
+            queries = self.rope.forward(&queries, offset)?;
+            keys = self.rope.forward(&keys, offset)?;
+
+            let context = "updating cache";
+            let (k, v) = cache_ref
+                .write_lock(context)?
+                .update_and_fetch(&keys, &values)?;
+            keys = k;
+            values = v;
+        } else {
+            queries = self.rope.forward(&queries, 0)?;
+            keys = self.rope.forward(&keys, 0)?;
+        }
+
+        let output =
+            scaled_dot_product_attention(&queries, &keys, &values, None, self.scale as f32, mask)?
+                .transpose_axes(&[0, 2, 1, 3])?
+                .reshape(&[b, l, -1])?;
+
+        self.o_proj
+            .forward_lora(&output, self.lora.as_deref(), "o_proj")
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name {
+            "q_proj.weight" => Ok(self.q_proj.update_weight(&tensor.data)),
+            "k_proj.weight" => Ok(self.k_proj.update_weight(&tensor.data)),
+            "v_proj.weight" => Ok(self.v_proj.update_weight(&tensor.data)),
+            "o_proj.weight" => Ok(self.o_proj.update_weight(&tensor.data)),
+
+            "q_proj.bias" => Ok(self.q_proj.update_bias(&tensor.data)),
+            "k_proj.bias" => Ok(self.k_proj.update_bias(&tensor.data)),
+            "v_proj.bias" => Ok(self.v_proj.update_bias(&tensor.data)),
+            "o_proj.bias" => Ok(self.o_proj.update_bias(&tensor.data)),
+
+            "q_proj.scales" => Ok(self.q_proj.update_scales(&tensor.data)),
+            "k_proj.scales" => Ok(self.k_proj.update_scales(&tensor.data)),
+            "v_proj.scales" => Ok(self.v_proj.update_scales(&tensor.data)),
+            "o_proj.scales" => Ok(self.o_proj.update_scales(&tensor.data)),
+
+            "q_proj.biases" => Ok(self.q_proj.update_biases(&tensor.data)),
+            "k_proj.biases" => Ok(self.k_proj.update_biases(&tensor.data)),
+            "v_proj.biases" => Ok(self.v_proj.update_biases(&tensor.data)),
+            "o_proj.biases" => Ok(self.o_proj.update_biases(&tensor.data)),
+            _ => match (sub_name.split_once('.'), &mut self.q_norm, &mut self.k_norm) {
+                (Some(("q_norm", sub_name)), Some(q_norm), _) => {
+                    q_norm.set_weight(name, sub_name, tensor)
+                }
+                (Some(("k_norm", sub_name)), _, Some(k_norm)) => {
+                    k_norm.set_weight(name, sub_name, tensor)
+                }
+                _ => Err(Error::UnsupportedWeight(name.to_string())),
+            },
+        }
+    }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
+}
+
+impl AttentionDefault {
+    pub fn new(config: &DefaultConfig) -> Result<AttentionDefault> {
+        let hidden_size = config.hidden_size;
+        let n_heads = config.num_attention_heads;
+        let n_kv_heads = config.num_key_value_heads();
+        let bias = config.attention_bias();
+
+        if n_heads % n_kv_heads != 0 {
+            return Err(Error::InvalidConfig(
+                "n_heads must be divisible by n_kv_heads".into(),
+            ));
+        }
+
+        let head_dim = config.head_dim();
+        let scale = 1.0 / (head_dim as f64).sqrt();
+
+        let q_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_size,
+                output_dims: n_heads * head_dim,
+                bias,
+            }
+            .build()?,
+        );
+        let k_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_size,
+                output_dims: n_kv_heads * head_dim,
+                bias,
+            }
+            .build()?,
+        );
+        let v_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_size,
+                output_dims: n_kv_heads * head_dim,
+                bias,
+            }
+            .build()?,
+        );
+        let o_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: n_heads * head_dim,
+                output_dims: hidden_size,
+                bias,
+            }
+            .build()?,
+        );
+
+        let (q_norm, k_norm) = if config.use_qk_norm() {
+            (
+                Some(NormDefault::new(config, head_dim)?),
+                Some(NormDefault::new(config, head_dim)?),
+            )
+        } else {
+            (None, None)
+        };
+
+        let rope = Rope::new(
+            head_dim,
+            config.rope_theta,
+            config.rope_traditional(),
+            config.rope_scaling.as_ref(),
+            config.max_position_embeddings.unwrap_or(4096),
+        )?;
+
+        Ok(AttentionDefault {
+            n_heads,
+            n_kv_heads,
+            scale,
+            q_proj,
+            k_proj,
+            v_proj,
+            o_proj,
+            rope,
+            q_norm,
+            k_norm,
+            lora: None,
+        })
+    }
+}
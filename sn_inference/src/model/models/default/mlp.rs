@@ -0,0 +1,153 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::default::DefaultConfig;
+use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
+use crate::mask::mask::AttentionMask;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use crate::safe_quantize;
+use crate::utils::maybe_quantized::{MaybeQuantizedLinear, QuantizableParam};
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::nn::{Linear, LinearBuilder, gelu, gelu_approximate, relu, silu};
+use mlx_rs::quantization::MaybeQuantized;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ActivationDefault {
+    Silu,
+    Gelu,
+    GeluApproximate,
+    Relu,
+}
+
+impl ActivationDefault {
+    pub fn from_hidden_act(hidden_act: &str) -> Result<ActivationDefault> {
+        match hidden_act {
+            "silu" | "swish" => Ok(ActivationDefault::Silu),
+            "gelu" => Ok(ActivationDefault::Gelu),
+            "gelu_pytorch_tanh" | "gelu_new" | "gelu_fast" => {
+                Ok(ActivationDefault::GeluApproximate)
+            }
+            "relu" => Ok(ActivationDefault::Relu),
+            other => Err(Error::InvalidConfig(format!(
+                "unsupported hidden_act: {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn apply(&self, x: &Array) -> Result<Array> {
+        match self {
+            ActivationDefault::Silu => Ok(silu(x)?),
+            ActivationDefault::Gelu => Ok(gelu(x)?),
+            ActivationDefault::GeluApproximate => Ok(gelu_approximate(x)?),
+            ActivationDefault::Relu => Ok(relu(x)?),
+        }
+    }
+}
+
+/// Gated MLP `down(act(gate(x)) * up(x))`.
+#[derive(Debug, Clone)]
+pub struct MLPDefault {
+    activation: ActivationDefault,
+    gate_proj: MaybeQuantized<Linear>,
+    down_proj: MaybeQuantized<Linear>,
+    up_proj: MaybeQuantized<Linear>,
+    lora: Option<Arc<LoraLayer>>,
+}
+
+impl Quantize for MLPDefault {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        safe_quantize!(self, group_size, bits, gate_proj, down_proj, up_proj,);
+        Ok(())
+    }
+}
+
+impl Module for MLPDefault {
+    fn forward(
+        &mut self,
+        x: &Array,
+        _: Option<&AttentionMask>,
+        _: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        let gated = self.activation.apply(&self.gate_proj.forward_lora(
+            x,
+            self.lora.as_deref(),
+            "gate_proj",
+        )?)?;
+        let up = self
+            .up_proj
+            .forward_lora(x, self.lora.as_deref(), "up_proj")?;
+        self.down_proj
+            .forward_lora(&(gated * up), self.lora.as_deref(), "down_proj")
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name {
+            "gate_proj.weight" => Ok(self.gate_proj.update_weight(&tensor.data)),
+            "down_proj.weight" => Ok(self.down_proj.update_weight(&tensor.data)),
+            "up_proj.weight" => Ok(self.up_proj.update_weight(&tensor.data)),
+
+            "gate_proj.bias" => Ok(self.gate_proj.update_bias(&tensor.data)),
+            "down_proj.bias" => Ok(self.down_proj.update_bias(&tensor.data)),
+            "up_proj.bias" => Ok(self.up_proj.update_bias(&tensor.data)),
+
+            "gate_proj.scales" => Ok(self.gate_proj.update_scales(&tensor.data)),
+            "down_proj.scales" => Ok(self.down_proj.update_scales(&tensor.data)),
+            "up_proj.scales" => Ok(self.up_proj.update_scales(&tensor.data)),
+
+            "gate_proj.biases" => Ok(self.gate_proj.update_biases(&tensor.data)),
+            "down_proj.biases" => Ok(self.down_proj.update_biases(&tensor.data)),
+            "up_proj.biases" => Ok(self.up_proj.update_biases(&tensor.data)),
+            _ => Err(Error::UnsupportedWeight(name.to_string())),
+        }
+    }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
+}
+
+impl MLPDefault {
+    pub fn new(config: &DefaultConfig) -> Result<Self> {
+        let dim = config.hidden_size;
+        let hidden_dim = config.intermediate_size;
+        let bias = config.mlp_bias;
+
+        let gate_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: dim,
+                output_dims: hidden_dim,
+                bias,
+            }
+            .build()?,
+        );
+        let down_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_dim,
+                output_dims: dim,
+                bias,
+            }
+            .build()?,
+        );
+        let up_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: dim,
+                output_dims: hidden_dim,
+                bias,
+            }
+            .build()?,
+        );
+
+        Ok(MLPDefault {
+            activation: ActivationDefault::from_hidden_act(&config.hidden_act)?,
+            gate_proj,
+            down_proj,
+            up_proj,
+            lora: None,
+        })
+    }
+}
@@ -1,2 +1,5 @@
+pub(crate) mod attention;
+pub(crate) mod mlp;
 pub(crate) mod model;
+pub(crate) mod norm;
 pub(crate) mod transformer_block;
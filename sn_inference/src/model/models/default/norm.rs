@@ -0,0 +1,52 @@
+use crate::config::config_models::default::{DefaultConfig, DefaultNormType};
+use crate::error::{Error, Result};
+use crate::model::weight::Tensor;
+use crate::utils::rms_norm::NormExt;
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::module::Module as MLXModule;
+use mlx_rs::nn::{LayerNorm, LayerNormBuilder, RmsNorm, RmsNormBuilder};
+
+#[derive(Debug, Clone)]
+pub enum NormDefault {
+    RmsNorm(RmsNorm),
+    LayerNorm(LayerNorm),
+}
+
+impl NormDefault {
+    pub fn new(config: &DefaultConfig, dimensions: i32) -> Result<NormDefault> {
+        let eps = config.norm_eps();
+        match config.norm_type() {
+            DefaultNormType::RmsNorm => Ok(NormDefault::RmsNorm(
+                RmsNormBuilder { dimensions, eps }.build()?,
+            )),
+            DefaultNormType::LayerNorm => Ok(NormDefault::LayerNorm(
+                LayerNormBuilder {
+                    dimensions,
+                    eps,
+                    affine: true,
+                }
+                .build()?,
+            )),
+        }
+    }
+
+    pub fn forward(&mut self, x: &Array) -> Result<Array> {
+        match self {
+            NormDefault::RmsNorm(norm) => Ok(norm.forward(x)?),
+            NormDefault::LayerNorm(norm) => Ok(norm.forward(x)?),
+        }
+    }
+
+    pub fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match (self, sub_name) {
+            (NormDefault::RmsNorm(norm), "weight") => Ok(norm.update_weight(&tensor.data)),
+            (NormDefault::LayerNorm(norm), "weight") => Ok(norm.update_weight(&tensor.data)),
+            (NormDefault::LayerNorm(norm), "bias") => {
+                norm.bias.value = Some((*tensor.data).clone());
+                Ok(())
+            }
+            _ => Err(Error::UnsupportedWeight(name.to_string())),
+        }
+    }
+}
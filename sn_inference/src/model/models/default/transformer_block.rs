@@ -1,3 +1,17 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::default::DefaultConfig;
+use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::mask::mask::AttentionMask;
+use crate::model::models::default::attention::AttentionDefault;
+use crate::model::models::default::mlp::MLPDefault;
+use crate::model::models::default::norm::NormDefault;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use mlx_rs::Array;
+use std::sync::Arc;
+
 #[macro_export]
 macro_rules! default_forward_transformer_block {
     (
@@ -14,3 +28,62 @@ macro_rules! default_forward_transformer_block {
         Ok(residual + mlp_output)
     }};
 }
+
+#[derive(Debug, Clone)]
+pub struct TransformerBlockDefault {
+    self_attn: AttentionDefault,
+    mlp: MLPDefault,
+    input_layernorm: NormDefault,
+    post_attention_layernorm: NormDefault,
+}
+
+impl Quantize for TransformerBlockDefault {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        self.mlp.quantize(group_size, bits)?;
+        self.self_attn.quantize(group_size, bits)?;
+        Ok(())
+    }
+}
+
+impl Module for TransformerBlockDefault {
+    fn forward(
+        &mut self,
+        x: &Array,
+        mask: Option<&AttentionMask>,
+        cache: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        default_forward_transformer_block!(self, x, mask, cache)
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name.split_once('.') {
+            Some(("input_layernorm", sub_name)) => {
+                self.input_layernorm.set_weight(name, sub_name, tensor)
+            }
+            Some(("post_attention_layernorm", sub_name)) => self
+                .post_attention_layernorm
+                .set_weight(name, sub_name, tensor),
+            Some(("mlp", sub_name)) => self.mlp.set_weight(name, sub_name, tensor),
+            Some(("self_attn", sub_name)) => self.self_attn.set_weight(name, sub_name, tensor),
+            _ => Err(Error::UnsupportedWeight(name.to_string())),
+        }
+    }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        let lora = lora.as_deref();
+        self.self_attn
+            .set_lora(lora.and_then(|lora| lora.scoped("self_attn")));
+        self.mlp.set_lora(lora.and_then(|lora| lora.scoped("mlp")));
+    }
+}
+
+impl TransformerBlockDefault {
+    pub fn new(config: &DefaultConfig) -> Result<TransformerBlockDefault> {
+        Ok(TransformerBlockDefault {
+            self_attn: AttentionDefault::new(config)?,
+            mlp: MLPDefault::new(config)?,
+            input_layernorm: NormDefault::new(config, config.hidden_size)?,
+            post_attention_layernorm: NormDefault::new(config, config.hidden_size)?,
+        })
+    }
+}
@@ -1,20 +1,22 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
 use crate::config::config_models::llama::LLaMAConfig;
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
 use crate::mask::mask::AttentionMask;
-use crate::model::models::llama::rope::RopeLlama;
 use crate::model::weight::Tensor;
 use crate::module::Module;
 use crate::quantized::Quantize;
 use crate::utils::maybe_quantized::MaybeQuantizedLinear;
+use crate::utils::rope::Rope;
 use crate::utils::scaled_dot_product_attention::scaled_dot_product_attention;
 use mlx_rs::Array;
 use mlx_rs::builder::Builder;
-use mlx_rs::module::Module as MLXModule;
 use mlx_rs::nn::{Linear, LinearBuilder};
 use mlx_rs::quantization::{MaybeQuantized, Quantizable};
 use sn_core::utils::rw_lock::RwLockExt;
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct AttentionLlama {
@@ -26,7 +28,8 @@ pub struct AttentionLlama {
     k_proj: MaybeQuantized<Linear>,
     v_proj: MaybeQuantized<Linear>,
     o_proj: MaybeQuantized<Linear>,
-    rope: RopeLlama,
+    rope: Rope,
+    lora: Option<Arc<LoraLayer>>,
 }
 
 impl Quantize for AttentionLlama {
@@ -50,9 +53,15 @@ impl Module for AttentionLlama {
         let b = shape[0];
         let l = shape[1];
 
-        let mut queries = self.q_proj.forward(x)?;
-        let mut keys = self.k_proj.forward(x)?;
-        let mut values = self.v_proj.forward(x)?;
+        let mut queries = self
+            .q_proj
+            .forward_lora(x, self.lora.as_deref(), "q_proj")?;
+        let mut keys = self
+            .k_proj
+            .forward_lora(x, self.lora.as_deref(), "k_proj")?;
+        let mut values = self
+            .v_proj
+            .forward_lora(x, self.lora.as_deref(), "v_proj")?;
 
         // Prepare the queries, keys and values for the attention computation
         queries = queries
@@ -87,7 +96,8 @@ impl Module for AttentionLlama {
             scaled_dot_product_attention(&queries, &keys, &values, None, self.scale as f32, mask)?;
 
         let output = output.transpose_axes(&[0, 2, 1, 3])?.reshape(&[b, l, -1])?;
-        Ok(self.o_proj.forward(&output)?)
+        self.o_proj
+            .forward_lora(&output, self.lora.as_deref(), "o_proj")
     }
 
     fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
@@ -109,6 +119,10 @@ impl Module for AttentionLlama {
             _ => Err(Error::UnsupportedWeight(name.to_string())),
         }
     }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
 }
 
 impl AttentionLlama {
@@ -169,11 +183,12 @@ impl AttentionLlama {
             .build()?,
         );
 
-        let rope = RopeLlama::new(
+        let rope = Rope::new(
             head_dim,
             llama_config.rope_theta,
             false,
             llama_config.rope_scaling.as_ref(),
+            llama_config.max_position_embeddings,
         )?;
 
         Ok(AttentionLlama {
@@ -185,6 +200,7 @@ impl AttentionLlama {
             v_proj,
             o_proj,
             rope,
+            lora: None,
         })
     }
 }
@@ -2,6 +2,7 @@ use crate::cache::k_v_cache::k_v_cache::{ArcCacheItem, ArcCacheList, KVCache};
 use crate::config::config_models::llama::LLaMAConfig;
 use crate::error::{Error, Result};
 use crate::factory::mask::create_attention_mask;
+use crate::lora::lora_adapter::{LoraAdapter, is_same_adapter};
 use crate::mask::mask::AttentionMask;
 use crate::model::model::{ForwardType, Model};
 use crate::model::models::llama::transformer_block::TransformerBlockLlama;
@@ -28,18 +29,11 @@ pub struct ModelLLama {
     pub lm_head: MaybeQuantized<Linear>,
     pub embed_tokens: MaybeQuantized<Embedding>,
     pub bytes: u64,
+    pub adapter: Option<Arc<LoraAdapter>>,
 }
 
 impl Quantize for ModelLLama {
-    fn quantize(&mut self, _: i32, _: i32) -> Result<()> {
-        let mut bits = 4;
-        let mut group_size = 64;
-
-        if let Some(quantization) = &self.llama_config.quantization {
-            group_size = quantization.group_size;
-            bits = quantization.bits;
-        }
-
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
         self.lm_head = self.lm_head.clone().try_into_quantized(group_size, bits)?;
         self.embed_tokens = self
             .embed_tokens
@@ -169,6 +163,16 @@ impl Model for ModelLLama {
     fn get_model_bytes(&self) -> u64 {
         self.bytes
     }
+
+    fn set_adapter(&mut self, adapter: Option<Arc<LoraAdapter>>) {
+        if is_same_adapter(&self.adapter, &adapter) {
+            return;
+        }
+        for (idx, layer) in self.layers.iter_mut().enumerate() {
+            layer.set_lora(adapter.as_ref().and_then(|adapter| adapter.layer(idx)));
+        }
+        self.adapter = adapter;
+    }
 }
 
 impl ModelLLama {
@@ -204,6 +208,7 @@ impl ModelLLama {
             lm_head,
             embed_tokens,
             bytes: 0,
+            adapter: None,
         })
     }
 }
@@ -1,6 +1,8 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
 use crate::config::config_models::llama::LLaMAConfig;
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
 use crate::mask::mask::AttentionMask;
 use crate::model::weight::Tensor;
 use crate::module::Module;
@@ -8,16 +10,17 @@ use crate::quantized::Quantize;
 use crate::utils::maybe_quantized::MaybeQuantizedLinear;
 use mlx_rs::Array;
 use mlx_rs::builder::Builder;
-use mlx_rs::module::Module as MLXModule;
 use mlx_rs::nn::{Linear, LinearBuilder, silu};
 use mlx_rs::quantization::{MaybeQuantized, Quantizable};
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct MLPLlama {
     gate_proj: MaybeQuantized<Linear>,
     down_proj: MaybeQuantized<Linear>,
     up_proj: MaybeQuantized<Linear>,
+    lora: Option<Arc<LoraLayer>>,
 }
 
 impl Quantize for MLPLlama {
@@ -43,12 +46,18 @@ impl Module for MLPLlama {
         _: Option<ArcCacheItem>,
     ) -> Result<Array> {
         // Apply gate projection and activation
-        let gated = silu(self.gate_proj.forward(x)?)?;
+        let gated = silu(
+            self.gate_proj
+                .forward_lora(x, self.lora.as_deref(), "gate_proj")?,
+        )?;
         // Apply up projection
-        let up = self.up_proj.forward(x)?;
+        let up = self
+            .up_proj
+            .forward_lora(x, self.lora.as_deref(), "up_proj")?;
         // Element-wise multiply
         let multiplied = gated * up;
-        Ok(self.down_proj.forward(&multiplied)?)
+        self.down_proj
+            .forward_lora(&multiplied, self.lora.as_deref(), "down_proj")
     }
 
     fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
@@ -68,6 +77,10 @@ impl Module for MLPLlama {
             _ => Err(Error::UnsupportedWeight(name.to_string())),
         }
     }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
 }
 
 impl MLPLlama {
@@ -107,6 +120,7 @@ impl MLPLlama {
             gate_proj,
             down_proj,
             up_proj,
+            lora: None,
         })
     }
 }
@@ -1,5 +1,4 @@
 pub(crate) mod attention;
 pub(crate) mod llama;
 pub(crate) mod mlp;
-pub(crate) mod rope;
 pub(crate) mod transformer_block;
@@ -1,3 +1,5 @@
 pub(crate) mod default;
 pub(crate) mod llama;
+pub(crate) mod qwen2;
 pub(crate) mod qwen3;
+pub(crate) mod qwen3_moe;
@@ -0,0 +1,201 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::qwen2::Qwen2Config;
+use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
+use crate::mask::mask::AttentionMask;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use crate::safe_quantize;
+use crate::utils::maybe_quantized::MaybeQuantizedLinear;
+use crate::utils::maybe_quantized::QuantizableParam;
+use crate::utils::rope::Rope;
+use crate::utils::scaled_dot_product_attention::scaled_dot_product_attention;
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::nn::{Linear, LinearBuilder};
+use mlx_rs::quantization::MaybeQuantized;
+use sn_core::utils::rw_lock::RwLockExt;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Qwen2 attention: biased q/k/v projections and no q/k norm, unlike Qwen3.
+#[derive(Clone, Debug)]
+pub struct AttentionQwen2 {
+    n_heads: i32,
+    n_kv_heads: i32,
+    scale: f64,
+
+    q_proj: MaybeQuantized<Linear>,
+    k_proj: MaybeQuantized<Linear>,
+    v_proj: MaybeQuantized<Linear>,
+    o_proj: MaybeQuantized<Linear>,
+    rope: Rope,
+    lora: Option<Arc<LoraLayer>>,
+}
+
+impl Quantize for AttentionQwen2 {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        safe_quantize!(self, group_size, bits, q_proj, k_proj, v_proj, o_proj,);
+        Ok(())
+    }
+}
+
+impl Module for AttentionQwen2 {
+    fn forward(
+        &mut self,
+        x: &Array,
+        mask: Option<&AttentionMask>,
+        cache: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        let shape = x.shape();
+        let b = shape[0];
+        let l = shape[1];
+
+        let mut queries = self
+            .q_proj
+            .forward_lora(x, self.lora.as_deref(), "q_proj")?
+            .reshape(&[b, l, self.n_heads, -1])?
+            .transpose_axes(&[0, 2, 1, 3])?;
+        let mut keys = self
+            .k_proj
+            .forward_lora(x, self.lora.as_deref(), "k_proj")?
+            .reshape(&[b, l, self.n_kv_heads, -1])?
+            .transpose_axes(&[0, 2, 1, 3])?;
+        let mut values = self
+            .v_proj
+            .forward_lora(x, self.lora.as_deref(), "v_proj")?
+            .reshape(&[b, l, self.n_kv_heads, -1])?
+            .transpose_axes(&[0, 2, 1, 3])?;
+
+        if let Some(cache_ref) = cache.as_ref() {
+            let context = "reading cache for offset";
+            let offset = cache_ref.read_lock(context)?.offset;
+            queries = self.rope.forward(&queries, offset)?;
+            keys = self.rope.forward(&keys, offset)?;
+
+            let context = "updating cache";
+            let (k, v) = cache_ref
+                .write_lock(context)?
+                .update_and_fetch(&keys, &values)?;
+            keys = k;
+            values = v;
+        } else {
+            queries = self.rope.forward(&queries, 0)?;
+            keys = self.rope.forward(&keys, 0)?;
+        }
+
+        let output =
+            scaled_dot_product_attention(&queries, &keys, &values, None, self.scale as f32, mask)?
+                .transpose_axes(&[0, 2, 1, 3])?
+                .reshape(&[b, l, -1])?;
+
+        self.o_proj
+            .forward_lora(&output, self.lora.as_deref(), "o_proj")
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name {
+            "q_proj.weight" => Ok(self.q_proj.update_weight(&tensor.data)),
+            "k_proj.weight" => Ok(self.k_proj.update_weight(&tensor.data)),
+            "v_proj.weight" => Ok(self.v_proj.update_weight(&tensor.data)),
+            "o_proj.weight" => Ok(self.o_proj.update_weight(&tensor.data)),
+
+            "q_proj.bias" => Ok(self.q_proj.update_bias(&tensor.data)),
+            "k_proj.bias" => Ok(self.k_proj.update_bias(&tensor.data)),
+            "v_proj.bias" => Ok(self.v_proj.update_bias(&tensor.data)),
+
+            "q_proj.scales" => Ok(self.q_proj.update_scales(&tensor.data)),
+            "k_proj.scales" => Ok(self.k_proj.update_scales(&tensor.data)),
+            "v_proj.scales" => Ok(self.v_proj.update_scales(&tensor.data)),
+            "o_proj.scales" => Ok(self.o_proj.update_scales(&tensor.data)),
+
+            "q_proj.biases" => Ok(self.q_proj.update_biases(&tensor.data)),
+            "k_proj.biases" => Ok(self.k_proj.update_biases(&tensor.data)),
+            "v_proj.biases" => Ok(self.v_proj.update_biases(&tensor.data)),
+            "o_proj.biases" => Ok(self.o_proj.update_biases(&tensor.data)),
+            _ => Err(Error::UnsupportedWeight(name.to_string())),
+        }
+    }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
+}
+
+impl AttentionQwen2 {
+    pub fn new(qwen2_config: Rc<Qwen2Config>) -> Result<AttentionQwen2> {
+        let hidden_size = qwen2_config.hidden_size;
+        let n_heads = qwen2_config.num_attention_heads;
+        let n_kv_heads = qwen2_config.num_key_value_heads;
+
+        if hidden_size % n_heads != 0 {
+            return Err(Error::InvalidConfig(
+                "hidden_size must be divisible by n_heads".into(),
+            ));
+        }
+
+        if n_heads % n_kv_heads != 0 {
+            return Err(Error::InvalidConfig(
+                "n_heads must be divisible by n_kv_heads".into(),
+            ));
+        }
+
+        let head_dim = hidden_size / n_heads;
+        let scale = 1.0 / (head_dim as f64).sqrt();
+
+        let q_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_size,
+                output_dims: n_heads * head_dim,
+                bias: true,
+            }
+            .build()?,
+        );
+        let k_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_size,
+                output_dims: n_kv_heads * head_dim,
+                bias: true,
+            }
+            .build()?,
+        );
+        let v_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: hidden_size,
+                output_dims: n_kv_heads * head_dim,
+                bias: true,
+            }
+            .build()?,
+        );
+        let o_proj = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: n_heads * head_dim,
+                output_dims: hidden_size,
+                bias: false,
+            }
+            .build()?,
+        );
+
+        let rope = Rope::new(
+            head_dim,
+            qwen2_config.rope_theta,
+            false,
+            qwen2_config.rope_scaling.as_ref(),
+            qwen2_config.max_position_embeddings,
+        )?;
+
+        Ok(AttentionQwen2 {
+            n_heads,
+            n_kv_heads,
+            scale,
+            q_proj,
+            k_proj,
+            v_proj,
+            o_proj,
+            rope,
+            lora: None,
+        })
+    }
+}
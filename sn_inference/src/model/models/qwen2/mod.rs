@@ -0,0 +1,3 @@
+pub(crate) mod attention;
+pub(crate) mod qwen2;
+pub(crate) mod transformer_block;
@@ -0,0 +1,92 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::qwen2::Qwen2Config;
+use crate::default_forward_transformer_block;
+use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::mask::mask::AttentionMask;
+use crate::model::models::qwen2::attention::AttentionQwen2;
+use crate::model::models::qwen3::mlp::MLPQwen3;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use crate::utils::rms_norm::NormExt;
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::module::Module as MLXModule;
+use mlx_rs::nn::{RmsNorm, RmsNormBuilder};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct TransformerBlockQwen2 {
+    self_attn: AttentionQwen2,
+    mlp: MLPQwen3,
+    input_layernorm: RmsNorm,
+    post_attention_layernorm: RmsNorm,
+}
+
+impl Quantize for TransformerBlockQwen2 {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        self.mlp.quantize(group_size, bits)?;
+        self.self_attn.quantize(group_size, bits)?;
+        Ok(())
+    }
+}
+
+impl Module for TransformerBlockQwen2 {
+    fn forward(
+        &mut self,
+        x: &Array,
+        mask: Option<&AttentionMask>,
+        cache: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        default_forward_transformer_block!(self, x, mask, cache)
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name {
+            "post_attention_layernorm.weight" => {
+                Ok(self.post_attention_layernorm.update_weight(&tensor.data))
+            }
+            "input_layernorm.weight" => Ok(self.input_layernorm.update_weight(&tensor.data)),
+            _ => match sub_name.split_once('.') {
+                Some(("mlp", sub_name)) => self.mlp.set_weight(name, sub_name, tensor),
+                Some(("self_attn", sub_name)) => self.self_attn.set_weight(name, sub_name, tensor),
+                _ => Err(Error::UnsupportedWeight(name.to_string())),
+            },
+        }
+    }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        let lora = lora.as_deref();
+        self.self_attn
+            .set_lora(lora.and_then(|lora| lora.scoped("self_attn")));
+        self.mlp.set_lora(lora.and_then(|lora| lora.scoped("mlp")));
+    }
+}
+
+impl TransformerBlockQwen2 {
+    pub fn new(qwen2_config: Rc<Qwen2Config>) -> Result<TransformerBlockQwen2> {
+        let self_attn = AttentionQwen2::new(qwen2_config.clone())?;
+        let mlp =
+            MLPQwen3::new_with_dims(qwen2_config.hidden_size, qwen2_config.intermediate_size)?;
+
+        let input_layernorm = RmsNormBuilder {
+            dimensions: qwen2_config.hidden_size,
+            eps: qwen2_config.rms_norm_eps,
+        }
+        .build()?;
+        let post_attention_layernorm = RmsNormBuilder {
+            dimensions: qwen2_config.hidden_size,
+            eps: qwen2_config.rms_norm_eps,
+        }
+        .build()?;
+
+        Ok(TransformerBlockQwen2 {
+            self_attn,
+            mlp,
+            input_layernorm,
+            post_attention_layernorm,
+        })
+    }
+}
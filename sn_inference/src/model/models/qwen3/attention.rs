@@ -1,8 +1,9 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
 use crate::config::config_models::qwen3::Qwen3Config;
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
 use crate::mask::mask::AttentionMask;
-use crate::model::models::qwen3::rope::RopeQwen3;
 use crate::model::weight::Tensor;
 use crate::module::Module;
 use crate::quantized::Quantize;
@@ -10,6 +11,7 @@ use crate::safe_quantize;
 use crate::utils::maybe_quantized::MaybeQuantizedLinear;
 use crate::utils::maybe_quantized::QuantizableParam;
 use crate::utils::rms_norm::NormExt;
+use crate::utils::rope::Rope;
 use crate::utils::scaled_dot_product_attention::scaled_dot_product_attention;
 use mlx_rs::Array;
 use mlx_rs::builder::Builder;
@@ -18,6 +20,7 @@ use mlx_rs::nn::{Linear, LinearBuilder, RmsNorm, RmsNormBuilder};
 use mlx_rs::quantization::MaybeQuantized;
 use sn_core::utils::rw_lock::RwLockExt;
 use std::rc::Rc;
+use std::sync::Arc;
 #[derive(Clone, Debug)]
 pub struct AttentionQwen3 {
     n_heads: i32,
@@ -28,9 +31,10 @@ pub struct AttentionQwen3 {
     k_proj: MaybeQuantized<Linear>,
     v_proj: MaybeQuantized<Linear>,
     o_proj: MaybeQuantized<Linear>,
-    rope: RopeQwen3,
+    rope: Rope,
     q_norm: RmsNorm,
     k_norm: RmsNorm,
+    lora: Option<Arc<LoraLayer>>,
 }
 
 impl Quantize for AttentionQwen3 {
@@ -52,9 +56,15 @@ impl Module for AttentionQwen3 {
         let b = shape[0];
         let l = shape[1];
 
-        let mut queries = self.q_proj.forward(x)?;
-        let mut keys = self.k_proj.forward(x)?;
-        let mut values = self.v_proj.forward(x)?;
+        let mut queries = self
+            .q_proj
+            .forward_lora(x, self.lora.as_deref(), "q_proj")?;
+        let mut keys = self
+            .k_proj
+            .forward_lora(x, self.lora.as_deref(), "k_proj")?;
+        let mut values = self
+            .v_proj
+            .forward_lora(x, self.lora.as_deref(), "v_proj")?;
 
         queries = self.q_norm.forward(
             &queries
@@ -94,7 +104,9 @@ impl Module for AttentionQwen3 {
 
         output = output.transpose_axes(&[0, 2, 1, 3])?.reshape(&[b, l, -1])?;
 
-        let output = self.o_proj.forward(&output)?;
+        let output = self
+            .o_proj
+            .forward_lora(&output, self.lora.as_deref(), "o_proj")?;
         Ok(output)
     }
 
@@ -120,6 +132,10 @@ impl Module for AttentionQwen3 {
             _ => Err(Error::UnsupportedWeight(name.to_string())),
         }
     }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
 }
 
 impl AttentionQwen3 {
@@ -191,7 +207,13 @@ impl AttentionQwen3 {
         }
         .build()?;
 
-        let rope = RopeQwen3::new(head_dim, qwen3_config.rope_theta, false)?;
+        let rope = Rope::new(
+            head_dim,
+            qwen3_config.rope_theta,
+            false,
+            qwen3_config.rope_scaling.as_ref(),
+            qwen3_config.max_position_embeddings,
+        )?;
 
         Ok(AttentionQwen3 {
             n_heads,
@@ -204,6 +226,7 @@ impl AttentionQwen3 {
             rope,
             q_norm,
             k_norm,
+            lora: None,
         })
     }
 }
@@ -1,6 +1,8 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
 use crate::config::config_models::qwen3::Qwen3Config;
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
+use crate::lora::lora_linear::LoraLinear;
 use crate::mask::mask::AttentionMask;
 use crate::model::weight::Tensor;
 use crate::module::Module;
@@ -8,16 +10,17 @@ use crate::quantized::Quantize;
 use crate::utils::maybe_quantized::MaybeQuantizedLinear;
 use mlx_rs::Array;
 use mlx_rs::builder::Builder;
-use mlx_rs::module::Module as MLXModule;
 use mlx_rs::nn::{Linear, LinearBuilder, silu};
 use mlx_rs::quantization::{MaybeQuantized, Quantizable};
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct MLPQwen3 {
     gate_proj: MaybeQuantized<Linear>,
     down_proj: MaybeQuantized<Linear>,
     up_proj: MaybeQuantized<Linear>,
+    lora: Option<Arc<LoraLayer>>,
 }
 
 impl Quantize for MLPQwen3 {
@@ -43,12 +46,19 @@ impl Module for MLPQwen3 {
         _: Option<ArcCacheItem>,
     ) -> Result<Array> {
         // Apply gate projection and activation
-        let gated = silu(&self.gate_proj.forward(x)?)?;
+        let gated = silu(
+            &self
+                .gate_proj
+                .forward_lora(x, self.lora.as_deref(), "gate_proj")?,
+        )?;
         // Apply up projection
-        let up = self.up_proj.forward(x)?;
+        let up = self
+            .up_proj
+            .forward_lora(x, self.lora.as_deref(), "up_proj")?;
         // Element-wise multiply
         let multiplied = gated * up;
-        Ok(self.down_proj.forward(&multiplied)?)
+        self.down_proj
+            .forward_lora(&multiplied, self.lora.as_deref(), "down_proj")
     }
 
     fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
@@ -67,13 +77,19 @@ impl Module for MLPQwen3 {
             _ => Err(Error::UnsupportedWeight(name.to_string())),
         }
     }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        self.lora = lora;
+    }
 }
 
 impl MLPQwen3 {
     pub fn new(config: Rc<Qwen3Config>) -> Result<Self> {
-        let dim = config.hidden_size;
-        let hidden_dim = config.intermediate_size;
+        Self::new_with_dims(config.hidden_size, config.intermediate_size)
+    }
 
+    /// Gated SiLU MLP without biases, shared by the Qwen architectures.
+    pub fn new_with_dims(dim: i32, hidden_dim: i32) -> Result<Self> {
         let gate_proj = MaybeQuantized::new(
             LinearBuilder {
                 input_dims: dim,
@@ -105,6 +121,7 @@ impl MLPQwen3 {
             gate_proj,
             down_proj,
             up_proj,
+            lora: None,
         })
     }
 }
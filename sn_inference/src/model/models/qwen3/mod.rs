@@ -3,4 +3,3 @@ pub(crate) mod qwen3;
 pub(crate) mod transformer_block;
 
 pub(crate) mod attention;
-pub(crate) mod rope;
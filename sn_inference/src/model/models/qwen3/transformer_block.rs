@@ -2,6 +2,7 @@ use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
 use crate::config::config_models::qwen3::Qwen3Config;
 use crate::default_forward_transformer_block;
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraLayer;
 use crate::mask::mask::AttentionMask;
 use crate::model::models::qwen3::attention::AttentionQwen3;
 use crate::model::models::qwen3::mlp::MLPQwen3;
@@ -14,6 +15,7 @@ use mlx_rs::builder::Builder;
 use mlx_rs::module::Module as MLXModule;
 use mlx_rs::nn::{RmsNorm, RmsNormBuilder};
 use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct TransformerBlockQwen3 {
@@ -64,6 +66,13 @@ impl Module for TransformerBlockQwen3 {
         }
         Err(Error::UnsupportedWeight(name.to_string()))
     }
+
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        let lora = lora.as_deref();
+        self.self_attn
+            .set_lora(lora.and_then(|lora| lora.scoped("self_attn")));
+        self.mlp.set_lora(lora.and_then(|lora| lora.scoped("mlp")));
+    }
 }
 
 impl TransformerBlockQwen3 {
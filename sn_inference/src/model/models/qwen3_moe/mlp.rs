@@ -0,0 +1,69 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::qwen3_moe::Qwen3MoeConfig;
+use crate::error::Result;
+use crate::lora::lora_adapter::LoraLayer;
+use crate::mask::mask::AttentionMask;
+use crate::model::models::qwen3::mlp::MLPQwen3;
+use crate::model::models::qwen3_moe::sparse_moe_block::SparseMoeBlockQwen3Moe;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use mlx_rs::Array;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Feed forward of a Qwen3-MoE layer, dense for the layers listed in `mlp_only_layers`
+/// or skipped by `decoder_sparse_step`, sparse otherwise.
+#[derive(Debug, Clone)]
+pub enum MLPQwen3Moe {
+    Dense(MLPQwen3),
+    Sparse(SparseMoeBlockQwen3Moe),
+}
+
+impl Quantize for MLPQwen3Moe {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        match self {
+            MLPQwen3Moe::Dense(mlp) => mlp.quantize(group_size, bits),
+            MLPQwen3Moe::Sparse(mlp) => mlp.quantize(group_size, bits),
+        }
+    }
+}
+
+impl Module for MLPQwen3Moe {
+    fn forward(
+        &mut self,
+        x: &Array,
+        mask: Option<&AttentionMask>,
+        cache: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        match self {
+            MLPQwen3Moe::Dense(mlp) => mlp.forward(x, mask, cache),
+            MLPQwen3Moe::Sparse(mlp) => mlp.forward(x, mask, cache),
+        }
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match self {
+            MLPQwen3Moe::Dense(mlp) => mlp.set_weight(name, sub_name, tensor),
+            MLPQwen3Moe::Sparse(mlp) => mlp.set_weight(name, sub_name, tensor),
+        }
+    }
+
+    /// Experts are not adapted, only the dense layers take the `mlp.*` deltas. Adapters
+    /// with deltas for sparse layers are refused when they load.
+    fn set_lora(&mut self, lora: Option<Arc<LoraLayer>>) {
+        if let MLPQwen3Moe::Dense(mlp) = self {
+            mlp.set_lora(lora);
+        }
+    }
+}
+
+impl MLPQwen3Moe {
+    pub fn new(config: Rc<Qwen3MoeConfig>, layer_idx: i32) -> Result<Self> {
+        if config.is_sparse_layer(layer_idx) {
+            Ok(MLPQwen3Moe::Sparse(SparseMoeBlockQwen3Moe::new(config)?))
+        } else {
+            Ok(MLPQwen3Moe::Dense(MLPQwen3::new(config.base.clone())?))
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub(crate) mod mlp;
+pub(crate) mod qwen3_moe;
+pub(crate) mod sparse_moe_block;
+pub(crate) mod switch_mlp;
+pub(crate) mod transformer_block;
@@ -0,0 +1,256 @@
+use crate::cache::k_v_cache::k_v_cache::{ArcCacheItem, ArcCacheList, KVCache};
+use crate::config::config_models::qwen3_moe::Qwen3MoeConfig;
+use crate::error::{Error, Result};
+use crate::factory::mask::create_attention_mask;
+use crate::lora::lora_adapter::{LoraAdapter, is_same_adapter};
+use crate::mask::mask::AttentionMask;
+use crate::model::model::{ForwardType, Model};
+use crate::model::models::qwen3_moe::transformer_block::TransformerBlockQwen3Moe;
+use crate::model::weight::{Tensor, Weight};
+use crate::module::Module;
+use crate::quantized::Quantize;
+use crate::utils::maybe_quantized::{MaybeQuantizedEmbedding, MaybeQuantizedLinear};
+use crate::utils::rms_norm::NormExt;
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::module::Module as MLXModule;
+use mlx_rs::nn::RmsNorm;
+use mlx_rs::nn::{Embedding, Linear, LinearBuilder, RmsNormBuilder};
+use mlx_rs::ops::stack;
+use mlx_rs::quantization::{MaybeQuantized, Quantizable};
+use sn_core::utils::rw_lock::RwLockExt;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+use tracing::error;
+
+#[derive(Debug)]
+pub struct ModelQwen3Moe {
+    pub qwen3_moe_config: Rc<Qwen3MoeConfig>,
+    pub layers: Vec<TransformerBlockQwen3Moe>,
+    pub norm: RmsNorm,
+    pub lm_head: MaybeQuantized<Linear>,
+    pub embed_tokens: MaybeQuantized<Embedding>,
+    pub bytes: u64,
+    pub adapter: Option<Arc<LoraAdapter>>,
+}
+
+impl Quantize for ModelQwen3Moe {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        self.lm_head = self.lm_head.clone().try_into_quantized(group_size, bits)?;
+        self.embed_tokens = self
+            .embed_tokens
+            .clone()
+            .try_into_quantized(group_size, bits)?;
+        for layer in &mut self.layers {
+            layer.quantize(group_size, bits)?;
+        }
+        Ok(())
+    }
+}
+
+impl Module for ModelQwen3Moe {
+    fn forward(
+        &mut self,
+        _: &Array,
+        _: Option<&AttentionMask>,
+        _: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        unimplemented!()
+    }
+
+    fn set_weight(&mut self, name: &str, _: &str, tensor: &Tensor) -> Result<()> {
+        self.bytes += tensor.size;
+        match name {
+            "lm_head.weight" => return Ok(self.lm_head.update_weight(&tensor.data)),
+            "lm_head.scales" => return Ok(self.lm_head.update_scales(&tensor.data)),
+            "lm_head.biases" => return Ok(self.lm_head.update_biases(&tensor.data)),
+            "embed_tokens.weight" => {
+                return Ok(self.embed_tokens.update_weight(&tensor.data));
+            }
+            "embed_tokens.scales" => {
+                return Ok(self.embed_tokens.update_scales(&tensor.data));
+            }
+            "embed_tokens.biases" => {
+                return Ok(self.embed_tokens.update_biases(&tensor.data));
+            }
+            "norm.weight" => return Ok(self.norm.update_weight(&tensor.data)),
+            _ => {
+                if let Some(layer_subname) = name.strip_prefix("layers.") {
+                    let (idx, sub_name) = layer_subname
+                        .split_once('.')
+                        .and_then(|(idx, sub_name)| Some((idx.parse::<usize>().ok()?, sub_name)))
+                        .ok_or_else(|| Error::UnsupportedParseWeight(name.to_string()))?;
+                    if idx < self.layers.len() {
+                        return self.layers[idx].set_weight(name, sub_name, tensor);
+                    }
+                }
+            }
+        }
+        Err(Error::UnsupportedWeight(name.to_string()))
+    }
+}
+
+impl Model for ModelQwen3Moe {
+    fn sanitize(&mut self, weight: &mut Weight) {
+        if self.qwen3_moe_config.base.tie_word_embeddings {
+            weight.tensors.remove("lm_head.weight");
+        }
+
+        // Checkpoints converted from transformers keep one tensor per expert, stack them
+        // into the `switch_mlp` layout used by the gathered matmuls.
+        let num_experts = self.qwen3_moe_config.num_experts;
+        for layer_idx in 0..self.qwen3_moe_config.base.num_hidden_layers {
+            let prefix = format!("layers.{}.mlp", layer_idx);
+            for proj in ["gate_proj", "down_proj", "up_proj"] {
+                for kind in ["weight", "scales", "biases"] {
+                    let expert_name =
+                        |e: i32| format!("{}.experts.{}.{}.{}", prefix, e, proj, kind);
+                    if !weight.tensors.contains_key(&expert_name(0)) {
+                        continue;
+                    }
+                    let experts: Vec<Tensor> = (0..num_experts)
+                        .filter_map(|e| weight.tensors.remove(&expert_name(e)))
+                        .collect();
+                    let name = format!("{}.switch_mlp.{}.{}", prefix, proj, kind);
+                    if experts.len() != num_experts as usize {
+                        error!("Missing experts weights for {}", name);
+                        continue;
+                    }
+
+                    let arrays: Vec<&Array> = experts.iter().map(|t| t.data.as_ref()).collect();
+                    match stack(&arrays) {
+                        Ok(data) => {
+                            let tensor = Tensor {
+                                size: experts.iter().map(|t| t.size).sum(),
+                                dtype: experts[0].dtype,
+                                shape: data.shape().to_vec(),
+                                data: Arc::new(data),
+                            };
+                            weight.tensors.insert(name, tensor);
+                        }
+                        Err(e) => error!("Failed to stack experts weights for {}: {}", name, e),
+                    }
+                }
+            }
+        }
+    }
+
+    fn supports_quantization(&self) -> bool {
+        self.qwen3_moe_config.base.quantization.is_some()
+    }
+
+    fn load_weights(&mut self, weight: &Weight) -> Result<()> {
+        for (name, tensor) in &weight.tensors {
+            self.set_weight(name.as_str(), "", tensor)?
+        }
+        Ok(())
+    }
+
+    fn get_num_layer(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn get_sparse_mlp_layers(&self) -> Vec<usize> {
+        (0..self.layers.len())
+            .filter(|idx| self.qwen3_moe_config.is_sparse_layer(*idx as i32))
+            .collect()
+    }
+
+    fn forward_model(
+        &mut self,
+        x: &Array,
+        mask: Option<&AttentionMask>,
+        caches: Option<ArcCacheList>,
+        forward_type: &ForwardType,
+    ) -> Result<Array> {
+        let mut h = self.embed_tokens.forward(x)?;
+        let default_cache: Vec<Arc<RwLock<KVCache>>> = (0..self.layers.len())
+            .map(|idx| {
+                let mut cache = KVCache::default();
+                cache.layer_idx = idx as i32;
+                Arc::new(RwLock::new(cache))
+            })
+            .collect();
+
+        let default_cache = Arc::new(RwLock::new(default_cache));
+
+        let caches = caches.unwrap_or(default_cache);
+
+        let default_mask = create_attention_mask(&h, None, false)?;
+        let mask = match mask {
+            Some(_) => mask,
+            _ => Some(&default_mask),
+        };
+
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            let context = format!("ModelQwen3Moe:layers:{}:cache", i);
+            if let Some(cache) = caches.read_lock(context.as_str())?.get(i) {
+                h = layer.forward(&h, mask, Some(cache.clone()))?;
+            } else {
+                h = layer.forward(&h, mask, None)?;
+            }
+        }
+
+        let out = self.norm.forward(&h)?;
+        match forward_type {
+            ForwardType::Embedding => Ok(out),
+            ForwardType::Logits => {
+                if self.qwen3_moe_config.base.tie_word_embeddings {
+                    Ok(self.embed_tokens.as_linear(&out)?)
+                } else {
+                    Ok(self.lm_head.forward(&out)?)
+                }
+            }
+        }
+    }
+
+    fn get_model_bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    fn set_adapter(&mut self, adapter: Option<Arc<LoraAdapter>>) {
+        if is_same_adapter(&self.adapter, &adapter) {
+            return;
+        }
+        for (idx, layer) in self.layers.iter_mut().enumerate() {
+            layer.set_lora(adapter.as_ref().and_then(|adapter| adapter.layer(idx)));
+        }
+        self.adapter = adapter;
+    }
+}
+
+impl ModelQwen3Moe {
+    pub fn new(qwen3_moe_config: Rc<Qwen3MoeConfig>) -> Result<ModelQwen3Moe> {
+        let base = qwen3_moe_config.base.clone();
+        let layers = (0..base.num_hidden_layers)
+            .map(|layer_idx| TransformerBlockQwen3Moe::new(qwen3_moe_config.clone(), layer_idx))
+            .collect::<Result<Vec<_>>>()?;
+
+        let norm = RmsNormBuilder {
+            dimensions: base.hidden_size,
+            eps: base.rms_norm_eps,
+        }
+        .build()?;
+
+        let lm_head = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: base.hidden_size,
+                output_dims: base.vocab_size,
+                bias: false,
+            }
+            .build()?,
+        );
+
+        let embed_tokens = MaybeQuantized::new(Embedding::new(base.vocab_size, base.hidden_size)?);
+
+        Ok(ModelQwen3Moe {
+            qwen3_moe_config,
+            layers,
+            norm,
+            lm_head,
+            embed_tokens,
+            bytes: 0,
+            adapter: None,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
+use crate::config::config_models::qwen3_moe::Qwen3MoeConfig;
+use crate::error::{Error, Result};
+use crate::mask::mask::AttentionMask;
+use crate::model::models::qwen3_moe::switch_mlp::SwitchMLPQwen3Moe;
+use crate::model::weight::Tensor;
+use crate::module::Module;
+use crate::quantized::Quantize;
+use crate::safe_quantize;
+use crate::utils::maybe_quantized::{MaybeQuantizedLinear, QuantizableParam};
+use mlx_rs::Array;
+use mlx_rs::builder::Builder;
+use mlx_rs::module::Module as MLXModule;
+use mlx_rs::nn::{Linear, LinearBuilder};
+use mlx_rs::ops::indexing::{IndexOp, take_along_axis};
+use mlx_rs::ops::{argpartition_axis, expand_dims, softmax_axis};
+use mlx_rs::quantization::MaybeQuantized;
+use std::rc::Rc;
+
+#[derive(Debug, Clone)]
+pub struct SparseMoeBlockQwen3Moe {
+    top_k: i32,
+    norm_topk_prob: bool,
+    gate: MaybeQuantized<Linear>,
+    switch_mlp: SwitchMLPQwen3Moe,
+}
+
+impl Quantize for SparseMoeBlockQwen3Moe {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        safe_quantize!(self, group_size, bits, gate,);
+        self.switch_mlp.quantize(group_size, bits)?;
+        Ok(())
+    }
+}
+
+impl Module for SparseMoeBlockQwen3Moe {
+    fn forward(
+        &mut self,
+        x: &Array,
+        _: Option<&AttentionMask>,
+        _: Option<ArcCacheItem>,
+    ) -> Result<Array> {
+        // Route every token to its top-k experts
+        let gates = softmax_axis(&self.gate.forward(x)?, -1, true)?;
+        let k = self.top_k;
+        let indices = argpartition_axis(&gates.negative()?, k - 1, -1)?.index((.., .., ..k));
+        let mut scores = take_along_axis(&gates, &indices, -1)?;
+        if self.norm_topk_prob {
+            scores = &scores / scores.sum_axis(-1, true)?;
+        }
+
+        // Weighted sum of the selected experts outputs
+        let y = self.switch_mlp.forward(x, &indices)?;
+        Ok((y * expand_dims(&scores, -1)?).sum_axis(-2, false)?)
+    }
+
+    fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name {
+            "gate.weight" => Ok(self.gate.update_weight(&tensor.data)),
+            "gate.scales" => Ok(self.gate.update_scales(&tensor.data)),
+            "gate.biases" => Ok(self.gate.update_biases(&tensor.data)),
+            _ => match sub_name.split_once('.') {
+                Some(("switch_mlp", sub_name)) => {
+                    self.switch_mlp.set_weight(name, sub_name, tensor)
+                }
+                _ => Err(Error::UnsupportedWeight(name.to_string())),
+            },
+        }
+    }
+}
+
+impl SparseMoeBlockQwen3Moe {
+    pub fn new(config: Rc<Qwen3MoeConfig>) -> Result<Self> {
+        let gate = MaybeQuantized::new(
+            LinearBuilder {
+                input_dims: config.base.hidden_size,
+                output_dims: config.num_experts,
+                bias: false,
+            }
+            .build()?,
+        );
+        let switch_mlp = SwitchMLPQwen3Moe::new(config.clone())?;
+
+        Ok(SparseMoeBlockQwen3Moe {
+            top_k: config.num_experts_per_tok,
+            norm_topk_prob: config.norm_topk_prob,
+            gate,
+            switch_mlp,
+        })
+    }
+}
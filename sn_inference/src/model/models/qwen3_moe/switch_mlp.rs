@@ -0,0 +1,115 @@
+use crate::config::config_models::qwen3_moe::Qwen3MoeConfig;
+use crate::error::{Error, Result};
+use crate::model::weight::Tensor;
+use crate::quantized::Quantize;
+use crate::safe_quantize;
+use crate::utils::maybe_quantized::{
+    MaybeQuantizedLinear, MaybeQuantizedSwitchLinear, QuantizableParam,
+};
+use mlx_rs::Array;
+use mlx_rs::module::Param;
+use mlx_rs::nn::{Linear, silu};
+use mlx_rs::ops::{argsort_axis, floor_divide};
+use mlx_rs::quantization::MaybeQuantized;
+use std::rc::Rc;
+
+/// Below this amount of (token, expert) pairs, sorting the tokens by expert costs more
+/// than it saves on the gathered matmuls.
+const SORT_INDICES_THRESHOLD: usize = 64;
+
+/// All the experts of a sparse layer, each projection stacked on a leading expert axis.
+#[derive(Debug, Clone)]
+pub struct SwitchMLPQwen3Moe {
+    gate_proj: MaybeQuantized<Linear>,
+    down_proj: MaybeQuantized<Linear>,
+    up_proj: MaybeQuantized<Linear>,
+}
+
+impl Quantize for SwitchMLPQwen3Moe {
+    fn quantize(&mut self, group_size: i32, bits: i32) -> Result<()> {
+        safe_quantize!(self, group_size, bits, gate_proj, down_proj, up_proj,);
+        Ok(())
+    }
+}
+
+impl SwitchMLPQwen3Moe {
+    pub fn new(config: Rc<Qwen3MoeConfig>) -> Result<Self> {
+        let num_experts = config.num_experts;
+        let dim = config.base.hidden_size;
+        let hidden_dim = config.moe_intermediate_size;
+
+        Ok(SwitchMLPQwen3Moe {
+            gate_proj: Self::build_experts(num_experts, dim, hidden_dim)?,
+            down_proj: Self::build_experts(num_experts, hidden_dim, dim)?,
+            up_proj: Self::build_experts(num_experts, dim, hidden_dim)?,
+        })
+    }
+
+    fn build_experts(
+        num_experts: i32,
+        input_dims: i32,
+        output_dims: i32,
+    ) -> Result<MaybeQuantized<Linear>> {
+        Ok(MaybeQuantized::new(Linear {
+            weight: Param::new(Array::zeros::<f32>(&[
+                num_experts,
+                output_dims,
+                input_dims,
+            ])?),
+            bias: Param::new(None),
+        }))
+    }
+
+    /// Runs `x` of shape `[.., dim]` through the experts selected in `indices` of shape
+    /// `[.., top_k]`, returning one output per selected expert `[.., top_k, dim]`.
+    pub fn forward(&self, x: &Array, indices: &Array) -> Result<Array> {
+        let x = x.expand_dims_axes(&[-2, -3])?;
+        let indices_shape = indices.shape().to_vec();
+        let top_k = indices_shape[indices_shape.len() - 1];
+
+        // Grouping the tokens by expert lets the gathered matmuls read each expert once
+        let do_sort = indices.size() >= SORT_INDICES_THRESHOLD;
+        let (x, idx, inverse_order) = if do_sort {
+            let flat_indices = indices.flatten(None, None)?;
+            let order = argsort_axis(&flat_indices, 0)?;
+            let inverse_order = argsort_axis(&order, 0)?;
+            let x = x
+                .flatten(0, -3)?
+                .take_axis(&floor_divide(&order, Array::from_int(top_k))?, 0)?;
+            let idx = flat_indices.take_axis(&order, 0)?;
+            (x, idx, Some(inverse_order))
+        } else {
+            (x, indices.clone(), None)
+        };
+
+        let x_up = self.up_proj.gather_forward(&x, &idx, do_sort)?;
+        let x_gate = self.gate_proj.gather_forward(&x, &idx, do_sort)?;
+        let mut x = self
+            .down_proj
+            .gather_forward(&(silu(&x_gate)? * x_up), &idx, do_sort)?;
+
+        if let Some(inverse_order) = inverse_order {
+            let mut shape = indices_shape.clone();
+            shape.extend_from_slice(&x.shape()[1..]);
+            x = x.take_axis(&inverse_order, 0)?.reshape(&shape)?;
+        }
+        Ok(x.squeeze_axes(&[-2])?)
+    }
+
+    pub fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()> {
+        match sub_name {
+            "gate_proj.weight" => Ok(self.gate_proj.update_weight(&tensor.data)),
+            "down_proj.weight" => Ok(self.down_proj.update_weight(&tensor.data)),
+            "up_proj.weight" => Ok(self.up_proj.update_weight(&tensor.data)),
+
+            "gate_proj.scales" => Ok(self.gate_proj.update_scales(&tensor.data)),
+            "down_proj.scales" => Ok(self.down_proj.update_scales(&tensor.data)),
+            "up_proj.scales" => Ok(self.up_proj.update_scales(&tensor.data)),
+
+            "gate_proj.biases" => Ok(self.gate_proj.update_biases(&tensor.data)),
+            "down_proj.biases" => Ok(self.down_proj.update_biases(&tensor.data)),
+            "up_proj.biases" => Ok(self.up_proj.update_biases(&tensor.data)),
+            _ => Err(Error::UnsupportedWeight(name.to_string())),
+        }
+    }
+}
@@ -1,15 +1,17 @@
 use crate::config::config::Config;
 use crate::error::{Error, Result};
+use crate::model::gguf::reader::open_gguf;
+use crate::model::gguf::tensor::load_gguf_weights;
 use crate::token::token_stream_manager::PromptStreamCallback;
 use crate::utils::d_type::DTypeExt;
-use crate::utils::string::find_json_object_end_bytes;
 use glob::glob;
 use memmap2::MmapOptions;
 use mlx_rs::{Array, Dtype};
 use serde::Deserialize;
 use sn_core::server::payload::backend::run_model_response::RunModelResponseSSE;
+use sn_core::types::model_quantization::ModelQuantization;
 use sn_core::types::stream_data::StreamData;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::c_void;
 use std::fs::File;
 use std::path::Path;
@@ -17,8 +19,8 @@ use std::sync::Arc;
 use std::vec::Vec;
 use tracing::{debug, error};
 
-static HEADER_MAX_SAFETENSORS: usize = 100_000_000;
 static HEADER_OFFSET_SAFETENSORS: usize = 8;
+pub static INDEX_FILE_SAFETENSORS: &str = "model.safetensors.index.json";
 
 #[derive(Debug, Deserialize)]
 pub struct TensorJSON {
@@ -38,6 +40,59 @@ pub struct WeightJSON {
     #[serde(flatten)]
     pub tensors: HashMap<String, TensorJSON>,
 }
+/// Content of `model.safetensors.index.json` for sharded checkpoints.
+#[derive(Debug, Deserialize)]
+pub struct ModelIndexJSON {
+    pub weight_map: HashMap<String, String>,
+}
+
+impl ModelIndexJSON {
+    /// Shard file names referenced by the weight map, sorted and deduplicated.
+    pub fn shard_names(&self) -> Vec<String> {
+        let mut shards: Vec<String> = self
+            .weight_map
+            .values()
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        shards.sort();
+        shards
+    }
+
+    /// Check that the tensors declared in a shard header are exactly the ones
+    /// the index maps to that shard.
+    pub fn validate_shard(&self, shard: &str, weight_json: &WeightJSON) -> Result<()> {
+        let mismatch = |reason: String| Error::SafetensorsIndexMismatch {
+            shard: shard.to_string(),
+            reason,
+        };
+
+        for name in weight_json.tensors.keys() {
+            match self.weight_map.get(name) {
+                Some(mapped) if mapped == shard => {}
+                Some(mapped) => {
+                    return Err(mismatch(format!(
+                        "tensor '{}' is indexed in shard '{}'",
+                        name, mapped
+                    )));
+                }
+                None => return Err(mismatch(format!("tensor '{}' is not indexed", name))),
+            }
+        }
+
+        let missing = self
+            .weight_map
+            .iter()
+            .find(|(name, mapped)| *mapped == shard && !weight_json.tensors.contains_key(*name));
+        if let Some((name, _)) = missing {
+            return Err(mismatch(format!("indexed tensor '{}' is missing", name)));
+        }
+
+        Ok(())
+    }
+}
+
 #[allow(unused_variables)]
 #[derive(Debug)]
 pub struct Tensor {
@@ -58,30 +113,98 @@ pub struct Weight {
 
 impl Weight {
     pub fn new(config: &Config, callback: Option<PromptStreamCallback>) -> Result<Self> {
-        let weights_files = find_model_files(&config.root_path)?;
-        load_weights(&weights_files, callback)
+        if let Some(gguf_path) = &config.gguf_path {
+            return load_gguf_weights(gguf_path, callback);
+        }
+        let index = read_model_index(&config.root_path)?;
+        let weights_files = match &index {
+            Some(index) => find_index_files(&config.root_path, index)?,
+            None => find_model_files(&config.root_path)?,
+        };
+        load_weights(&weights_files, index.as_ref(), callback)
     }
 }
 
-fn read_safetensors_header(mmap: &memmap2::Mmap) -> Result<(WeightJSON, usize)> {
-    if mmap.len() < HEADER_MAX_SAFETENSORS {
-        return Err(Error::SafetensorsHeaderReadError);
+/// Number of parameters of the model, read from the tensor headers without loading the
+/// weights.
+pub(crate) fn count_parameters(config: &Config) -> Result<u64> {
+    if let Some(gguf_path) = &config.gguf_path {
+        let (_, header) = open_gguf(gguf_path)?;
+        return Ok(header
+            .tensors
+            .iter()
+            .map(|tensor| tensor.dims.iter().product::<u64>())
+            .sum());
     }
-    // Read len_bytes if needed (depending on your format)
-    let buffer = &mmap[HEADER_OFFSET_SAFETENSORS..HEADER_MAX_SAFETENSORS];
-
-    let real_size_header = find_json_object_end_bytes(buffer);
-    match real_size_header {
-        Some(size_header) => {
-            let buffer_slice = &buffer[..size_header];
-            let json_str = String::from_utf8_lossy(&buffer_slice)
-                .trim_end()
-                .to_string();
-            let json: WeightJSON = serde_json::from_str(&json_str)?;
-            Ok((json, size_header))
-        }
-        None => Err(Error::SafetensorsHeaderReadError),
+    let index = read_model_index(&config.root_path)?;
+    let weights_files = match &index {
+        Some(index) => find_index_files(&config.root_path, index)?,
+        None => find_model_files(&config.root_path)?,
+    };
+
+    let mut shapes: HashMap<String, Vec<i32>> = HashMap::new();
+    for file_path in &weights_files {
+        let file = File::open(file_path).map_err(|_| Error::FileOpenError(file_path.to_owned()))?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        let (weight_json, _) = read_safetensors_header(&mmap)?;
+        shapes.extend(
+            weight_json
+                .tensors
+                .into_iter()
+                .map(|(name, tensor)| (name, tensor.shape)),
+        );
     }
+    Ok(count_shapes_parameters(
+        &shapes,
+        config.model.quantization(),
+    ))
+}
+
+/// Packed quantized matrices are counted with their unpacked size, their scales and
+/// biases are left out.
+fn count_shapes_parameters(
+    shapes: &HashMap<String, Vec<i32>>,
+    quantization: Option<ModelQuantization>,
+) -> u64 {
+    let is_quantized = |prefix: &str| shapes.contains_key(&format!("{}.scales", prefix));
+    shapes
+        .iter()
+        .map(|(name, shape)| {
+            let size = shape.iter().map(|dim| *dim as u64).product::<u64>();
+            if let Some(prefix) = name
+                .strip_suffix(".scales")
+                .or_else(|| name.strip_suffix(".biases"))
+                && is_quantized(prefix)
+            {
+                return 0;
+            }
+            match (name.strip_suffix(".weight"), quantization) {
+                (Some(prefix), Some(q)) if is_quantized(prefix) => size * 32 / q.bits as u64,
+                _ => size,
+            }
+        })
+        .sum()
+}
+
+/// Parse a safetensors header: an 8-byte little-endian length `N` followed by
+/// `N` bytes of JSON. Returns the header and its size in bytes.
+pub(crate) fn read_safetensors_header(buffer: &[u8]) -> Result<(WeightJSON, usize)> {
+    let Some(len_bytes) = buffer.get(..HEADER_OFFSET_SAFETENSORS) else {
+        return Err(Error::SafetensorsHeaderReadError);
+    };
+    let size_header = u64::from_le_bytes(
+        len_bytes
+            .try_into()
+            .map_err(|_| Error::SafetensorsHeaderReadError)?,
+    );
+    let header_end = usize::try_from(size_header)
+        .ok()
+        .and_then(|size| size.checked_add(HEADER_OFFSET_SAFETENSORS))
+        .filter(|end| *end <= buffer.len())
+        .ok_or(Error::SafetensorsHeaderReadError)?;
+
+    let json: WeightJSON = serde_json::from_slice(&buffer[HEADER_OFFSET_SAFETENSORS..header_end])?;
+    Ok((json, header_end - HEADER_OFFSET_SAFETENSORS))
 }
 
 fn read_safetensors_weights(
@@ -121,7 +244,7 @@ fn read_safetensors_weights(
             ));
         }
 
-        if offset_end > mmap.len() {
+        if offset_start > offset_end || offset_end > mmap.len() {
             return Err(Error::SafetensorsOutOfBounds {
                 tensor: name.clone(),
                 start: offset_start,
@@ -157,7 +280,11 @@ fn read_safetensors_weights(
     Ok(weights)
 }
 
-fn load_weights(files: &Vec<String>, callback: Option<PromptStreamCallback>) -> Result<Weight> {
+pub(crate) fn load_weights(
+    files: &Vec<String>,
+    index: Option<&ModelIndexJSON>,
+    callback: Option<PromptStreamCallback>,
+) -> Result<Weight> {
     let mut list: Vec<Weight> = Vec::new();
     // Preallocate buffer once, reuse it for each file
     let mut total_expected_tensors: usize = 0;
@@ -171,6 +298,14 @@ fn load_weights(files: &Vec<String>, callback: Option<PromptStreamCallback>) ->
         // Read header into buffer
         let (weight_json, header_size) = read_safetensors_header(&mmap)?;
 
+        if let Some(index) = index {
+            let shard = Path::new(file_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            index.validate_shard(&shard, &weight_json)?;
+        }
+
         total_expected_tensors += weight_json.tensors.len();
 
         let metadata_format = weight_json.metadata.as_ref().and_then(|m| m.format.clone());
@@ -242,6 +377,37 @@ fn find_model(model_path: &str, regex_files: &str) -> Result<Vec<String>> {
     Ok(result)
 }
 
+fn read_model_index(model_path: &str) -> Result<Option<ModelIndexJSON>> {
+    let index_path = Path::new(model_path).join(INDEX_FILE_SAFETENSORS);
+    if !index_path.is_file() {
+        return Ok(None);
+    }
+    let file = File::open(&index_path)
+        .map_err(|_| Error::FileOpenError(index_path.display().to_string()))?;
+    let index: ModelIndexJSON = serde_json::from_reader(file)?;
+    Ok(Some(index))
+}
+
+fn find_index_files(model_path: &str, index: &ModelIndexJSON) -> Result<Vec<String>> {
+    let files: Vec<String> = index
+        .shard_names()
+        .iter()
+        .map(|shard| Path::new(model_path).join(shard))
+        .map(|path| {
+            if path.is_file() {
+                Ok(path.display().to_string())
+            } else {
+                Err(Error::ModelWeightPathNotFound(path.display().to_string()))
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    if files.is_empty() {
+        return Err(Error::NoTensorInModelFile);
+    }
+    Ok(files)
+}
+
 fn find_model_files(model_path: &str) -> Result<Vec<String>> {
     match find_model(model_path, &"model*.safetensors") {
         Ok(files) => Ok(files),
@@ -251,3 +417,93 @@ fn find_model_files(model_path: &str) -> Result<Vec<String>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safetensors_header_bytes(header: &str, data_len: usize) -> Vec<u8> {
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend(std::iter::repeat_n(0u8, data_len));
+        bytes
+    }
+
+    #[test]
+    fn test_count_shapes_parameters() {
+        let shapes: HashMap<String, Vec<i32>> = [
+            ("layers.0.mlp.up_proj.weight", vec![64, 16]),
+            ("layers.0.mlp.up_proj.scales", vec![64, 2]),
+            ("layers.0.mlp.up_proj.biases", vec![64, 2]),
+            ("layers.0.input_layernorm.weight", vec![64]),
+            ("layers.0.self_attn.q_proj.bias", vec![64]),
+        ]
+        .into_iter()
+        .map(|(name, shape)| (name.to_string(), shape))
+        .collect();
+        let quantization = ModelQuantization {
+            bits: 4,
+            group_size: 64,
+        };
+        assert_eq!(
+            count_shapes_parameters(&shapes, Some(quantization)),
+            64 * 128 + 64 + 64
+        );
+    }
+
+    #[test]
+    fn test_read_small_safetensors_header() {
+        let header = r#"{"__metadata__":{"format":"pt"},"embed.weight":{"dtype":"F32","shape":[2,2],"data_offsets":[0,16]}}  "#;
+        let bytes = safetensors_header_bytes(header, 16);
+
+        let (json, size) = read_safetensors_header(&bytes).unwrap();
+        assert_eq!(size, header.len());
+        assert_eq!(json.metadata.unwrap().format.as_deref(), Some("pt"));
+        assert_eq!(json.tensors["embed.weight"].data_offsets, [0, 16]);
+    }
+
+    #[test]
+    fn test_read_truncated_safetensors_header() {
+        let header = r#"{"embed.weight":{"dtype":"F32","shape":[1],"data_offsets":[0,4]}}"#;
+        let mut bytes = safetensors_header_bytes(header, 0);
+        bytes.truncate(bytes.len() - 1);
+
+        assert!(matches!(
+            read_safetensors_header(&bytes),
+            Err(Error::SafetensorsHeaderReadError)
+        ));
+        assert!(matches!(
+            read_safetensors_header(&[1, 0, 0]),
+            Err(Error::SafetensorsHeaderReadError)
+        ));
+    }
+
+    #[test]
+    fn test_index_validate_shard() {
+        let index: ModelIndexJSON = serde_json::from_str(
+            r#"{"metadata":{"total_size":32},"weight_map":{
+                "a.weight":"model-00001-of-00002.safetensors",
+                "b.weight":"model-00002-of-00002.safetensors"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            index.shard_names(),
+            vec![
+                "model-00001-of-00002.safetensors",
+                "model-00002-of-00002.safetensors"
+            ]
+        );
+
+        let header = r#"{"a.weight":{"dtype":"F32","shape":[1],"data_offsets":[0,4]}}"#;
+        let (shard_1, _) = read_safetensors_header(&safetensors_header_bytes(header, 4)).unwrap();
+        assert!(
+            index
+                .validate_shard("model-00001-of-00002.safetensors", &shard_1)
+                .is_ok()
+        );
+        assert!(matches!(
+            index.validate_shard("model-00002-of-00002.safetensors", &shard_1),
+            Err(Error::SafetensorsIndexMismatch { .. })
+        ));
+    }
+}
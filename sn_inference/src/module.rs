@@ -1,10 +1,12 @@
 use crate::cache::k_v_cache::k_v_cache::ArcCacheItem;
 use crate::error::Result;
+use crate::lora::lora_adapter::LoraLayer;
 use crate::mask::mask::AttentionMask;
 use crate::model::weight::Tensor;
 use crate::quantized::Quantize;
 use mlx_rs::Array;
 use std::any::Any;
+use std::sync::Arc;
 
 pub trait Module: Any + Quantize {
     fn forward(
@@ -15,4 +17,7 @@ pub trait Module: Any + Quantize {
     ) -> Result<Array>;
 
     fn set_weight(&mut self, name: &str, sub_name: &str, tensor: &Tensor) -> Result<()>;
+
+    /// Select the LoRA deltas applied by this module, `None` to run the base weights only.
+    fn set_lora(&mut self, _lora: Option<Arc<LoraLayer>>) {}
 }
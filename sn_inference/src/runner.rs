@@ -1,26 +1,54 @@
-use crate::cache::k_v_cache::k_v_cache::ArcCacheList;
+use crate::cache::k_v_cache::k_v_cache::{ArcCacheList, CacheSize};
 use crate::cache::k_v_cache::k_v_cache_session::KvCacheSession;
+use crate::convert::convert::convert_model;
 use crate::error::{Error, Result};
 use crate::factory::k_v_cache::create_cache_from_model_runtime;
+use crate::lora::lora_adapter::LoraAdapter;
+use crate::memory::memory_manager::{MemoryConfig, MemoryManager, estimate_model_bytes};
+use crate::model::driver::{
+    DEFAULT_DRIVER_MODEL_NAME, DRIVER_CONFIG_FILE, Driver, DriverConfig, ENV_DRIVER_MODEL,
+};
+use crate::model::hf_cache::{
+    default_alias, find_cached_model, hf_hub_cache_path, import_snapshot,
+};
+use crate::model::model_registry::{ModelRegistry, REGISTRY_FILE};
 use crate::model::model_runtime::{GenerateTextResult, ModelRuntime};
 use crate::token::token_stream_manager::PromptStreamCallback;
+use crate::utils::mlx::clear_cache::clear_cache;
+use crate::utils::mlx::reset_peak_memory::reset_peak_memory;
 use mlx_rs::Array;
+use sn_core::server::payload::backend::adapter_response::AdapterResponse;
+use sn_core::server::payload::backend::convert_model_response::ConvertModelResponse;
+use sn_core::server::payload::backend::driver_response::DriverResponse;
+use sn_core::server::payload::backend::import_model_response::ImportModelResponse;
+use sn_core::types::completion_prompt::CompletionPrompt;
+use sn_core::types::continuation_score::ContinuationScore;
 use sn_core::types::conversation::Conversation;
+use sn_core::types::embedding_options::EmbeddingOptions;
+use sn_core::types::fim_prompt::FimPrompt;
+use sn_core::types::generate_text_options::GenerateTextOptions;
+use sn_core::types::model_info::ModelInfo;
+use sn_core::types::model_quantization::ModelQuantization;
 use sn_core::utils::rw_lock::RwLockExt;
 use std::ops::Add;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 const BASE_PATH_DEFAULT: &str = "~/.sanaga";
-//TODO: should be configurable
-const DEFAULT_DRIVER_MODEL_NAME: &str = "models--Qwen--Qwen3-1.7B-MLX-4bit";
 
 #[derive(Debug)]
 pub struct Runner {
-    pub driver: Option<Arc<RwLock<ModelRuntime>>>,
+    pub driver: Option<Driver>,
+    pub driver_config: DriverConfig,
+    /// Embedding dimension of the previous driver when the current one differs.
+    pub previous_embedding_dim: Option<usize>,
     pub models: Arc<RwLock<Vec<Arc<ModelRuntime>>>>,
     pub session_caches: Arc<RwLock<Vec<KvCacheSession>>>,
+    pub registry: Arc<RwLock<ModelRegistry>>,
+    pub memory: Arc<RwLock<MemoryManager>>,
+    /// Held while a model loads, so loads run one at a time.
+    loading: Arc<RwLock<()>>,
 }
 
 fn expand_tilde(path: &str) -> String {
@@ -49,48 +77,178 @@ fn get_base_path_driver() -> String {
     get_base_path().add("/driver/")
 }
 
+fn get_driver_config_path() -> PathBuf {
+    Path::new(&get_base_path()).join(DRIVER_CONFIG_FILE)
+}
+
 fn get_base_path_models() -> String {
     get_base_path().add("/models/")
 }
 
+/// Model names are directories right under the models path, anything that could
+/// resolve elsewhere is refused.
+fn check_model_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") || name.starts_with('.')
+    {
+        return Err(Error::InvalidModelName(name.to_string()));
+    }
+    Ok(())
+}
+
+fn get_base_path_adapters() -> String {
+    get_base_path().add("/adapters/")
+}
+
+fn adapter_response(adapter: &LoraAdapter) -> AdapterResponse {
+    AdapterResponse {
+        name: adapter.name.clone(),
+        path: adapter.path.clone(),
+        rank: adapter.rank,
+        scale: adapter.scale,
+        num_layers: adapter.num_layers(),
+    }
+}
+
 fn create_cache(model_runtime: Arc<ModelRuntime>) -> Result<ArcCacheList> {
     Ok(create_cache_from_model_runtime(model_runtime)?)
 }
 
 impl Runner {
     pub fn new() -> Result<Self> {
-        let driver_id = String::from("driver");
-        let driver_path = get_base_path_driver().add(DEFAULT_DRIVER_MODEL_NAME);
-        let driver = match ModelRuntime::load_with_path(&driver_path, &driver_id, None) {
-            Ok(driver) => Some(Arc::new(RwLock::new(driver))),
+        let mut runner = Runner {
+            driver: None,
+            driver_config: DriverConfig::load(&get_driver_config_path())?,
+            previous_embedding_dim: None,
+            models: Arc::new(RwLock::new(Vec::new())),
+            session_caches: Arc::new(RwLock::new(Vec::new())),
+            registry: Arc::new(RwLock::new(ModelRegistry::load(
+                Path::new(&get_base_path()).join(REGISTRY_FILE).as_path(),
+            )?)),
+            memory: Arc::new(RwLock::new(MemoryManager::new(MemoryConfig::from_env()))),
+            loading: Arc::new(RwLock::new(())),
+        };
+
+        let driver_name = std::env::var(ENV_DRIVER_MODEL)
+            .ok()
+            .or_else(|| runner.driver_config.name.clone())
+            .unwrap_or_else(|| DEFAULT_DRIVER_MODEL_NAME.to_string());
+        match runner.load_driver(&driver_name) {
+            Ok(driver) => {
+                runner.set_driver(driver)?;
+            }
             Err(e) => {
                 error!(
-                    "Failed to load default driver model from path {}: {}",
-                    driver_path, e
+                    "Failed to load driver model {}, embeddings are unavailable: {}",
+                    driver_name, e
                 );
-                None
             }
         };
-        Ok(Runner {
-            driver,
-            models: Arc::new(RwLock::new(Vec::new())),
-            session_caches: Arc::new(RwLock::new(Vec::new())),
+        Ok(runner)
+    }
+
+    /// Load a driver without replacing the current one, so requests keep being served
+    /// while it loads. `name` is looked up in the driver directory, then in the
+    /// installed models.
+    pub fn load_driver(&self, name: &str) -> Result<Driver> {
+        let driver_path = get_base_path_driver().add(name);
+        let path = if Path::new(&driver_path).exists() {
+            driver_path
+        } else {
+            self.find_model(name)?.path
+        };
+        Driver::load(name, &path)
+    }
+
+    /// Replace the driver with `driver` and remember it for the next start.
+    pub fn set_driver(&mut self, driver: Driver) -> Result<DriverResponse> {
+        self.previous_embedding_dim = self
+            .driver_config
+            .embedding_dim
+            .filter(|dim| *dim != driver.embedding_dim);
+        if let Some(previous) = self.previous_embedding_dim {
+            warn!(
+                "Embedding dimension changed from {} to {} with driver {}",
+                previous, driver.embedding_dim, driver.name
+            );
+        }
+        self.driver_config = DriverConfig {
+            name: Some(driver.name.clone()),
+            embedding_dim: Some(driver.embedding_dim),
+        };
+        self.driver_config.save(&get_driver_config_path())?;
+        let driver_bytes = driver
+            .model_runtime
+            .read_lock("reading driver memory")?
+            .get_model_bytes()?;
+        self.memory
+            .write_lock_mut("registering driver memory")?
+            .set_driver_bytes(driver_bytes);
+        if self.driver.replace(driver).is_some() {
+            clear_cache()?;
+        }
+        self.get_driver().ok_or(Error::DriverNotLoaded)
+    }
+
+    pub fn get_driver(&self) -> Option<DriverResponse> {
+        self.driver.as_ref().map(|driver| DriverResponse {
+            name: driver.name.clone(),
+            path: driver.path.clone(),
+            embedding_dim: driver.embedding_dim,
+            previous_embedding_dim: self.previous_embedding_dim,
         })
     }
 
-    fn generate_path_id(salt: &String) -> String {
-        let id = hex::encode(salt.as_bytes());
-        String::from(&id[(id.len() - 10)..])
+    /// Installed models with their metadata, registering the ones added since the last call.
+    pub fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut registry = self.registry.write_lock_mut("listing models")?;
+        registry.sync(Path::new(&get_base_path_models()))?;
+        Ok(registry.models.clone())
+    }
+
+    /// Find an installed model by alias or id.
+    fn find_model(&self, name: &str) -> Result<ModelInfo> {
+        let mut registry = self.registry.write_lock_mut("finding model")?;
+        if registry.find(name).is_none() {
+            registry.sync(Path::new(&get_base_path_models()))?;
+        }
+        registry
+            .find(name)
+            .cloned()
+            .ok_or_else(|| Error::ModelNotInstalled(name.to_string()))
     }
+
     pub fn load_model_name(
         &self,
         name: &str,
+        quantization: Option<ModelQuantization>,
         callback: Option<PromptStreamCallback>,
     ) -> Result<String> {
-        let path = get_base_path_models().add(name);
-        let id = Self::generate_path_id(&path);
+        if let Some(q) = quantization
+            && !q.is_supported()
+        {
+            return Err(Error::UnsupportedQuantization(q.to_string()));
+        }
+
+        let model_info = self.find_model(name)?;
+        let id = model_info.id.clone();
 
+        // Two requests for the same unloaded model would otherwise both load it and
+        // overrun the memory budget, the second one finds it loaded by the first.
+        let _loading = self.loading.write_lock_mut("loading model")?;
         if let Some(model_runtime) = self.get_model_by_id(&id) {
+            let quantized_on_load = model_runtime.config.model.quantization().is_none();
+            if quantization.is_some()
+                && quantized_on_load
+                && quantization != model_runtime.quantization
+            {
+                return Err(Error::ModelRunningWithOtherQuantization(
+                    model_runtime.name.clone(),
+                    model_runtime
+                        .quantization
+                        .map(|q| q.to_string())
+                        .unwrap_or_else(|| "none".to_string()),
+                ));
+            }
             info!(
                 "Model {} already loaded in container {}",
                 model_runtime.name, model_runtime.id
@@ -98,12 +256,23 @@ impl Runner {
             return Ok(id);
         }
 
-        let mut model_runtime = ModelRuntime::load_with_path(path.as_str(), &id, callback)?;
-        let _ = &model_runtime.routine_model()?;
+        self.unload_idle_models()?;
+        let quantized_on_load = quantization.filter(|_| model_info.quantization.is_none());
+        self.make_room(
+            &model_info.alias,
+            estimate_model_bytes(&model_info.path, quantized_on_load),
+        )?;
+
+        let mut model_runtime =
+            ModelRuntime::load_with_path(&model_info.path, &id, &model_info.alias, callback)?;
+        let _ = &model_runtime.routine_model(quantization)?;
         info!(
             "Model {} loaded in container {}",
             model_runtime.name, model_runtime.id
         );
+        self.memory
+            .write_lock_mut("registering model memory")?
+            .register(&id, model_runtime.get_model_bytes()?, quantization);
         {
             let context = "adding model to container";
             let mut guard = self.models.write_lock_mut(context)?;
@@ -112,6 +281,96 @@ impl Runner {
         Ok(id)
     }
 
+    /// Unload least recently used models until `model_bytes` more fit in the memory
+    /// budget. Models in the middle of a request are kept.
+    fn make_room(&self, name: &str, model_bytes: u64) -> Result<()> {
+        let pinned = self.models_in_use()?;
+        let evicted =
+            self.memory
+                .read_lock("make_room")?
+                .plan_eviction(name, model_bytes, &pinned)?;
+        for model_id in &evicted {
+            info!(
+                "Unloading model {} to stay within the memory budget",
+                model_id
+            );
+            self.remove_model(model_id)?;
+        }
+        if !evicted.is_empty() {
+            clear_cache()?;
+        }
+        Ok(())
+    }
+
+    /// Unload the models unused for longer than the idle TTL, except the ones in the
+    /// middle of a request that outlasted it.
+    pub fn unload_idle_models(&self) -> Result<()> {
+        let in_use = self.models_in_use()?;
+        let idle: Vec<String> = self
+            .memory
+            .read_lock("unload_idle_models")?
+            .idle_models()
+            .into_iter()
+            .filter(|model_id| !in_use.contains(model_id))
+            .collect();
+        for model_id in &idle {
+            info!("Unloading idle model {}", model_id);
+            self.remove_model(model_id)?;
+        }
+        if !idle.is_empty() {
+            clear_cache()?;
+        }
+        Ok(())
+    }
+
+    /// Ids of the running models held by a request besides the runner.
+    fn models_in_use(&self) -> Result<Vec<String>> {
+        Ok(self
+            .models
+            .read_lock("models_in_use")?
+            .iter()
+            .filter(|model| Arc::strong_count(model) > 1)
+            .map(|model| model.id.clone())
+            .collect())
+    }
+
+    /// Drop a running model with its session caches.
+    fn remove_model(&self, model_id: &str) -> Result<()> {
+        self.models
+            .write_lock_mut("remove_model")?
+            .retain(|model| model.id != model_id);
+        self.session_caches
+            .write_lock_mut("remove_model")?
+            .retain(|session| session.model_id != model_id);
+        self.memory.write_lock_mut("remove_model")?.remove(model_id);
+        Ok(())
+    }
+
+    /// The running model with `model_id`, loaded again if it was unloaded.
+    fn get_or_load_model(&self, model_id: &str) -> Result<Arc<ModelRuntime>> {
+        if let Some(model_runtime) = self.get_model_by_id(model_id) {
+            self.memory
+                .write_lock_mut("get_or_load_model")?
+                .touch(model_id);
+            return Ok(model_runtime);
+        }
+        let quantization = self
+            .memory
+            .read_lock("get_or_load_model")?
+            .unloaded_quantization(model_id)
+            .flatten();
+        info!("Loading model {} on demand", model_id);
+        match self.load_model_name(model_id, quantization, None) {
+            Ok(_) => {}
+            Err(Error::ModelNotInstalled(_)) => {
+                return Err(Error::ModelRuntimeNotFoundWithId(model_id.to_string()));
+            }
+            Err(e) => return Err(e),
+        }
+        self.get_model_by_id(model_id)
+            .ok_or_else(|| Error::ModelRuntimeNotFoundWithId(model_id.to_string()))
+    }
+
     fn get_model_by_id(&self, model_id: &str) -> Option<Arc<ModelRuntime>> {
         let context = "get_model_by_id";
         let result = self.models.read_lock(context);
@@ -124,31 +383,28 @@ impl Runner {
         }
     }
     pub fn unload_model(&mut self, model_id: &str) {
-        let result = self.models.read_lock("unload_model");
-        match result {
-            Ok(guard_models) => {
-                guard_models
-                    .iter()
-                    .position(|model| model.id == model_id)
-                    .map(|index| {
-                        info!("Unloading model: {}", guard_models[index].name);
-                        let guard = self.models.write_lock("unload_model");
-                        match guard {
-                            Ok(mut models) => {
-                                models.remove(index);
-                            }
-                            Err(_) => {
-                                error!("Failed to acquire write lock for unloading model");
-                            }
-                        }
-                    });
-            }
-            Err(_) => {
-                error!("Failed to acquire read lock for unloading model");
-            }
+        info!("Unloading model: {}", model_id);
+        if let Err(e) = self.remove_model(model_id).and_then(|_| clear_cache()) {
+            error!("Failed to unload model {}: {}", model_id, e);
         }
     }
 
+    /// Charge `model_id` with the memory of its KV caches: the session caches, kept
+    /// between requests, or the cache of the last run when it was larger.
+    fn record_cache_memory(&self, model_id: &str, cache: &ArcCacheList) -> Result<()> {
+        let sessions_bytes: usize = self
+            .session_caches
+            .read_lock("record_cache_memory")?
+            .iter()
+            .filter(|session| session.model_id == model_id)
+            .map(|session| session.cache.cache_size())
+            .sum();
+        self.memory
+            .write_lock_mut("record_cache_memory")?
+            .record_kv_bytes(model_id, sessions_bytes.max(cache.cache_size()) as u64);
+        Ok(())
+    }
+
     pub fn get_session_cache(
         &self,
         session_id: Option<i32>,
@@ -165,7 +421,10 @@ impl Runner {
                 let guard = self
                     .session_caches
                     .read_lock("check existing session cache")?;
-                if let Some(existing) = guard.iter().find(|c| c.session_id == id) {
+                if let Some(existing) = guard
+                    .iter()
+                    .find(|c| c.session_id == id && c.model_id == model_id)
+                {
                     return Ok(existing.cache.clone());
                 }
             }
@@ -174,6 +433,7 @@ impl Runner {
             let new_cache = create_cache(model.clone())?;
             let new_session = KvCacheSession {
                 session_id: id,
+                model_id: model_id.to_string(),
                 cache: new_cache.clone(),
             };
             self.session_caches
@@ -191,64 +451,238 @@ impl Runner {
         &self,
         model_id: &str,
         conversation: &Conversation,
+        options: &GenerateTextOptions,
         session_id: Option<i32>,
+        adapter: Option<&str>,
         callback: Option<PromptStreamCallback>,
     ) -> Result<GenerateTextResult> {
-        if let Some(model_runtime) = self.get_model_by_id(model_id) {
-            let cache = self.get_session_cache(session_id, model_id)?;
-            model_runtime.generate_text(conversation, cache, callback)
-        } else {
-            Err(Error::ModelRuntimeNotFoundWithId(model_id.to_string()))
-        }
+        let model_runtime = self.get_or_load_model(model_id)?;
+        let cache = self.get_session_cache(session_id, model_id)?;
+        // The peak memory in the stats of the run starts from here.
+        reset_peak_memory()?;
+        let result =
+            model_runtime.generate_text(conversation, options, cache.clone(), adapter, callback)?;
+        self.record_cache_memory(model_id, &cache)?;
+        Ok(result)
     }
 
-    pub fn generate_embeddings(&self, inputs: &Vec<String>) -> Result<Array> {
-        if let Some(model_runtime) = &self.driver {
-            let model_runtime = model_runtime.read_lock("generate_embeddings")?;
-            Ok(model_runtime.generate_embeddings(inputs)?)
-        } else {
-            Err(Error::ModelRuntimeNotFoundWithId(String::from("driver")))
-        }
+    /// Generate from a raw prompt, text or token ids, without applying the chat template.
+    /// With `ignore_eos` the generation runs to `max_tokens`, e.g. for benchmarks.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_completion(
+        &self,
+        model_id: &str,
+        prompt: &CompletionPrompt,
+        add_special_tokens: bool,
+        max_tokens: Option<usize>,
+        ignore_eos: bool,
+        session_id: Option<i32>,
+        adapter: Option<&str>,
+        callback: Option<PromptStreamCallback>,
+    ) -> Result<GenerateTextResult> {
+        let model_runtime = self.get_or_load_model(model_id)?;
+        let cache = self.get_session_cache(session_id, model_id)?;
+        reset_peak_memory()?;
+        let result = model_runtime.generate_completion(
+            prompt,
+            add_special_tokens,
+            max_tokens,
+            ignore_eos,
+            cache.clone(),
+            adapter,
+            callback,
+        )?;
+        self.record_cache_memory(model_id, &cache)?;
+        Ok(result)
+    }
+
+    /// Fill in the middle of `prompt` with the FIM tokens of a code model.
+    pub fn generate_fim(
+        &self,
+        model_id: &str,
+        prompt: &FimPrompt,
+        session_id: Option<i32>,
+        adapter: Option<&str>,
+        callback: Option<PromptStreamCallback>,
+    ) -> Result<GenerateTextResult> {
+        let model_runtime = self.get_or_load_model(model_id)?;
+        let cache = self.get_session_cache(session_id, model_id)?;
+        reset_peak_memory()?;
+        let result = model_runtime.generate_fim(prompt, cache.clone(), adapter, callback)?;
+        self.record_cache_memory(model_id, &cache)?;
+        Ok(result)
+    }
+
+    /// Token ids of `text` with the tokenizer of the model, e.g. to build prompts of a
+    /// given length.
+    pub fn tokenize(
+        &self,
+        model_id: &str,
+        text: &str,
+        add_special_tokens: bool,
+    ) -> Result<Vec<u32>> {
+        let model_runtime = self.get_or_load_model(model_id)?;
+        model_runtime.tokenize(text, add_special_tokens)
+    }
+
+    /// Log-likelihood of each continuation given `context`, for multiple-choice
+    /// evaluation, perplexity or reranking of candidate answers.
+    pub fn score(
+        &self,
+        model_id: &str,
+        context: &str,
+        continuations: &[String],
+        adapter: Option<&str>,
+    ) -> Result<Vec<ContinuationScore>> {
+        let model_runtime = self.get_or_load_model(model_id)?;
+        model_runtime.score(context, continuations, adapter)
+    }
+
+    /// Load the adapter installed in `adapters/{name}` on top of a running model.
+    pub fn load_adapter(&self, model_id: &str, name: &str) -> Result<AdapterResponse> {
+        let model_runtime = self
+            .get_model_by_id(model_id)
+            .ok_or_else(|| Error::ModelRuntimeNotFoundWithId(model_id.to_string()))?;
+        let path = get_base_path_adapters().add(name);
+        let adapter = model_runtime.load_adapter(name, &path)?;
+        info!(
+            "Adapter {} loaded on model {}",
+            adapter.name, model_runtime.name
+        );
+        Ok(adapter_response(&adapter))
+    }
+
+    pub fn unload_adapter(&self, model_id: &str, name: &str) -> Result<()> {
+        let model_runtime = self
+            .get_model_by_id(model_id)
+            .ok_or_else(|| Error::ModelRuntimeNotFoundWithId(model_id.to_string()))?;
+        model_runtime.unload_adapter(name)?;
+        info!(
+            "Adapter {} unloaded from model {}",
+            name, model_runtime.name
+        );
+        Ok(())
+    }
+
+    pub fn list_adapters(&self, model_id: &str) -> Result<Vec<AdapterResponse>> {
+        let model_runtime = self
+            .get_model_by_id(model_id)
+            .ok_or_else(|| Error::ModelRuntimeNotFoundWithId(model_id.to_string()))?;
+        Ok(model_runtime
+            .list_adapters()?
+            .iter()
+            .map(|adapter| adapter_response(adapter))
+            .collect())
+    }
+
+    pub fn generate_embeddings(
+        &self,
+        inputs: &Vec<String>,
+        options: &EmbeddingOptions,
+    ) -> Result<Array> {
+        let driver = self.driver.as_ref().ok_or(Error::DriverNotLoaded)?;
+        let model_runtime = driver.model_runtime.read_lock("generate_embeddings")?;
+        model_runtime.generate_embeddings(inputs, options)
     }
 
     pub fn generate_similarity(
         &self,
         queries: &Vec<String>,
         documents: &Vec<String>,
+        options: &EmbeddingOptions,
     ) -> Result<Array> {
-        if let Some(model_runtime) = &self.driver {
-            let model_runtime = model_runtime.read_lock("generate_similarity")?;
-            Ok(model_runtime.generate_similarity(queries, documents)?)
-        } else {
-            Err(Error::ModelRuntimeNotFoundWithId(String::from("driver")))
-        }
+        let driver = self.driver.as_ref().ok_or(Error::DriverNotLoaded)?;
+        let model_runtime = driver.model_runtime.read_lock("generate_similarity")?;
+        model_runtime.generate_similarity(queries, documents, options)
     }
 
-    pub fn scan_model_installed(&self) -> Result<Vec<String>> {
-        let path_models = get_base_path_models();
-        let paths = match std::fs::read_dir(&path_models) {
-            Ok(paths) => paths,
+    /// Quantize an installed full precision model into a new model directory next to it.
+    /// The output name defaults to `{name}-{bits}bit`.
+    pub fn convert_model(
+        &self,
+        name: &str,
+        output_name: Option<&str>,
+        quantization: ModelQuantization,
+    ) -> Result<ConvertModelResponse> {
+        if !quantization.is_supported() {
+            return Err(Error::UnsupportedQuantization(quantization.to_string()));
+        }
+
+        check_model_name(name)?;
+        let src_root = get_base_path_models().add(name);
+        let src_path = ModelRuntime::find_model_path_from_root(&src_root)?;
+        let output_name = output_name
+            .map(String::from)
+            .unwrap_or_else(|| format!("{}-{}bit", name, quantization.bits));
+        check_model_name(&output_name)?;
+        let dst_path = get_base_path_models().add(&output_name);
+        // Creating the directory here claims it, so only our own output is removed on
+        // failure.
+        match std::fs::create_dir(&dst_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                return Err(Error::ModelAlreadyExists(dst_path));
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let num_shards = match convert_model(&src_path, &dst_path, quantization) {
+            Ok(num_shards) => num_shards,
             Err(e) => {
-                error!("Failed to read directory: {}", path_models);
-                return Err(Error::IOError(e));
+                let _ = std::fs::remove_dir_all(&dst_path);
+                return Err(e);
             }
         };
-        let list = paths
-            .map(|res| res.map(|dir| dir.path()))
-            .map(|path| path.ok())
-            .map(|path| {
-                if let Some(path) = path {
-                    if let Some(name) = path.file_name() {
-                        return String::from(name.display().to_string());
-                    }
-                }
-                String::default()
-            })
-            .collect::<Vec<_>>();
+        Ok(ConvertModelResponse {
+            model_name: output_name,
+            path: dst_path,
+            quantization,
+            num_shards,
+        })
+    }
+
+    /// Install a model from the local Hugging Face hub cache under `alias`, linking its
+    /// blobs instead of copying them. The alias defaults to the repository name.
+    pub fn import_model(
+        &self,
+        repo_id: &str,
+        alias: Option<&str>,
+        revision: Option<&str>,
+    ) -> Result<ImportModelResponse> {
+        let cached_model = find_cached_model(&hf_hub_cache_path(), repo_id)?;
+        let alias = alias
+            .map(String::from)
+            .unwrap_or_else(|| default_alias(&cached_model.repo_id));
+        check_model_name(&alias)?;
 
-        Ok(list)
+        let snapshot_path = cached_model.snapshot_path(revision)?;
+        let dst_path = get_base_path_models().add(&alias);
+        let num_files = import_snapshot(&snapshot_path, Path::new(&dst_path))?;
+        Ok(ImportModelResponse {
+            model_name: alias,
+            path: dst_path,
+            snapshot_path: snapshot_path.display().to_string(),
+            num_files,
+        })
     }
 }
 
 unsafe impl Sync for Runner {}
 unsafe impl Send for Runner {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_model_name() {
+        assert!(check_model_name("Qwen3-1.7B-4bit").is_ok());
+        for name in ["", ".hidden", "../../x", "a/b", "a\\b", "a..b"] {
+            assert!(
+                matches!(check_model_name(name), Err(Error::InvalidModelName(_))),
+                "{}",
+                name
+            );
+        }
+    }
+}
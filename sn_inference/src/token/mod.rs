@@ -1,4 +1,7 @@
+pub(crate) mod token_embedding_batcher;
 pub(crate) mod token_embedding_generator;
 pub(crate) mod token_generated_info;
 pub(crate) mod token_generator;
+pub(crate) mod token_reasoning_parser;
+pub(crate) mod token_scorer;
 pub(crate) mod token_stream_manager;
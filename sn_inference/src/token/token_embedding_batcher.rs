@@ -0,0 +1,136 @@
+use tokenizers::{Encoding, TruncationDirection};
+
+/// Tokens a micro-batch may hold once padded, its size times its longest input.
+pub const DEFAULT_EMBEDDING_BATCH_TOKENS: usize = 8192;
+
+/// Splits the inputs of an embedding request into micro-batches of similar length so
+/// padding stays small and one long document does not size the whole batch.
+pub struct TokenEmbeddingBatcher {
+    max_length: Option<usize>,
+    batch_tokens: usize,
+}
+
+impl TokenEmbeddingBatcher {
+    /// `max_length` is the smallest of the model context and the request limit, when
+    /// either is known.
+    pub fn new(max_length: Option<usize>, batch_tokens: usize) -> Self {
+        TokenEmbeddingBatcher {
+            max_length,
+            batch_tokens: batch_tokens.max(1),
+        }
+    }
+
+    /// Drop the tokens past `max_length`, the start of each input is kept. The special
+    /// tokens closing the input, like the end of sequence last-token pooling reads, are
+    /// put back after the cut.
+    pub fn truncate(&self, encodings: &mut [Encoding]) {
+        let Some(max_length) = self.max_length else {
+            return;
+        };
+        for encoding in encodings.iter_mut() {
+            if encoding.len() <= max_length {
+                continue;
+            }
+            let suffix_len = encoding
+                .get_special_tokens_mask()
+                .iter()
+                .rev()
+                .take_while(|special| **special == 1)
+                .count()
+                .min(max_length);
+            let mut suffix = encoding.clone();
+            suffix.truncate(suffix_len, 0, TruncationDirection::Left);
+            suffix.take_overflowing();
+            encoding.truncate(max_length - suffix_len, 0, TruncationDirection::Right);
+            // The cut off tokens are not embedded, they are not kept around either.
+            encoding.take_overflowing();
+            encoding.merge_with(suffix, false);
+        }
+    }
+
+    /// Indices of the inputs grouped into micro-batches, shortest inputs first. A batch
+    /// grows while its padded size fits in the token budget, an input longer than the
+    /// budget gets a batch of its own.
+    pub fn plan(&self, lengths: &[usize]) -> Vec<Vec<usize>> {
+        let mut order: Vec<usize> = (0..lengths.len()).collect();
+        order.sort_by_key(|&idx| lengths[idx]);
+
+        let mut batches: Vec<Vec<usize>> = Vec::new();
+        let mut batch: Vec<usize> = Vec::new();
+        for idx in order {
+            // Sorted by length, the input added is the longest of the batch
+            let padded = (batch.len() + 1) * lengths[idx];
+            if !batch.is_empty() && padded > self.batch_tokens {
+                batches.push(std::mem::take(&mut batch));
+            }
+            batch.push(idx);
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        batches
+    }
+}
+
+/// Position of each input in the concatenated micro-batches, to put the embeddings
+/// back in the order of the request.
+pub fn restore_order(batches: &[Vec<usize>]) -> Vec<u32> {
+    let planned: Vec<usize> = batches.iter().flatten().copied().collect();
+    let mut positions = vec![0u32; planned.len()];
+    for (position, idx) in planned.into_iter().enumerate() {
+        positions[idx] = position as u32;
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_buckets_by_length_within_budget() {
+        let batcher = TokenEmbeddingBatcher::new(None, 64);
+        let lengths = [40, 8, 100, 10, 12, 30];
+
+        let batches = batcher.plan(&lengths);
+        assert_eq!(batches, vec![vec![1, 3, 4], vec![5], vec![0], vec![2]]);
+        for batch in &batches {
+            let longest = batch.iter().map(|&idx| lengths[idx]).max().unwrap();
+            assert!(batch.len() == 1 || batch.len() * longest <= 64);
+        }
+
+        let positions = restore_order(&batches);
+        assert_eq!(positions, vec![4, 0, 5, 1, 2, 3]);
+        assert!(batcher.plan(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_truncate_keeps_closing_special_tokens() {
+        let encoding = |ids: &[u32], special: &[u32]| {
+            Encoding::new(
+                ids.to_vec(),
+                vec![0; ids.len()],
+                ids.iter().map(|id| id.to_string()).collect(),
+                vec![None; ids.len()],
+                vec![(0, 0); ids.len()],
+                special.to_vec(),
+                vec![1; ids.len()],
+                Vec::new(),
+                Default::default(),
+            )
+        };
+        let mut encodings = [
+            // `<bos> a b c d <eos>` as built by a post-processor.
+            encoding(&[1, 10, 11, 12, 13, 2], &[1, 0, 0, 0, 0, 1]),
+            encoding(&[10, 11, 12, 13, 14], &[0, 0, 0, 0, 0]),
+            encoding(&[10, 2], &[0, 1]),
+        ];
+        TokenEmbeddingBatcher::new(Some(4), 64).truncate(&mut encodings);
+
+        assert_eq!(encodings[0].get_ids(), &[1, 10, 11, 2]);
+        assert_eq!(encodings[0].get_attention_mask(), &[1, 1, 1, 1]);
+        assert!(encodings[0].get_overflowing().is_empty());
+        assert_eq!(encodings[1].get_ids(), &[10, 11, 12, 13]);
+        assert_eq!(encodings[2].get_ids(), &[10, 2]);
+    }
+}
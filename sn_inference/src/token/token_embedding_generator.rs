@@ -1,10 +1,11 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::model::model::{ForwardType, Model};
 use crate::model::model_kind::ModelKind;
 use mlx_rs::linalg::norm;
 use mlx_rs::ops::indexing::IndexOp;
 use mlx_rs::ops::stack;
-use mlx_rs::{Array, maximum};
+use mlx_rs::{Array, Dtype, maximum};
+use sn_core::types::embedding_options::{EmbeddingOptions, EmbeddingPooling};
 use sn_core::utils::rw_lock::RwLockExt;
 use std::sync::{Arc, RwLock};
 
@@ -17,7 +18,12 @@ impl TokenEmbeddingGenerator {
         TokenEmbeddingGenerator { model }
     }
 
-    pub fn generate(&self, input: &Array, attention_mask: &Array) -> Result<Array> {
+    pub fn generate(
+        &self,
+        input: &Array,
+        attention_mask: &Array,
+        options: &EmbeddingOptions,
+    ) -> Result<Array> {
         let context = "read model for forwarding";
         let token_embeddings = self.model.write_lock(context)?.forward_model(
             &input,
@@ -25,12 +31,35 @@ impl TokenEmbeddingGenerator {
             None,
             &ForwardType::Embedding,
         )?;
-        let token_embeddings = self.pooling(&token_embeddings, attention_mask)?;
-        let token_embeddings = self.normalize(&token_embeddings)?;
+        let embeddings = Self::pool(&token_embeddings, attention_mask, options)?;
+        embeddings.eval()?;
+        Ok(embeddings)
+    }
+
+    /// One f32 vector per sequence, whatever the dtype of the model (bf16 for most
+    /// quantized MLX models).
+    fn pool(
+        token_embeddings: &Array,
+        attention_mask: &Array,
+        options: &EmbeddingOptions,
+    ) -> Result<Array> {
+        let token_embeddings = match options.pooling {
+            EmbeddingPooling::Last => Self::last_pooling(token_embeddings, attention_mask)?,
+            EmbeddingPooling::Mean => Self::mean_pooling(token_embeddings, attention_mask)?,
+            EmbeddingPooling::Cls => token_embeddings.index((.., 0, ..)),
+        };
+        let token_embeddings = match options.dimensions {
+            Some(dimensions) => Self::truncate(&token_embeddings, dimensions)?,
+            None => token_embeddings,
+        };
+        let token_embeddings = token_embeddings.as_dtype(Dtype::Float32)?;
+        if options.normalize {
+            return Self::normalize(&token_embeddings);
+        }
         Ok(token_embeddings)
     }
 
-    fn pooling(&self, hidden_states: &Array, attention_mask: &Array) -> Result<Array> {
+    fn last_pooling(hidden_states: &Array, attention_mask: &Array) -> Result<Array> {
         // Compute the index of the last valid token for each sequence
         let sequence_lengths = attention_mask.sum_axis(-1, true)? - 1;
         let batch_size = hidden_states.shape()[0];
@@ -49,10 +78,67 @@ impl TokenEmbeddingGenerator {
         Ok(stack(&list)?)
     }
 
-    fn normalize(&self, hidden_states: &Array) -> Result<Array> {
+    fn mean_pooling(hidden_states: &Array, attention_mask: &Array) -> Result<Array> {
+        // Padding tokens are masked out of both the sum and the count
+        let mask = attention_mask
+            .as_dtype(hidden_states.dtype())?
+            .expand_dims(-1)?;
+        let sum = (hidden_states * &mask).sum_axis(1, false)?;
+        let count = mask.sum_axis(1, false)?;
+        Ok(sum / maximum!(count, Array::from_f32(1e-9_f32))?)
+    }
+
+    /// Matryoshka models put the most information in the first dimensions, the vector
+    /// is cut before normalising.
+    fn truncate(embeddings: &Array, dimensions: usize) -> Result<Array> {
+        let dim = embeddings.shape().last().copied().unwrap_or_default() as usize;
+        if dimensions == 0 || dimensions > dim {
+            return Err(Error::InvalidEmbeddingDimensions(dimensions, dim));
+        }
+        Ok(embeddings.index((.., ..dimensions as i32)))
+    }
+
+    fn normalize(hidden_states: &Array) -> Result<Array> {
         let norm = norm(hidden_states, 2.0, &[-1], true)?;
-        let embeddings = hidden_states / maximum!(norm, Array::from_f32(1e-9_f32))?;
-        embeddings.eval()?;
-        Ok(embeddings)
+        Ok(hidden_states / maximum!(norm, Array::from_f32(1e-9_f32))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_without_normalize_returns_f32() {
+        let hidden_states =
+            Array::from_slice(&[1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0, 0.0, 0.0], &[2, 2, 2])
+                .as_dtype(Dtype::Bfloat16)
+                .unwrap();
+        let attention_mask = Array::from_slice(&[1_i64, 1, 1, 0], &[2, 2]);
+        for pooling in [EmbeddingPooling::Last, EmbeddingPooling::Cls] {
+            let options = EmbeddingOptions {
+                pooling,
+                normalize: false,
+                ..EmbeddingOptions::default()
+            };
+            let embeddings =
+                TokenEmbeddingGenerator::pool(&hidden_states, &attention_mask, &options).unwrap();
+            assert_eq!(embeddings.dtype(), Dtype::Float32);
+            let expected = match pooling {
+                EmbeddingPooling::Last => [3.0, 4.0, 5.0, 6.0],
+                _ => [1.0, 2.0, 5.0, 6.0],
+            };
+            assert_eq!(embeddings.as_slice::<f32>(), expected);
+        }
+
+        let options = EmbeddingOptions {
+            normalize: false,
+            dimensions: Some(1),
+            ..EmbeddingOptions::default()
+        };
+        let embeddings =
+            TokenEmbeddingGenerator::pool(&hidden_states, &attention_mask, &options).unwrap();
+        assert_eq!(embeddings.dtype(), Dtype::Float32);
+        assert_eq!(embeddings.as_slice::<f32>(), [3.0, 5.0]);
     }
 }
@@ -1,4 +1,5 @@
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraAdapter;
 use crate::model::model::{ForwardType, Model};
 use crate::model::model_kind::ModelKind;
 use crate::token::token_generated_info::TokenGeneratedInfo;
@@ -58,6 +59,7 @@ pub struct TokenGeneratorOpts {
 
 pub struct TokenGenerator {
     model: Arc<RwLock<ModelKind>>,
+    adapter: Option<Arc<LoraAdapter>>,
     pub cache: ArcCacheList,
     sampler: SamplerFn,
     logits_processors: Vec<LogitsProcessor>,
@@ -68,6 +70,7 @@ pub struct TokenGenerator {
     stop: bool,
     options: TokenGeneratorOpts,
     token_sender: Option<Sender<TokenGeneratedInfo>>,
+    pub prompt_tokens: usize,
     pub total_generated_tokens: usize,
     pub prefill_duration: f64,
     pub generation_duration: f64,
@@ -76,6 +79,7 @@ pub struct TokenGenerator {
 impl TokenGenerator {
     pub fn new(
         model: Arc<RwLock<ModelKind>>,
+        adapter: Option<Arc<LoraAdapter>>,
         prompt: Vec<u32>,
         eot_ids: HashSet<u32>,
         cache: ArcCacheList,
@@ -90,6 +94,7 @@ impl TokenGenerator {
             token_sender,
             cache,
             model: model.clone(),
+            adapter,
             max_tokens,
             logits_processors: Vec::new(),
             tokens: None,
@@ -98,19 +103,32 @@ impl TokenGenerator {
             eot_ids,
             stop: false,
             options: TokenGeneratorOpts::default(),
+            prompt_tokens: prompt_len,
             total_generated_tokens: 0,
             generation_duration: 0.0,
             prefill_duration: 0.0,
         })
     }
 
+    /// Stop after `max_tokens` generated tokens, no limit but the end of turn otherwise.
+    pub fn with_max_tokens(mut self, max_tokens: Option<usize>) -> TokenGenerator {
+        if let Some(max_tokens) = max_tokens {
+            self.max_tokens = max_tokens;
+        }
+        self
+    }
+
     fn model_call(
         &mut self,
         input_prompt: &Array,
         _: Option<&Array>, // input_embeddings
     ) -> Result<Array> {
         let context = "TokenGenerator:model_call";
-        let result = self.model.write_lock(context)?.forward_model(
+        let mut model = self.model.write_lock(context)?;
+        // The model is shared between requests, select this request's adapter under
+        // the same lock as the forward.
+        model.set_adapter(self.adapter.clone());
+        let result = model.forward_model(
             &input_prompt,
             None,
             Option::from(self.cache.clone()),
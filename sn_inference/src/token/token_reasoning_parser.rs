@@ -0,0 +1,158 @@
+pub(crate) static REASONING_START: &str = "<think>";
+static REASONING_END: &str = "</think>";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReasoningChunk {
+    Reasoning(String),
+    Content(String),
+}
+
+/// Splits the decoded tokens of a generation between the reasoning, written between
+/// `<think>` and `</think>`, and the answer, as they stream. A tag can be decoded over
+/// several tokens, text that may be the start of one is held until the next push.
+#[derive(Debug, Default)]
+pub struct TokenReasoningParser {
+    in_reasoning: bool,
+    pending: String,
+    /// Drop the line breaks following a tag.
+    trim_start: bool,
+    reasoning: String,
+    content: String,
+}
+
+impl TokenReasoningParser {
+    /// `in_reasoning` when the prompt already opened the reasoning, the model then only
+    /// writes the closing tag.
+    pub fn new(in_reasoning: bool) -> TokenReasoningParser {
+        TokenReasoningParser {
+            in_reasoning,
+            trim_start: in_reasoning,
+            ..TokenReasoningParser::default()
+        }
+    }
+
+    /// Whether the rendered prompt ends inside a reasoning span.
+    pub fn prompt_opens_reasoning(prompt: &str) -> bool {
+        prompt.trim_end().ends_with(REASONING_START)
+    }
+
+    pub fn push(&mut self, text: &str) -> Vec<ReasoningChunk> {
+        self.pending.push_str(text);
+        let mut chunks = Vec::new();
+        loop {
+            let tag = if self.in_reasoning {
+                REASONING_END
+            } else {
+                REASONING_START
+            };
+            match self.pending.find(tag) {
+                Some(idx) => {
+                    let text: String = self.pending.drain(..idx + tag.len()).collect();
+                    self.emit(&text[..idx], &mut chunks);
+                    self.in_reasoning = !self.in_reasoning;
+                    self.trim_start = true;
+                }
+                None => {
+                    let keep = partial_tag_len(&self.pending, tag);
+                    let text: String = self.pending.drain(..self.pending.len() - keep).collect();
+                    self.emit(&text, &mut chunks);
+                    return chunks;
+                }
+            }
+        }
+    }
+
+    /// Flush the text held back at the end of the generation.
+    pub fn finish(&mut self) -> Vec<ReasoningChunk> {
+        let text = std::mem::take(&mut self.pending);
+        let mut chunks = Vec::new();
+        self.emit(&text, &mut chunks);
+        chunks
+    }
+
+    /// The answer and the reasoning, when there was one.
+    pub fn into_parts(self) -> (String, Option<String>) {
+        let reasoning = self.reasoning.trim().to_string();
+        (self.content, (!reasoning.is_empty()).then_some(reasoning))
+    }
+
+    fn emit(&mut self, text: &str, chunks: &mut Vec<ReasoningChunk>) {
+        let text = if self.trim_start {
+            text.trim_start_matches(['\n', '\r'])
+        } else {
+            text
+        };
+        if text.is_empty() {
+            return;
+        }
+        self.trim_start = false;
+        if self.in_reasoning {
+            self.reasoning.push_str(text);
+            chunks.push(ReasoningChunk::Reasoning(text.to_string()));
+        } else {
+            self.content.push_str(text);
+            chunks.push(ReasoningChunk::Content(text.to_string()));
+        }
+    }
+}
+
+/// Length of the longest end of `text` that starts `tag`.
+pub(crate) fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len().min(text.len() + 1))
+        .rev()
+        .find(|len| {
+            text.is_char_boundary(text.len() - len) && tag.starts_with(&text[text.len() - len..])
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_reasoning_while_streaming() {
+        let mut parser = TokenReasoningParser::new(false);
+        let mut chunks = Vec::new();
+        for text in [
+            "<th",
+            "ink>\nThe user",
+            " greets.</",
+            "think>\n\n",
+            "Hello",
+            "!",
+        ] {
+            chunks.extend(parser.push(text));
+        }
+        chunks.extend(parser.finish());
+        assert_eq!(
+            chunks,
+            vec![
+                ReasoningChunk::Reasoning("The user".to_string()),
+                ReasoningChunk::Reasoning(" greets.".to_string()),
+                ReasoningChunk::Content("Hello".to_string()),
+                ReasoningChunk::Content("!".to_string()),
+            ]
+        );
+        assert_eq!(
+            parser.into_parts(),
+            ("Hello!".to_string(), Some("The user greets.".to_string()))
+        );
+
+        assert!(TokenReasoningParser::prompt_opens_reasoning(
+            "<|im_start|>assistant\n<think>\n"
+        ));
+        let mut parser = TokenReasoningParser::new(true);
+        parser.push("Short.</think>Hi <");
+        parser.finish();
+        assert_eq!(
+            parser.into_parts(),
+            ("Hi <".to_string(), Some("Short.".to_string()))
+        );
+
+        let mut parser = TokenReasoningParser::new(false);
+        parser.push("No reasoning.");
+        parser.finish();
+        assert_eq!(parser.into_parts(), ("No reasoning.".to_string(), None));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::error::Result;
+use crate::lora::lora_adapter::LoraAdapter;
+use crate::model::model::{ForwardType, Model};
+use crate::model::model_kind::ModelKind;
+use mlx_rs::ops::indexing::{IndexOp, argmax_axis};
+use mlx_rs::{Array, Dtype};
+use sn_core::utils::rw_lock::RwLockExt;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenScores {
+    /// Log-probability of each token of the sequence after the first.
+    pub logprobs: Vec<f32>,
+    /// Whether each of these tokens was the most likely one.
+    pub greedy: Vec<bool>,
+}
+
+/// Reads the log-likelihood of given tokens from the logits of a single forward pass,
+/// nothing is sampled.
+pub struct TokenScorer {
+    model: Arc<RwLock<ModelKind>>,
+    adapter: Option<Arc<LoraAdapter>>,
+}
+
+impl TokenScorer {
+    pub fn new(model: Arc<RwLock<ModelKind>>, adapter: Option<Arc<LoraAdapter>>) -> Self {
+        TokenScorer { model, adapter }
+    }
+
+    /// Score the tokens of each sequence given the ones before it. The sequences are
+    /// padded to the same length on the right, where the causal mask keeps the padding
+    /// from being attended to.
+    pub fn score(&self, sequences: &[Vec<u32>], pad_id: u32) -> Result<Vec<TokenScores>> {
+        let max_len = sequences.iter().map(Vec::len).max().unwrap_or_default();
+        if max_len < 2 {
+            return Ok(vec![TokenScores::default(); sequences.len()]);
+        }
+        let mut ids = Vec::with_capacity(sequences.len() * max_len);
+        for sequence in sequences {
+            ids.extend_from_slice(sequence);
+            ids.resize(ids.len() + max_len - sequence.len(), pad_id);
+        }
+        let len = max_len as i32;
+        let inputs = Array::from_slice(&ids, &[sequences.len() as i32, len]);
+
+        let logits = {
+            let context = "TokenScorer:score";
+            let mut model = self.model.write_lock(context)?;
+            model.set_adapter(self.adapter.clone());
+            model.forward_model(&inputs, None, None, &ForwardType::Logits)?
+        };
+        // The logits at a position are for the token following it.
+        let logits = logits.index((.., ..len - 1, ..)).as_dtype(Dtype::Float32)?;
+        let logprobs = &logits - logits.logsumexp_axis(-1, true)?;
+        let targets = inputs.index((.., 1..));
+        let target_logprobs = logprobs
+            .take_along_axis(targets.expand_dims(-1)?, -1)?
+            .squeeze_axes(&[-1])?;
+        let greedy = argmax_axis(&logits, -1, false)?.eq(&targets)?;
+        target_logprobs.eval()?;
+        greedy.eval()?;
+
+        let target_logprobs = target_logprobs.as_slice::<f32>();
+        let greedy = greedy.as_slice::<bool>();
+        let row_len = max_len - 1;
+        Ok(sequences
+            .iter()
+            .enumerate()
+            .map(|(row, sequence)| {
+                let start = row * row_len;
+                let end = start + sequence.len().saturating_sub(1);
+                TokenScores {
+                    logprobs: target_logprobs[start..end].to_vec(),
+                    greedy: greedy[start..end].to_vec(),
+                }
+            })
+            .collect())
+    }
+}
@@ -1,11 +1,14 @@
 use crate::cache::k_v_cache::k_v_cache::{ArcCacheList, CacheSize};
+use crate::chat_template::tool_call_parser::ToolCallStreamFilter;
 use crate::error::{Error, Result};
+use crate::lora::lora_adapter::LoraAdapter;
 use crate::model::model_kind::ModelKind;
 use crate::token::token_generated_info::TokenGeneratedInfo;
 use crate::token::token_generator::TokenGenerator;
+use crate::token::token_reasoning_parser::{ReasoningChunk, TokenReasoningParser};
 use crate::tokenizer::tokenizer::Tokenizer;
-use crossbeam::channel::{bounded, Receiver, Sender};
-use rayon::prelude::*;
+use crate::utils::mlx::get_peak_memory::get_peak_memory;
+use crossbeam::channel::{Receiver, Sender, bounded};
 use sn_core::server::payload::backend::run_model_metadata_response_sse::RunModelMetadataResponseSSE;
 use sn_core::server::payload::backend::text_generated_metadata_response_sse::{
     IntoMessageStat, TextGeneratedMetadataResponseSSE,
@@ -13,6 +16,7 @@ use sn_core::server::payload::backend::text_generated_metadata_response_sse::{
 use sn_core::types::message_stats::{MessageStats, MessageStatsBuilder};
 use sn_core::types::stream_data::{StreamData, StreamDataContent};
 use sn_core::utils::rw_lock::RwLockExt;
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 use std::thread;
@@ -23,33 +27,98 @@ pub type PromptStreamCallback = Arc<Sender<StreamData>>;
 pub struct TokenStreamManager {
     tokenizer: Rc<Tokenizer>,
     model: Arc<RwLock<ModelKind>>,
+    adapter: Option<Arc<LoraAdapter>>,
     pub token_generator: Option<Arc<RwLock<TokenGenerator>>>,
     stop: bool,
     responses: Vec<TokenGeneratedInfo>,
     token_receiver: Option<Receiver<TokenGeneratedInfo>>,
+    /// Reasoning is only told apart from the answer for chat prompts, raw completions
+    /// are returned as generated.
+    reasoning_parser: Option<TokenReasoningParser>,
+    /// Set when tools were offered, their calls are sent parsed once the generation ends.
+    tool_call_filter: Option<ToolCallStreamFilter>,
+    /// Tokens ending the generation besides the end of turn of the model.
+    stop_ids: HashSet<u32>,
+    /// Only `max_tokens` ends the generation, used to benchmark a fixed length.
+    ignore_eos: bool,
+    max_tokens: Option<usize>,
 }
 
 impl TokenStreamManager {
-    pub fn new(model: Arc<RwLock<ModelKind>>, tokenizer: Rc<Tokenizer>) -> TokenStreamManager {
+    pub fn new(
+        model: Arc<RwLock<ModelKind>>,
+        adapter: Option<Arc<LoraAdapter>>,
+        tokenizer: Rc<Tokenizer>,
+    ) -> TokenStreamManager {
         TokenStreamManager {
             model,
+            adapter,
             tokenizer,
             token_generator: None,
             stop: false,
             responses: Vec::new(),
             token_receiver: None,
+            reasoning_parser: None,
+            tool_call_filter: None,
+            stop_ids: HashSet::new(),
+            ignore_eos: false,
+            max_tokens: None,
         }
     }
 
+    pub fn with_stop_ids(mut self, stop_ids: HashSet<u32>) -> TokenStreamManager {
+        self.stop_ids = stop_ids;
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: Option<usize>) -> TokenStreamManager {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_ignore_eos(mut self, ignore_eos: bool) -> TokenStreamManager {
+        self.ignore_eos = ignore_eos;
+        self
+    }
+
+    fn eot_ids(&self) -> HashSet<u32> {
+        if self.ignore_eos {
+            return HashSet::new();
+        }
+        let mut eot_ids = self.tokenizer.eot_ids();
+        eot_ids.extend(&self.stop_ids);
+        eot_ids
+    }
+
+    /// The prompt opened the reasoning, the generation starts inside it.
+    pub fn with_reasoning_started(mut self, in_reasoning: bool) -> TokenStreamManager {
+        self.reasoning_parser = Some(TokenReasoningParser::new(in_reasoning));
+        self
+    }
+
+    /// Hold the tool calls back from the streamed answer.
+    pub fn with_tool_calls(mut self, tools_offered: bool) -> TokenStreamManager {
+        self.tool_call_filter = tools_offered.then(ToolCallStreamFilter::default);
+        self
+    }
+
     fn prelude_generate_text(&mut self, prompt: Vec<u32>, cache: ArcCacheList) -> Result<()> {
-        let eot_ids = &self.tokenizer.eot_ids();
+        let eot_ids = &self.eot_ids();
         let model = self.model.clone();
 
         let (tx, rx): (Sender<TokenGeneratedInfo>, Receiver<TokenGeneratedInfo>) = bounded(100);
         self.token_receiver = Some(rx);
 
         // Create TokenGenerator on main thread to avoid error
-        let tg = TokenGenerator::new(model, prompt, eot_ids.clone(), cache, Some(tx))?;
+        let tg = TokenGenerator::new(
+            model,
+            self.adapter.clone(),
+            prompt,
+            eot_ids.clone(),
+            cache,
+            Some(tx),
+        )?
+        .with_max_tokens(self.max_tokens);
 
         // Set token_generator so it can be used later
         let tg_arc = Arc::new(RwLock::new(tg));
@@ -67,22 +136,40 @@ impl TokenStreamManager {
         Ok(())
     }
 
-    pub fn get_text(&self) -> String {
-        self.responses
-            .par_iter()
-            .map(|gti| gti.text.clone())
-            .collect::<Vec<String>>()
-            .join("")
+    fn send_reasoning_chunks(
+        chunks: Vec<ReasoningChunk>,
+        mut tool_call_filter: Option<&mut ToolCallStreamFilter>,
+        callback: &Option<PromptStreamCallback>,
+    ) {
+        let Some(cb) = callback else {
+            return;
+        };
+        for chunk in chunks {
+            let _ = match chunk {
+                ReasoningChunk::Reasoning(text) => cb.send(StreamData::for_reasoning(text)),
+                ReasoningChunk::Content(text) => {
+                    let text = match tool_call_filter.as_deref_mut() {
+                        Some(filter) => filter.push(&text),
+                        None => text,
+                    };
+                    if text.is_empty() {
+                        continue;
+                    }
+                    cb.send(StreamData::for_string(text))
+                }
+            };
+        }
     }
 
+    /// Generate the answer, returned with the reasoning written before it.
     pub fn generate_text(
         &mut self,
         prompt: Vec<u32>,
         cache: ArcCacheList,
         callback: Option<PromptStreamCallback>,
-    ) -> Result<String> {
+    ) -> Result<(String, Option<String>)> {
         self.prelude_generate_text(prompt, cache.clone())?;
-        let eot_ids = self.tokenizer.eot_ids();
+        let eot_ids = self.eot_ids();
         let header_token_ids = self.tokenizer.header_token_ids();
 
         if let Some(_generator) = &mut self.token_generator {
@@ -115,9 +202,18 @@ impl TokenStreamManager {
                     has_header_end,
                 );
 
-                if let Some(cb) = &callback {
-                    // Cal the callback with the decoded response
-                    let _ = cb.send(StreamData::for_string(gti.text.clone()));
+                // Call the callback with the decoded response, reasoning apart
+                match &mut self.reasoning_parser {
+                    Some(parser) => Self::send_reasoning_chunks(
+                        parser.push(&gti.text),
+                        self.tool_call_filter.as_mut(),
+                        &callback,
+                    ),
+                    None => {
+                        if let Some(cb) = &callback {
+                            let _ = cb.send(StreamData::for_string(gti.text.clone()));
+                        }
+                    }
                 }
 
                 if let Err(e) = gti.end(None) {
@@ -134,7 +230,25 @@ impl TokenStreamManager {
             return Err(Error::TokenGenerationStartFailure);
         }
         debug!("cache size: {}", cache.cache_size());
-        Ok(self.get_text())
+        match self.reasoning_parser.take() {
+            Some(mut parser) => {
+                let mut tool_call_filter = self.tool_call_filter.take();
+                Self::send_reasoning_chunks(parser.finish(), tool_call_filter.as_mut(), &callback);
+                // What was held back and is not a tool call.
+                if let (Some(filter), Some(cb)) = (&mut tool_call_filter, &callback) {
+                    let rest = filter.finish();
+                    if !rest.is_empty() {
+                        let _ = cb.send(StreamData::for_string(rest));
+                    }
+                }
+                Ok(parser.into_parts())
+            }
+            None => Ok((self.get_text(), None)),
+        }
+    }
+
+    fn get_text(&self) -> String {
+        self.responses.iter().map(|gti| gti.text.as_str()).collect()
     }
 
     pub fn get_average_stats(
@@ -155,10 +269,21 @@ impl TokenStreamManager {
                 let context = "reading prefill_duration from token_generator";
                 token_generator.read_lock(context)?.prefill_duration
             };
+            let (prompt_tokens, cache_bytes) = {
+                let context = "reading prompt_tokens and cache from token_generator";
+                let token_generator = token_generator.read_lock(context)?;
+                (
+                    token_generator.prompt_tokens,
+                    token_generator.cache.cache_size(),
+                )
+            };
             let stats = MessageStatsBuilder::new()
                 .with_total_generated_tokens(total_generated_tokens as f64)
                 .with_generation_duration(generation_duration)
                 .with_prefill_duration(prefill_duration)
+                .with_prompt_tokens(prompt_tokens)
+                .with_peak_memory(get_peak_memory()? as u64)
+                .with_cache_bytes(cache_bytes as u64)
                 .build();
             if let Some(cb) = &callback {
                 let _ = cb.send(StreamData::for_text_generated_metadata_sse_response(
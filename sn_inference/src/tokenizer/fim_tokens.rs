@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+/// Prefix, suffix and middle tokens of the fill-in-the-middle formats we know of:
+/// Qwen2.5-Coder, StarCoder, DeepSeek-Coder and CodeLlama.
+static FIM_TOKEN_SETS: [[&str; 3]; 4] = [
+    ["<|fim_prefix|>", "<|fim_suffix|>", "<|fim_middle|>"],
+    ["<fim_prefix>", "<fim_suffix>", "<fim_middle>"],
+    ["<｜fim▁begin｜>", "<｜fim▁hole｜>", "<｜fim▁end｜>"],
+    ["▁<PRE>", "▁<SUF>", "▁<MID>"],
+];
+
+/// Tokens ending an infill besides the end of sequence of the model, code models often
+/// go on with the next file otherwise.
+static FIM_STOP_TOKENS: [&str; 6] = [
+    "<|endoftext|>",
+    "<|fim_pad|>",
+    "<|file_sep|>",
+    "<EOT>",
+    "▁<EOT>",
+    "<｜end▁of▁sentence｜>",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FimTokens {
+    pub prefix: u32,
+    pub suffix: u32,
+    pub middle: u32,
+    pub stop_ids: HashSet<u32>,
+}
+
+impl FimTokens {
+    /// Find the FIM tokens among the added tokens of a tokenizer.
+    pub fn find<'a>(added_tokens: impl IntoIterator<Item = (u32, &'a str)>) -> Option<FimTokens> {
+        let added_tokens: Vec<(u32, &str)> = added_tokens.into_iter().collect();
+        let id_of = |content: &str| {
+            added_tokens
+                .iter()
+                .find(|(_, token)| *token == content)
+                .map(|(id, _)| *id)
+        };
+        FIM_TOKEN_SETS.iter().find_map(|[prefix, suffix, middle]| {
+            let (prefix, suffix, middle) = (id_of(prefix)?, id_of(suffix)?, id_of(middle)?);
+            let mut stop_ids: HashSet<u32> = FIM_STOP_TOKENS
+                .iter()
+                .filter_map(|token| id_of(token))
+                .collect();
+            stop_ids.extend([prefix, suffix, middle]);
+            Some(FimTokens {
+                prefix,
+                suffix,
+                middle,
+                stop_ids,
+            })
+        })
+    }
+
+    /// Prompt in prefix-suffix-middle order, the `middle` hint is where the infill
+    /// starts from.
+    pub fn prompt_ids(&self, prefix: &[u32], suffix: &[u32], middle: &[u32]) -> Vec<u32> {
+        let mut ids = Vec::with_capacity(prefix.len() + suffix.len() + middle.len() + 3);
+        ids.push(self.prefix);
+        ids.extend_from_slice(prefix);
+        ids.push(self.suffix);
+        ids.extend_from_slice(suffix);
+        ids.push(self.middle);
+        ids.extend_from_slice(middle);
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_fim_tokens() {
+        let added_tokens = [
+            (151643, "<|endoftext|>"),
+            (151644, "<|im_start|>"),
+            (151659, "<|fim_prefix|>"),
+            (151660, "<|fim_middle|>"),
+            (151661, "<|fim_suffix|>"),
+            (151664, "<|file_sep|>"),
+        ];
+        let fim = FimTokens::find(added_tokens).unwrap();
+        assert_eq!(
+            (fim.prefix, fim.suffix, fim.middle),
+            (151659, 151661, 151660)
+        );
+        assert_eq!(
+            fim.stop_ids,
+            HashSet::from([151643, 151664, 151659, 151660, 151661])
+        );
+        assert_eq!(
+            fim.prompt_ids(&[1, 2], &[3], &[4]),
+            vec![151659, 1, 2, 151661, 3, 151660, 4]
+        );
+
+        assert!(FimTokens::find([(151644, "<|im_start|>")]).is_none());
+    }
+}
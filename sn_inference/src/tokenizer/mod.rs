@@ -1 +1,2 @@
+pub(crate) mod fim_tokens;
 pub(crate) mod tokenizer;
@@ -1,10 +1,15 @@
 use crate::config::config::Config;
 use crate::config::config_model::ConfigModel;
 use crate::error::{Error, Result};
+use crate::model::gguf::reader::open_gguf;
+use crate::model::gguf::tokenizer::tokenizer_json;
 use crate::token::token_generated_info::TokenGeneratedInfo;
+use crate::tokenizer::fim_tokens::FimTokens;
 use rayon::prelude::*;
 use std::collections::HashSet;
+use std::path::Path;
 use std::rc::Rc;
+use std::str::FromStr;
 use tokenizers::tokenizer::Tokenizer as HugTokenizer;
 use tokenizers::{EncodeInput, Encoding, PaddingParams};
 use tracing::debug;
@@ -36,8 +41,17 @@ fn add_padding_params(config: &Rc<Config>, tool: &mut HugTokenizer) -> Result<()
 
 impl Tokenizer {
     pub fn new(config: Rc<Config>) -> Result<Tokenizer> {
-        debug!("loading config in {}", &config.tokenizer_path);
-        let mut tool = HugTokenizer::from_file(&config.tokenizer_path)?;
+        let mut tool = match &config.gguf_path {
+            Some(gguf_path) if !Path::new(&config.tokenizer_path).is_file() => {
+                debug!("loading tokenizer from GGUF metadata in {}", gguf_path);
+                let (_, header) = open_gguf(gguf_path)?;
+                HugTokenizer::from_str(&tokenizer_json(&header)?.to_string())?
+            }
+            _ => {
+                debug!("loading config in {}", &config.tokenizer_path);
+                HugTokenizer::from_file(&config.tokenizer_path)?
+            }
+        };
         add_padding_params(&config, &mut tool)?;
         Ok(Tokenizer { tool, config })
     }
@@ -103,6 +117,15 @@ impl Tokenizer {
             .collect()
     }
 
+    pub fn fim_tokens(&self) -> Option<FimTokens> {
+        FimTokens::find(
+            self.tool
+                .get_added_tokens_decoder()
+                .iter()
+                .map(|(id, ad)| (*id, ad.content.as_str())),
+        )
+    }
+
     pub fn eot_ids(&self) -> HashSet<u32> {
         match self.config.model.as_ref() {
             ConfigModel::LLaMA(config) => config
@@ -110,7 +133,14 @@ impl Tokenizer {
                 .iter()
                 .map(|i| i.clone() as u32)
                 .collect(),
+            ConfigModel::Qwen2(config) => HashSet::from([config.eos_token_id as u32]),
             ConfigModel::Qwen3(config) => HashSet::from([config.eos_token_id as u32]),
+            ConfigModel::Qwen3Moe(config) => HashSet::from([config.base.eos_token_id as u32]),
+            ConfigModel::Default(config) => config
+                .eos_token_ids()
+                .into_iter()
+                .map(|i| i as u32)
+                .collect(),
         }
     }
 }
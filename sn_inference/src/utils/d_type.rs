@@ -2,6 +2,7 @@ use mlx_rs::Dtype;
 
 pub trait DTypeExt {
     fn from_string_unsafe(type_in_str: &str) -> Dtype;
+    fn to_safetensors_str(&self) -> Option<&'static str>;
 }
 
 impl DTypeExt for Dtype {
@@ -25,4 +26,23 @@ impl DTypeExt for Dtype {
             _ => Dtype::Float32,
         }
     }
+
+    fn to_safetensors_str(&self) -> Option<&'static str> {
+        match self {
+            Dtype::Bool => Some("BOOL"),
+            Dtype::Uint8 => Some("U8"),
+            Dtype::Uint16 => Some("U16"),
+            Dtype::Uint32 => Some("U32"),
+            Dtype::Uint64 => Some("U64"),
+            Dtype::Int8 => Some("I8"),
+            Dtype::Int16 => Some("I16"),
+            Dtype::Int32 => Some("I32"),
+            Dtype::Int64 => Some("I64"),
+            Dtype::Float16 => Some("F16"),
+            Dtype::Float32 => Some("F32"),
+            Dtype::Float64 => Some("F64"),
+            Dtype::Bfloat16 => Some("BF16"),
+            _ => None,
+        }
+    }
 }
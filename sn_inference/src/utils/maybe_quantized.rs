@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::utils::mlx::gather_mm::{gather_mm, gather_qmm};
 use mlx_rs::Array;
 use mlx_rs::module::Param;
 use mlx_rs::nn::{Embedding, Linear};
@@ -40,6 +41,7 @@ macro_rules! update_biases {
 
 pub trait MaybeQuantizedLinear {
     fn update_weight(&mut self, x: &Array);
+    fn update_bias(&mut self, x: &Array);
     fn update_scales(&mut self, x: &Array);
     fn update_biases(&mut self, x: &Array);
 }
@@ -78,6 +80,14 @@ impl MaybeQuantizedLinear for MaybeQuantized<Linear> {
         update_weight!(self, x);
     }
 
+    /// Additive bias of the layer, not to be confused with the quantization `biases`.
+    fn update_bias(&mut self, x: &Array) {
+        match self {
+            MaybeQuantized::Original(o) => o.bias.value = Some(x.clone()),
+            MaybeQuantized::Quantized(q) => q.inner.bias.value = Some(x.clone()),
+        }
+    }
+
     #[allow(clippy::duplicate)]
     fn update_scales(&mut self, x: &Array) {
         update_scales!(self, x);
@@ -89,6 +99,45 @@ impl MaybeQuantizedLinear for MaybeQuantized<Linear> {
     }
 }
 
+/// Expert layers stored as a single stacked `[num_experts, output_dims, input_dims]` linear,
+/// where each token is only multiplied by the experts selected in `indices`.
+pub trait MaybeQuantizedSwitchLinear {
+    fn gather_forward(&self, x: &Array, indices: &Array, sorted_indices: bool) -> Result<Array>;
+}
+
+impl MaybeQuantizedSwitchLinear for MaybeQuantized<Linear> {
+    fn gather_forward(&self, x: &Array, indices: &Array, sorted_indices: bool) -> Result<Array> {
+        let (output, bias) = match self {
+            MaybeQuantized::Original(o) => (
+                gather_mm(
+                    x,
+                    &o.weight.value.swap_axes(-1, -2)?,
+                    indices,
+                    sorted_indices,
+                )?,
+                &o.bias.value,
+            ),
+            MaybeQuantized::Quantized(q) => (
+                gather_qmm(
+                    x,
+                    &q.inner.weight.value,
+                    &q.scales.value,
+                    &q.biases.value,
+                    indices,
+                    q.group_size,
+                    q.bits,
+                    sorted_indices,
+                )?,
+                &q.inner.bias.value,
+            ),
+        };
+        match bias {
+            Some(bias) => Ok(output + bias.take_axis(indices, 0)?.expand_dims(-2)?),
+            None => Ok(output),
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! safe_quantize {
     ($self:ident, $group_size:expr, $bits:expr, $( $field:ident ),+ $(,)?) => {
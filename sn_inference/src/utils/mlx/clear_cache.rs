@@ -0,0 +1,12 @@
+use crate::error::{Error, Result};
+use mlx_sys::mlx_clear_cache;
+
+/// Release the buffers MLX keeps around for reuse, e.g. once a model has been dropped.
+pub fn clear_cache() -> Result<()> {
+    let code = unsafe { mlx_clear_cache() };
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(Error::MemoryCacheClearFailure)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::error::{Error, Result};
+use mlx_rs::{Array, Stream};
+use mlx_sys::{mlx_array_free, mlx_array_new, mlx_gather_mm, mlx_gather_qmm};
+
+/// Matrix multiplication where each row block of `a` is multiplied by the matrix of `b`
+/// selected by `rhs_indices` (mlx-rs does not expose `gather_mm` yet).
+pub fn gather_mm(a: &Array, b: &Array, rhs_indices: &Array, sorted_indices: bool) -> Result<Array> {
+    let stream = Stream::task_local_or_default();
+    unsafe {
+        let mut result = mlx_array_new();
+        let lhs_indices = mlx_array_new();
+        let code = mlx_gather_mm(
+            &mut result,
+            a.as_ptr(),
+            b.as_ptr(),
+            lhs_indices,
+            rhs_indices.as_ptr(),
+            sorted_indices,
+            stream.as_ptr(),
+        );
+        mlx_array_free(lhs_indices);
+        if code == 0 {
+            Ok(Array::from_ptr(result))
+        } else {
+            mlx_array_free(result);
+            Err(Error::GatherMatmulFailure("gather_mm".to_string()))
+        }
+    }
+}
+
+/// Quantized counterpart of [`gather_mm`], `w` being packed with `scales` and `biases`
+/// the same way `QuantizedLinear` stores them.
+#[allow(clippy::too_many_arguments)]
+pub fn gather_qmm(
+    x: &Array,
+    w: &Array,
+    scales: &Array,
+    biases: &Array,
+    rhs_indices: &Array,
+    group_size: i32,
+    bits: i32,
+    sorted_indices: bool,
+) -> Result<Array> {
+    let stream = Stream::task_local_or_default();
+    unsafe {
+        let mut result = mlx_array_new();
+        let lhs_indices = mlx_array_new();
+        let code = mlx_gather_qmm(
+            &mut result,
+            x.as_ptr(),
+            w.as_ptr(),
+            scales.as_ptr(),
+            biases.as_ptr(),
+            lhs_indices,
+            rhs_indices.as_ptr(),
+            true,
+            group_size,
+            bits,
+            sorted_indices,
+            stream.as_ptr(),
+        );
+        mlx_array_free(lhs_indices);
+        if code == 0 {
+            Ok(Array::from_ptr(result))
+        } else {
+            mlx_array_free(result);
+            Err(Error::GatherMatmulFailure("gather_qmm".to_string()))
+        }
+    }
+}
@@ -1,4 +1,7 @@
+pub(crate) mod clear_cache;
 pub(crate) mod debug;
+pub(crate) mod gather_mm;
 pub(crate) mod get_peak_memory;
 pub(crate) mod mlx_compute_lock;
+pub(crate) mod reset_peak_memory;
 pub(crate) mod similarity;
@@ -0,0 +1,11 @@
+use crate::error::{Error, Result};
+use mlx_sys::mlx_reset_peak_memory;
+
+pub fn reset_peak_memory() -> Result<()> {
+    let code = unsafe { mlx_reset_peak_memory() };
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(Error::MemoryPeakResetFailure)
+    }
+}
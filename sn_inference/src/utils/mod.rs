@@ -2,6 +2,6 @@ pub(crate) mod d_type;
 pub(crate) mod maybe_quantized;
 pub(crate) mod mlx;
 pub(crate) mod rms_norm;
+pub(crate) mod rope;
 pub(crate) mod scaled_dot_product_attention;
-pub(crate) mod string;
 pub(crate) mod tokenizer;
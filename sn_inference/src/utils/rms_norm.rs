@@ -1,5 +1,5 @@
 use mlx_rs::Array;
-use mlx_rs::nn::RmsNorm;
+use mlx_rs::nn::{LayerNorm, RmsNorm};
 
 pub trait NormExt {
     fn update_weight(&mut self, x: &Array);
@@ -10,3 +10,9 @@ impl NormExt for RmsNorm {
         self.weight.value = x.clone();
     }
 }
+
+impl NormExt for LayerNorm {
+    fn update_weight(&mut self, x: &Array) {
+        self.weight.value = Some(x.clone());
+    }
+}
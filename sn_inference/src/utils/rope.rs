@@ -0,0 +1,251 @@
+use crate::config::config_models::rope_scaling_config::RopeScalingConfig;
+use crate::error::{Error, Result};
+use mlx_rs::ops::{arange, clip, gt, logical_and, lt, power, r#where};
+use mlx_rs::{Array, rope};
+
+const PI: f32 = std::f64::consts::PI as f32;
+
+#[derive(Clone, Debug)]
+enum RopeScaling {
+    /// Plain rope with `base`.
+    None,
+    /// Positions are divided by `factor`.
+    Linear { factor: f32 },
+    /// `base` grows with the sequence once it exceeds the trained context.
+    DynamicNtk {
+        factor: f32,
+        original_max_position_embeddings: i32,
+    },
+    /// Precomputed per-dimension frequencies (llama3, yarn) and the yarn attention factor.
+    Frequencies { freqs: Array, mscale: f32 },
+}
+
+/// Rotary position embedding shared by every model, scaled according to the
+/// `rope_scaling` section of the model config.
+#[derive(Clone, Debug)]
+pub struct Rope {
+    dims: i32,
+    traditional: bool,
+    base: f32,
+    scaling: RopeScaling,
+}
+
+impl Rope {
+    pub fn new(
+        dims: i32,
+        base: f32,
+        traditional: bool,
+        rope_scaling: Option<&RopeScalingConfig>,
+        max_position_embeddings: i32,
+    ) -> Result<Rope> {
+        let scaling = match rope_scaling {
+            None => RopeScaling::None,
+            Some(config) => {
+                let factor = config.factor.unwrap_or(1.0);
+                let original_max_position_embeddings = config
+                    .original_max_position_embeddings
+                    .unwrap_or(max_position_embeddings);
+                match config.rope_type() {
+                    "default" => RopeScaling::None,
+                    "linear" => RopeScaling::Linear { factor },
+                    "dynamic" => RopeScaling::DynamicNtk {
+                        factor,
+                        original_max_position_embeddings,
+                    },
+                    "yarn" => {
+                        Self::yarn(dims, base, factor, original_max_position_embeddings, config)?
+                    }
+                    "llama3" => match &config.rope_freqs {
+                        Some(rope_freqs) => Self::from_rope_freqs(dims, base, rope_freqs)?,
+                        None => Self::llama3(
+                            dims,
+                            base,
+                            factor,
+                            original_max_position_embeddings,
+                            config,
+                        )?,
+                    },
+                    other => {
+                        return Err(Error::InvalidConfig(format!(
+                            "unsupported rope_type: {}",
+                            other
+                        )));
+                    }
+                }
+            }
+        };
+
+        Ok(Rope {
+            dims,
+            traditional,
+            base,
+            scaling,
+        })
+    }
+
+    /// Base frequencies divided by the precomputed `rope_freqs` factors of a GGUF file.
+    fn from_rope_freqs(dims: i32, base: f32, rope_freqs: &[f32]) -> Result<RopeScaling> {
+        if rope_freqs.len() != (dims / 2) as usize {
+            return Err(Error::InvalidConfig(format!(
+                "rope_freqs holds {} factors for {} dimensions",
+                rope_freqs.len(),
+                dims
+            )));
+        }
+        let base_freqs = power(
+            &Array::from_f32(base),
+            &(arange::<_, f32>(0.0, dims as f32, 2.0)? / (dims as f32)),
+        )?;
+        Ok(RopeScaling::Frequencies {
+            freqs: &base_freqs * Array::from_slice(rope_freqs, &[dims / 2]),
+            mscale: 1.0,
+        })
+    }
+
+    fn llama3(
+        dims: i32,
+        base: f32,
+        factor: f32,
+        old_context_len: i32,
+        config: &RopeScalingConfig,
+    ) -> Result<RopeScaling> {
+        let low_freq_factor = config.low_freq_factor.unwrap_or(1.0);
+        let high_freq_factor = config.high_freq_factor.unwrap_or(4.0);
+
+        let low_freq_wavelen = old_context_len as f32 / low_freq_factor;
+        let high_freq_wavelen = old_context_len as f32 / high_freq_factor;
+
+        let base_freqs = {
+            let indices = arange::<_, f32>(0.0, dims as f32, 2.0)?;
+            let exponent = &indices / (dims as f32);
+            power(&Array::from_f32(base), &exponent)?
+        };
+        let wavelens = &Array::from_f32(2.0 * PI) * &base_freqs;
+
+        // Low frequencies are scaled by `factor`, high frequencies kept as is
+        let freqs = {
+            let mask = gt(&wavelens, &Array::from_f32(low_freq_wavelen))?;
+            let scaled = &base_freqs * factor;
+            r#where(&mask, &scaled, &base_freqs)?
+        };
+
+        // and the ones in between are smoothly interpolated
+        let is_medium_freq = logical_and(
+            &gt(&wavelens, &Array::from_f32(high_freq_wavelen))?,
+            &lt(&wavelens, &Array::from_f32(low_freq_wavelen))?,
+        )?;
+        let smooth_factors = (Array::from_f32(old_context_len as f32) / &wavelens
+            - low_freq_factor)
+            / (high_freq_factor - low_freq_factor);
+        let smooth_freqs =
+            &freqs / ((Array::from_f32(1.0) - &smooth_factors) / factor + &smooth_factors);
+
+        Ok(RopeScaling::Frequencies {
+            freqs: r#where(&is_medium_freq, &smooth_freqs, &freqs)?,
+            mscale: 1.0,
+        })
+    }
+
+    fn yarn(
+        dims: i32,
+        base: f32,
+        factor: f32,
+        original_max_position_embeddings: i32,
+        config: &RopeScalingConfig,
+    ) -> Result<RopeScaling> {
+        let beta_fast = config.beta_fast.unwrap_or(32.0);
+        let beta_slow = config.beta_slow.unwrap_or(1.0);
+
+        let correction_dim = |num_rotations: f32| {
+            (dims as f32
+                * (original_max_position_embeddings as f32 / (num_rotations * 2.0 * PI)).ln())
+                / (2.0 * base.ln())
+        };
+        let low = correction_dim(beta_fast).floor().max(0.0);
+        let mut high = correction_dim(beta_slow).ceil().min((dims - 1) as f32);
+        if low == high {
+            high += 0.001;
+        }
+
+        let get_mscale = |scale: f32, mscale: f32| {
+            if scale <= 1.0 {
+                1.0
+            } else {
+                0.1 * mscale * scale.ln() + 1.0
+            }
+        };
+        let mscale = get_mscale(factor, config.mscale.unwrap_or(1.0))
+            / get_mscale(factor, config.mscale_all_dim.unwrap_or(0.0));
+
+        // Interpolate the rotations that do not complete a full turn within the
+        // trained context, extrapolate the others
+        let freq_extra = power(
+            &Array::from_f32(base),
+            &(arange::<_, f32>(0.0, dims as f32, 2.0)? / (dims as f32)),
+        )?;
+        let freq_inter = &freq_extra * factor;
+        let ramp = clip(
+            &((arange::<_, f32>(0.0, (dims / 2) as f32, 1.0)? - low) / (high - low)),
+            (0.0, 1.0),
+        )?;
+        let freq_mask = Array::from_f32(1.0) - ramp;
+        let freqs = (&freq_inter * &freq_extra)
+            / (&freq_inter * &freq_mask + &freq_extra * (Array::from_f32(1.0) - &freq_mask));
+
+        Ok(RopeScaling::Frequencies { freqs, mscale })
+    }
+
+    pub fn forward(&self, x: &Array, offset: i32) -> Result<Array> {
+        match &self.scaling {
+            RopeScaling::None => self.rope_with_base(x, self.base, 1.0, offset),
+            RopeScaling::Linear { factor } => {
+                self.rope_with_base(x, self.base, 1.0 / factor, offset)
+            }
+            RopeScaling::DynamicNtk {
+                factor,
+                original_max_position_embeddings,
+            } => {
+                let shape = x.shape();
+                let seq_len = (offset + shape[shape.len() - 2]) as f32;
+                let max_len = *original_max_position_embeddings as f32;
+                let base = if seq_len > max_len {
+                    let dims = self.dims as f32;
+                    self.base
+                        * ((factor * seq_len / max_len) - (factor - 1.0)).powf(dims / (dims - 2.0))
+                } else {
+                    self.base
+                };
+                self.rope_with_base(x, base, 1.0, offset)
+            }
+            RopeScaling::Frequencies { freqs, mscale } => {
+                // The yarn attention factor applies to both queries and keys
+                let x = if *mscale != 1.0 {
+                    x * *mscale
+                } else {
+                    x.clone()
+                };
+                rope!(
+                    array = x,
+                    dimensions = self.dims,
+                    traditional = self.traditional,
+                    scale = 1.0,
+                    offset = offset,
+                    freqs = freqs
+                )
+                .map_err(Error::ExceptionMLX)
+            }
+        }
+    }
+
+    fn rope_with_base(&self, x: &Array, base: f32, scale: f32, offset: i32) -> Result<Array> {
+        rope!(
+            array = x,
+            dimensions = self.dims,
+            traditional = self.traditional,
+            base = base,
+            scale = scale,
+            offset = offset
+        )
+        .map_err(Error::ExceptionMLX)
+    }
+}